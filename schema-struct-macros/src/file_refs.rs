@@ -0,0 +1,378 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bundles the locations referenced by file-relative refs, such as
+/// `common.json#/$defs/Address`, into a schema. Each referenced location is
+/// copied into a subschema of the schema, named after the last segment of
+/// its pointer (or the file stem, for refs to a whole file) and qualified
+/// with the file stem if that name is taken, and the ref is rewritten to
+/// point to the new subschema. Refs within the copied locations
+/// are resolved relative to the files they come from.
+///
+/// Relative file paths are resolved against the directory of `schema_file`,
+/// or `base_dir` if the schema did not come from a file. Refs to whole files
+/// listed in `shared_files` are left as they are.
+pub fn bundle_file_refs(
+    schema: &mut Value,
+    schema_file: Option<&Path>,
+    base_dir: &Path,
+    shared_files: &[PathBuf],
+) -> Result<(), String> {
+    let defs_key = if schema.get("$defs").is_none() && schema.get("definitions").is_some() {
+        "definitions"
+    } else {
+        "$defs"
+    };
+    let existing_defs = schema
+        .get(defs_key)
+        .and_then(Value::as_object)
+        .map(|defs| defs.keys().cloned().collect())
+        .unwrap_or_default();
+    let schema_file = schema_file
+        .map(|schema_file| canonicalize(schema_file, &schema_file.to_string_lossy()))
+        .transpose()?;
+    let base_dir = schema_file
+        .as_deref()
+        .and_then(Path::parent)
+        .unwrap_or(base_dir)
+        .to_path_buf();
+
+    let mut bundler = FileRefBundler {
+        defs_key,
+        existing_defs,
+        shared_files,
+        defs: Map::new(),
+        imported: HashMap::new(),
+        files: schema_file.into_iter().collect(),
+    };
+    bundler.rewrite_refs(schema, &base_dir, None)?;
+
+    if bundler.defs.is_empty() {
+        return Ok(());
+    }
+
+    schema
+        .as_object_mut()
+        .ok_or("expected schema to be an object")?
+        .entry(defs_key)
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or(format!("expected property `{}` to be an object", defs_key))?
+        .extend(bundler.defs);
+
+    Ok(())
+}
+
+/// Canonicalizes the path of a referenced file.
+fn canonicalize(path: &Path, ref_path: &str) -> Result<PathBuf, String> {
+    fs::canonicalize(path).map_err(|e| format!("cannot resolve ref '{}': {}", ref_path, e))
+}
+
+/// State for bundling the locations referenced by file-relative refs.
+struct FileRefBundler<'a> {
+    /// The property of the schema holding subschemas.
+    defs_key: &'static str,
+    /// The names of the schema's own subschemas.
+    existing_defs: Vec<String>,
+    /// Files whose whole-file refs are left as they are.
+    shared_files: &'a [PathBuf],
+    /// The bundled subschemas.
+    defs: Map<String, Value>,
+    /// The names of the subschemas bundled so far, keyed by file and pointer.
+    imported: HashMap<(PathBuf, String), String>,
+    /// The files whose locations are currently being bundled, used to detect
+    /// circular file references.
+    files: Vec<PathBuf>,
+}
+
+impl FileRefBundler<'_> {
+    /// Recursively rewrites the file-relative refs in a value. Local refs are
+    /// resolved within `current_file`, or left as they are if the value
+    /// belongs to the schema itself.
+    fn rewrite_refs(
+        &mut self,
+        value: &mut Value,
+        base_dir: &Path,
+        current_file: Option<&Path>,
+    ) -> Result<(), String> {
+        match value {
+            Value::Object(obj) => {
+                if let Some(ref_path) = obj.get("$ref").and_then(Value::as_str) {
+                    if let Some(new_ref_path) = self.resolve(ref_path, base_dir, current_file)? {
+                        obj.insert("$ref".to_owned(), Value::String(new_ref_path));
+                    }
+                }
+
+                obj.values_mut()
+                    .try_for_each(|inner| self.rewrite_refs(inner, base_dir, current_file))
+            }
+            Value::Array(arr) => arr
+                .iter_mut()
+                .try_for_each(|inner| self.rewrite_refs(inner, base_dir, current_file)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Bundles the location referenced by a ref, returning the path of the
+    /// subschema it was bundled into. Returns `None` for refs that are left
+    /// as they are.
+    fn resolve(
+        &mut self,
+        ref_path: &str,
+        base_dir: &Path,
+        current_file: Option<&Path>,
+    ) -> Result<Option<String>, String> {
+        // Remote refs are not supported, and are reported when the schema is
+        // parsed.
+        if ref_path.contains("://") {
+            return Ok(None);
+        }
+
+        let (file, pointer) = ref_path.split_once('#').unwrap_or((ref_path, ""));
+        let file_path = match (file, current_file) {
+            ("", None) => return Ok(None),
+            ("", Some(current_file)) => current_file.to_path_buf(),
+            (file, _) => canonicalize(&base_dir.join(file), ref_path)?,
+        };
+
+        if pointer.is_empty() && self.shared_files.contains(&file_path) {
+            return Ok(None);
+        }
+
+        let key = (file_path.clone(), pointer.to_owned());
+        if let Some(name) = self.imported.get(&key) {
+            return Ok(Some(format!("#/{}/{}", self.defs_key, name)));
+        }
+
+        if Some(file_path.as_path()) != current_file && self.files.contains(&file_path) {
+            let cycle = self
+                .files
+                .iter()
+                .chain([&file_path])
+                .map(|file| {
+                    file.file_name()
+                        .map(|file_name| file_name.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+            return Err(format!("circular file reference: {}", cycle.join(" -> ")));
+        }
+
+        let document = fs::read_to_string(&file_path)
+            .map_err(|e| format!("cannot read file for ref '{}': {}", ref_path, e))
+            .and_then(|contents| {
+                serde_json::from_str::<Value>(&contents)
+                    .map_err(|e| format!("error parsing file for ref '{}': {}", ref_path, e))
+            })?;
+        let mut target = document
+            .pointer(pointer)
+            .cloned()
+            .ok_or(format!("ref '{}' could not be resolved", ref_path))?;
+
+        // Keywords identifying the referenced document do not apply to the
+        // bundled subschema.
+        if let Value::Object(obj) = &mut target {
+            obj.remove("$schema");
+            obj.remove("$id");
+        }
+
+        let stem = file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = match pointer
+            .rsplit('/')
+            .next()
+            .filter(|segment| !segment.is_empty())
+        {
+            Some(segment) => segment.replace("~1", "/").replace("~0", "~"),
+            None => stem.clone(),
+        };
+        let name = self.unique_name(name, &stem);
+
+        self.imported.insert(key, name.clone());
+        // Reserve the name until the location's own refs are bundled.
+        self.defs.insert(name.clone(), Value::Null);
+
+        let file_dir = file_path.parent().unwrap_or(base_dir).to_path_buf();
+        self.files.push(file_path.clone());
+        let result = self.rewrite_refs(&mut target, &file_dir, Some(&file_path));
+        self.files.pop();
+        result?;
+
+        self.defs.insert(name.clone(), target);
+
+        Ok(Some(format!("#/{}/{}", self.defs_key, name)))
+    }
+
+    /// Makes the name of a bundled subschema unique, qualifying a name that
+    /// is already taken with the stem of the file it comes from (e.g.
+    /// `common_Error`), followed by a number if that is taken as well.
+    fn unique_name(&self, name: String, stem: &str) -> String {
+        let is_taken =
+            |name: &String| self.existing_defs.contains(name) || self.defs.contains_key(name);
+        if !is_taken(&name) {
+            return name;
+        }
+
+        let qualified = if name == stem {
+            name
+        } else {
+            format!("{}_{}", stem, name)
+        };
+        (1..)
+            .map(|n| match n {
+                1 => qualified.clone(),
+                n => format!("{}{}", qualified, n),
+            })
+            .find(|name| !is_taken(name))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::env;
+
+    /// Creates an empty directory unique to a test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "schema-struct-file-refs-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_bundle_file_refs() {
+        let dir = test_dir("bundle");
+        fs::write(
+            dir.join("common.json"),
+            json!({
+                "$defs": {
+                    "Address": {
+                        "type": "object",
+                        "properties": {
+                            "country": { "$ref": "#/$defs/Country" }
+                        }
+                    },
+                    "Country": { "type": "string" }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "home": { "$ref": "common.json#/$defs/Address" },
+                "work": { "$ref": "./common.json#/$defs/Address" },
+                "local": { "$ref": "#/$defs/Local" }
+            },
+            "$defs": {
+                "Local": { "type": "integer" }
+            }
+        });
+        bundle_file_refs(&mut schema, None, &dir, &[]).unwrap();
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "home": { "$ref": "#/$defs/Address" },
+                    "work": { "$ref": "#/$defs/Address" },
+                    "local": { "$ref": "#/$defs/Local" }
+                },
+                "$defs": {
+                    "Local": { "type": "integer" },
+                    "Address": {
+                        "type": "object",
+                        "properties": {
+                            "country": { "$ref": "#/$defs/Country" }
+                        }
+                    },
+                    "Country": { "type": "string" }
+                }
+            })
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_colliding_file_refs() {
+        let dir = test_dir("colliding");
+        fs::write(
+            dir.join("common.json"),
+            json!({ "$defs": { "Error": { "type": "string" } } }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("billing.json"),
+            json!({ "$defs": { "Error": { "type": "integer" } } }).to_string(),
+        )
+        .unwrap();
+
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "local": { "$ref": "#/$defs/Error" },
+                "common": { "$ref": "common.json#/$defs/Error" },
+                "billing": { "$ref": "billing.json#/$defs/Error" }
+            },
+            "$defs": {
+                "Error": { "type": "boolean" }
+            }
+        });
+        bundle_file_refs(&mut schema, None, &dir, &[]).unwrap();
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "local": { "$ref": "#/$defs/Error" },
+                    "common": { "$ref": "#/$defs/common_Error" },
+                    "billing": { "$ref": "#/$defs/billing_Error" }
+                },
+                "$defs": {
+                    "Error": { "type": "boolean" },
+                    "common_Error": { "type": "string" },
+                    "billing_Error": { "type": "integer" }
+                }
+            })
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_circular_file_refs() {
+        let dir = test_dir("circular");
+        fs::write(
+            dir.join("a.json"),
+            json!({ "$defs": { "A": { "$ref": "b.json#/$defs/B" } } }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.json"),
+            json!({ "$defs": { "B": { "$ref": "a.json#/$defs/Other" } } }).to_string(),
+        )
+        .unwrap();
+
+        let mut schema = json!({ "$ref": "a.json#/$defs/A" });
+        let err = bundle_file_refs(&mut schema, None, &dir, &[]).unwrap_err();
+        assert_eq!(err, "circular file reference: a.json -> b.json -> a.json");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
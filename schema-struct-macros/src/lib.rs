@@ -4,6 +4,7 @@
 
 #![forbid(unsafe_code)]
 
+mod file_refs;
 mod parse;
 mod schema;
 mod schema_struct;
@@ -66,7 +67,17 @@ use proc_macro::TokenStream;
 /// ```
 ///
 /// Each generated item also gets `to_str` and `from_str` methods for
-/// performing serialization and deserialization.
+/// performing serialization and deserialization. A `from_string` method is
+/// also provided for deserializing from an owned `String`. Generated types
+/// also implement the standard `FromStr` and `Display` traits by delegating
+/// to these methods, so they can be used with `str::parse` and `format!`.
+/// `FromStr` reports errors as `JsonSchemaError`s, and `Display` falls back
+/// to the value's `Debug` output if it cannot be serialized. Generated
+/// structs additionally get a `wire_name` function, which maps the name of a
+/// Rust field to the JSON key it is serialized as. The top-level type also
+/// gets a `schema_version` function, which returns the `$schema` URI declared
+/// by the schema, if any, along with `TITLE` and `DESCRIPTION` constants
+/// holding the type's name and the schema description.
 ///
 /// Note that the top-level schema value must be an object.
 ///
@@ -74,10 +85,10 @@ use proc_macro::TokenStream;
 ///
 /// ### Schema
 ///
-/// A schema is always required, and can be provided in one of three ways:
-/// directly, via a file, or via a URL. The schema configuration must always
-/// be the last option passed to the macro. Any config options after it will
-/// be ignored.
+/// A schema is always required, and can be provided in one of four ways:
+/// directly, via a file, via a byte slice, or via a URL. The schema
+/// configuration must always be the last option passed to the macro. Any
+/// config options after it will be ignored.
 ///
 /// #### Direct schema
 ///
@@ -95,16 +106,92 @@ use proc_macro::TokenStream;
 ///
 /// #### Schema from a file
 ///
+/// Relative paths are resolved against the directory containing the invoking
+/// crate's `Cargo.toml`, so they do not depend on the directory Cargo is run
+/// from. Paths that do not exist there are resolved against the working
+/// directory instead, for compatibility with earlier versions.
+///
 /// ```ignore
 /// schema_struct!(file = "path/to/schema.json");
 /// ```
 ///
+/// #### Schema from bytes
+///
+/// The schema can also be given as a byte string literal or an
+/// `include_bytes!` invocation. The bytes must be valid UTF-8. As with the
+/// standard macro, paths passed to `include_bytes!` are resolved relative to
+/// the source file containing the invocation, not the crate's manifest
+/// directory as `file` paths are.
+///
+/// ```ignore
+/// schema_struct!(bytes = include_bytes!("path/to/schema.json"));
+/// ```
+///
 /// #### Schema from a URL
 ///
 /// ```ignore
 /// schema_struct!(url = "https://url.where/schema/resides.json");
 /// ```
 ///
+/// Schemas are fetched with a blocking `reqwest` client by default. To use
+/// the lighter `ureq` client instead, disable the default features and enable
+/// the `ureq` feature:
+///
+/// ```toml
+/// schema-struct = { version = "0.1", default-features = false, features = ["ureq"] }
+/// ```
+///
+/// Fetched schemas are cached on disk, keyed by URL, in the invoking crate's
+/// `OUT_DIR` if it has one, in the directory given by `CARGO_TARGET_DIR` if
+/// it is set, and otherwise in a directory private to the current user in the
+/// system's temporary directory. A cached schema is reused for a day before
+/// it is fetched again. If fetching fails, for example when building offline,
+/// an older cached copy is used instead, with a warning printed once per URL.
+/// The `cache` option, given before the URL, disables the cache and always
+/// fetches the schema.
+///
+/// ```ignore
+/// schema_struct!(
+///     cache = false,
+///     url = "https://url.where/schema/resides.json"
+/// );
+/// ```
+///
+/// #### Schemas from a directory
+///
+/// ```ignore
+/// schema_struct!(dir = "path/to/schemas/");
+/// ```
+///
+/// Every `.json` file in the directory is parsed as a separate schema, and a
+/// type is generated for each one, named after its `"title"` property or, if
+/// it has none, its file stem (e.g. `order-line.json` becomes `OrderLine`). A
+/// ref naming another file in the directory, such as `"$ref":
+/// "customer.json"`, uses the type generated for that file. All other options
+/// apply to every file, except for `ident`, `debug_file` and `output`, which
+/// cannot be combined with `dir`. When validating, values at refs to other
+/// files are not checked against those files' schemas.
+///
+/// #### Extending a schema
+///
+/// Local overrides can be applied to a schema with the `extend` option, which
+/// must come before the schema itself. The given JSON object is deep-merged
+/// onto the schema before it is parsed: objects are merged key by key, and
+/// all other values in the extension replace those in the schema.
+///
+/// ```ignore
+/// schema_struct!(
+///     extend = {
+///         "properties": {
+///             "id": {
+///                 "type": "string"
+///             }
+///         }
+///     },
+///     file = "path/to/schema.json"
+/// );
+/// ```
+///
 /// ### Visibility
 ///
 /// All generated items are private by default, but a visibility level (e.g.
@@ -133,6 +220,305 @@ use proc_macro::TokenStream;
 /// Note that if neither a custom identifier nor the `"title"` prop are
 /// available, an error will be raised.
 ///
+/// ### Required by default
+///
+/// JSON schemas treat every property as optional unless it is listed in
+/// `"required"`. The `required_by_default` option inverts this, treating
+/// every property as required unless its type allows `null`.
+///
+/// ```ignore
+/// schema_struct!(
+///     required_by_default = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// Note that this diverges from JSON schema semantics, and only affects the
+/// generated types. When combined with the `validate` option, values are
+/// still validated against the schema as written.
+///
+/// ### Ordered enums
+///
+/// The `ordered_enum` option derives `Eq`, `PartialOrd`, and `Ord` on
+/// generated enums. Variants are ordered by their declaration order in the
+/// schema, so an enum declared as `["low", "medium", "high"]` satisfies `Low
+/// < Medium < High`.
+///
+/// ```ignore
+/// schema_struct!(
+///     ordered_enum = true,
+///     schema = {
+///         ...
+///     }
+/// );
+/// ```
+///
+/// ### Extra derives
+///
+/// Generated structs and enums derive `Debug`, `Clone` and `PartialEq`, with
+/// enums also deriving `Copy`. The `derives` option lists additional traits
+/// to derive on every generated struct and enum, such as `Hash` and `Eq`, or
+/// a derive macro from another crate. Traits a type already derives are not
+/// repeated. `Default` is not derived on enums given a default by the
+/// schema, nor on structs given a `Default` implementation by
+/// `default_impl`, as these already implement it. Derives that a type cannot
+/// support, such as `Eq` on a struct with a number field or `Default` on an
+/// enum without a default, are reported by the compiler as usual.
+///
+/// ```ignore
+/// schema_struct!(
+///     derives = [Hash, Eq],
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Float precision
+///
+/// The `float_precision` option replaces the derived `Debug` implementation
+/// of structs with number fields by one that formats those fields with a
+/// fixed number of decimal places, so that debug output does not depend on
+/// how floats happen to be formatted by default. Numbers in arrays and maps,
+/// and fields referencing subschemas of these types, are formatted in the
+/// same way. Other fields, including tuples holding numbers, and structs
+/// without number fields, are formatted as usual.
+///
+/// ```ignore
+/// schema_struct!(
+///     float_precision = 6,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Enum representation
+///
+/// The `repr` option represents generated enums as a primitive integer type,
+/// such as `u8`, adding a `#[repr(...)]` attribute and explicit discriminants
+/// to every enum. This gives a compact representation suitable for FFI.
+/// Numeric enums use their values as discriminants, while string enums number
+/// their variants from zero in declaration order. Values that are not
+/// integers or do not fit in the chosen type are reported as errors. Note
+/// that derived ordering traits, as enabled by `ordered_enum`, order variants
+/// by their discriminants.
+///
+/// ```ignore
+/// schema_struct!(
+///     repr = u8,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Automatic renaming
+///
+/// Property names that are not valid snake_case Rust identifiers are renamed
+/// with a `#[serde(rename = "...")]` attribute on each field. The
+/// `auto_rename` option instead detects whether the schema's property names
+/// are predominantly camelCase or kebab-case and applies that style with a
+/// single `#[serde(rename_all = "...")]` attribute on each generated struct.
+/// Fields that do not follow the detected style are still renamed
+/// individually. This includes the structs wrapped by `oneOf` and `anyOf`
+/// enum variants, so variant payload fields follow the same casing as
+/// top-level fields.
+///
+/// ```ignore
+/// schema_struct!(
+///     auto_rename = true,
+///     schema = {
+///         ...
+///     }
+/// );
+/// ```
+///
+/// ### Rename rules
+///
+/// The `rename_all` option applies a serde rename rule to every generated
+/// struct with a single `#[serde(rename_all = "...")]` attribute, rather than
+/// detecting one as `auto_rename` does. Field identifiers remain snake_case,
+/// and fields whose names do not follow the rule are still renamed
+/// individually. The supported rules are those of serde: `lowercase`,
+/// `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`,
+/// `SCREAMING_SNAKE_CASE`, `kebab-case` and `SCREAMING-KEBAB-CASE`. The
+/// option cannot be combined with `auto_rename`.
+///
+/// ```ignore
+/// schema_struct!(
+///     rename_all = "camelCase",
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Raw identifiers
+///
+/// Properties named after Rust keywords, such as `type`, are given an
+/// underscore suffix by default (e.g. `type_`), and are renamed back to their
+/// original names during serialization. The `raw_idents` option uses raw
+/// identifiers (e.g. `r#type`) for these fields instead, except for keywords
+/// that cannot be raw identifiers, such as `self`.
+///
+/// ```ignore
+/// schema_struct!(
+///     raw_idents = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Boxed slices
+///
+/// Arrays are represented as `Vec`s by default. The `boxed_slices` option
+/// represents them as `Box<[T]>` instead, signaling that their contents are
+/// not meant to grow after deserialization.
+///
+/// ```ignore
+/// schema_struct!(
+///     boxed_slices = true,
+///     schema = {
+///         ...
+///     }
+/// );
+/// ```
+///
+/// ### Small vectors
+///
+/// With the `smallvec` feature enabled, the `vec_type` option represents
+/// arrays as `smallvec::SmallVec`s instead of `Vec`s, storing up to a given
+/// number of items inline without allocating. The option takes the form
+/// `"SmallVec<[_; N]>"`, where `N` is the inline capacity. It cannot be
+/// combined with `boxed_slices` or round-trip tests.
+///
+/// ```ignore
+/// schema_struct!(
+///     vec_type = "SmallVec<[_; 4]>",
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ```ignore
+/// schema-struct = { version = "0.1", features = ["smallvec"] }
+/// ```
+///
+/// ### Map type
+///
+/// Open objects, such as objects whose `additionalProperties` are given a
+/// schema, are represented as `HashMap`s by default. The `map_type` option
+/// selects `BTreeMap` instead, for all generated map fields, so that their
+/// keys are serialized in sorted order.
+///
+/// ```ignore
+/// schema_struct!(
+///     map_type = "BTreeMap",
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Compatible types
+///
+/// The `compat` option implements `TryFrom` for the generated struct from
+/// each of the given types, which must be serializable (e.g. other generated
+/// structs). The conversion serializes the other value and deserializes it as
+/// the generated struct, so matching fields are carried over, extra fields
+/// are ignored, and missing required fields result in an error. This is
+/// useful when converting between versions of an API.
+///
+/// ```ignore
+/// schema_struct!(
+///     compat = [ProductV2],
+///     schema = {
+///         ...
+///     }
+/// );
+/// ```
+///
+/// ### Subschema selection
+///
+/// By default, a type is generated for every subschema definition in the
+/// schema. Large schemas may define many more subschemas than are needed, in
+/// which case the `defs` option can be used to only generate the listed
+/// definitions. Any definitions referenced by the listed ones or by the root
+/// object are also generated, while all others are skipped.
+///
+/// ```ignore
+/// schema_struct!(
+///     defs = ["Money", "Address"],
+///     schema = { ... }
+/// );
+/// ```
+///
+/// Without an allowlist, the `prune_unused_defs` option can be used to skip
+/// only those definitions that are never referenced, directly or indirectly,
+/// by the root object.
+///
+/// ```ignore
+/// schema_struct!(
+///     prune_unused_defs = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Flattened refs
+///
+/// Refs are normally represented as a type alias, or a type definition for
+/// non-primitive subschemas. When the `flatten_refs` option is enabled,
+/// subschemas that are referenced exactly once and are not part of a
+/// reference cycle are instead inlined where they are referenced, as if they
+/// had been written there directly. Such fields have types named after the
+/// field rather than the subschema.
+///
+/// ```ignore
+/// schema_struct!(
+///     flatten_refs = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Custom deserialization
+///
+/// Derived `Deserialize` implementations report errors such as a type
+/// mismatch without saying which field was at fault. When the `custom_serde`
+/// option is enabled, generated structs instead get hand-written
+/// `Deserialize` implementations whose errors name the offending field and
+/// its expected schema type, e.g. ``invalid value for field `productId`,
+/// expected schema type `integer` ``. `Serialize` is still derived.
+///
+/// ```ignore
+/// schema_struct!(
+///     custom_serde = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Serde traits
+///
+/// Generated types implement both `Serialize` and `Deserialize` by default.
+/// For types that are only ever written or only ever read, the `serde` option
+/// limits the implemented traits to either `"serialize"` or `"deserialize"`.
+/// Methods that need the missing trait are omitted, so a serialize-only type
+/// has `to_str` and `to_value` but no `from_str`, `from_string` or
+/// `from_value`. Round-trip tests and `compat` conversions cannot be used
+/// with serialize-only types, and round-trip tests also cannot be used with
+/// deserialize-only types.
+///
+/// ```ignore
+/// schema_struct!(
+///     serde = "serialize",
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### External types
+///
+/// Subschemas describing types that already exist elsewhere, such as a type
+/// generated by another `schema_struct!` invocation, can be mapped to those
+/// types with the `external_types` option. Each entry maps a ref pointer to a
+/// type path. Fields referencing a mapped subschema use the given type, and
+/// no type is generated for the subschema itself. Only subschemas can be
+/// mapped, not the root object.
+///
+/// ```ignore
+/// schema_struct!(
+///     external_types = { "#/$defs/money": crate::billing::Money },
+///     schema = { ... }
+/// );
+/// ```
+///
 /// ### Type definition documentation
 ///
 /// By default, the generated type definitions will be appended to the doc
@@ -150,12 +536,129 @@ use proc_macro::TokenStream;
 /// the full and complete set of generated code, but rather a simpler, more
 /// readable representation of the code.
 ///
+/// ### Field tables
+///
+/// When the `doc_table` option is enabled, a Markdown table listing each
+/// top-level field's JSON name, Rust type, whether it is required and its
+/// description is added to the doc comment on the top-level struct, before
+/// the type definitions.
+///
+/// ```ignore
+/// schema_struct!(
+///     doc_table = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Skipping unset fields
+///
+/// Optional fields set to `None` are serialized as `null` by default. The
+/// `skip_none` option omits them from the serialized output instead,
+/// producing more compact payloads. Fields listed in `"required"` that are
+/// nullable are still serialized as `null`, since their keys must be present.
+/// Since missing fields receive their default values during deserialization,
+/// a defaulted field set to `None` is read back as its default.
+///
+/// ```ignore
+/// schema_struct!(
+///     skip_none = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Omitting empty collections
+///
+/// The `omit_empty_collections` option omits array and map fields from the
+/// serialized output when they are empty. Optional array and map fields are
+/// omitted when they are present but empty, and are still serialized as
+/// `null` when unset unless `skip_none` is also enabled. As with `skip_none`,
+/// required fields omitted this way can only be deserialized again if they
+/// have a default value.
+///
+/// ```ignore
+/// schema_struct!(
+///     omit_empty_collections = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Diffs
+///
+/// The `diff` option generates a `diff` method on the top-level type, which
+/// compares two values by their serialized JSON and returns a JSON object of
+/// the fields that differ, holding their values from the second value. Nested
+/// objects produce nested diffs, and fields missing from the second value are
+/// reported as `null`. This is useful for change detection and audit logging.
+/// The option requires `Serialize`.
+///
+/// ```ignore
+/// schema_struct!(
+///     diff = true,
+///     schema = { ... }
+/// );
+///
+/// let changes = old_value.diff(&new_value).unwrap();
+/// ```
+///
+/// ### Method names
+///
+/// The methods converting generated types to and from JSON strings are named
+/// `to_str` and `from_str` by default. Codebases that reserve these names for
+/// their own conventions can rename them with the `emit_method` and
+/// `parse_method` options. The renamed methods are generated on every type,
+/// and `from_string` calls the renamed parsing method.
+///
+/// ```ignore
+/// schema_struct!(
+///     parse_method = from_json,
+///     emit_method = to_json,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Schema draft
+///
+/// By default, the JSON schema draft is detected from the schema's `$schema`
+/// keyword. The `draft` option pins the schema to one of `"draft-04"`,
+/// `"draft-06"`, `"draft-07"`, `"2019-09"` or `"2020-12"`, which is then used
+/// both when compiling the schema and when validating values at runtime.
+/// Schemas that declare a different draft through `$schema`, or that use
+/// keywords introduced in a later draft, are rejected.
+///
+/// ```ignore
+/// schema_struct!(
+///     draft = "draft-07",
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Strict mode
+///
+/// Formats that are not recognized, such as a misspelled `"emial"`, are
+/// ignored by default, and the field is generated as if no format was given.
+/// When the `strict` option is enabled, unrecognized formats of string,
+/// integer and number fields are reported as errors instead.
+///
+/// ```ignore
+/// schema_struct!(
+///     strict = true,
+///     schema = { ... }
+/// );
+/// ```
+///
 /// ### Schema validation
 ///
 /// JSON objects are not validated against the schema when deserializing. The
 /// reason for this is that the macro is aimed more at performing compile-time
 /// validation via type-level guarantees. That said, runtime schema validation
-/// can be enabled via the `validate` option.
+/// can be enabled via the `validate` option. When enabled, any values listed
+/// in the schema's `examples` are also checked against the schema at compile
+/// time, and an invalid example results in a compile error. The top-level
+/// type also gets an `is_valid(json: &str) -> bool` function, which checks
+/// whether a JSON string both deserializes and validates without reporting
+/// why it does not. Validation runs on the JSON as given, before
+/// deserializing fills in any defaults, so a single `from_str` call both
+/// checks a payload and returns it with every default applied.
 ///
 /// ```ignore
 /// schema_struct!(
@@ -164,6 +667,20 @@ use proc_macro::TokenStream;
 /// );
 /// ```
 ///
+/// Values can also be validated against the schema before they are
+/// serialized, using the `validate_on_serialize` option. When enabled,
+/// `to_str` and `to_value` return an error if the serialized value does not
+/// conform to the schema. Schemas are compiled the first time they are used
+/// and then cached for the rest of the process, so types validating against
+/// identical schemas share a single compiled schema.
+///
+/// ```ignore
+/// schema_struct!(
+///     validate_on_serialize = true,
+///     schema = { ... }
+/// );
+/// ```
+///
 /// ### Debug information
 ///
 /// Currently, the only useful debug information the macro can provide is the
@@ -179,12 +696,89 @@ use proc_macro::TokenStream;
 /// );
 /// ```
 ///
+/// The `debug_file` option writes the same formatted code to a file instead,
+/// which is useful for snapshotting the expanded output. The file contains
+/// exactly the items emitted by the macro. Relative paths are resolved
+/// against the directory of the crate invoking the macro, and missing parent
+/// directories are created.
+///
+/// ```ignore
+/// schema_struct!(
+///     debug_file = "target/schema_struct_debug.rs",
+///     schema = { ... }
+/// );
+/// ```
+///
+/// The `output` option writes the file in the same way, and is meant for
+/// committing the generated code alongside the crate. It cannot be combined
+/// with `debug_file`. Failing to write either file results in a compile
+/// error.
+///
+/// ```ignore
+/// schema_struct!(
+///     output = "src/generated/product.rs",
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Round-trip tests
+///
+/// For additional confidence in the generated serialization code, a
+/// round-trip property test can be generated alongside the types with the
+/// `generate_tests` option. The generated `#[cfg(test)]` module uses
+/// [`proptest`](https://docs.rs/proptest) to create arbitrary instances of
+/// the top-level type and asserts that each one is equal to itself after
+/// serialization followed by deserialization.
+///
+/// ```ignore
+/// schema_struct!(
+///     generate_tests = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// The option requires the `round-trip-tests` feature, which also makes
+/// `serde_json` parse floats exactly so that they compare equal after a
+/// round trip. The generated code refers to the `proptest` and
+/// `proptest-derive` crates directly, so both must be listed in the
+/// dev-dependencies of the crate invoking the macro. The macro must also be
+/// invoked at module level, as tests cannot be declared inside function
+/// bodies.
+///
+/// Values holding non-finite floats are skipped, since they serialize to
+/// `null`. Strings with `minLength` or `maxLength` bounds are generated
+/// within those bounds. Other constraints are not taken into account, so
+/// round-trip tests cannot be combined with `validate` or
+/// `validate_on_serialize`. Self-referential schemas are not supported
+/// either, since arbitrary values of recursive types cannot be generated.
+/// Neither are strings represented by `chrono` or `uuid` types.
+///
+/// ### Crate path
+///
+/// Generated code refers to the `schema_struct` crate by the name it is given
+/// in `Cargo.toml`. When the crate is renamed or re-exported in a way that
+/// cannot be detected, the path can be set manually with the `internal_path`
+/// option:
+///
+/// ```ignore
+/// schema_struct!(
+///     internal_path = ::my_reexport::schema_struct,
+///     schema = {
+///         ...
+///     }
+/// );
+/// ```
+///
 /// ## Supported data types
 ///
 /// ### Null
 ///
 /// JSON values of type `null` are supported, and they are represented as the
-/// unit type `()`.
+/// unit type `()`. A required `null` field must be explicitly present as
+/// `null` when deserializing, and an object in which it is absent is
+/// rejected. Optional `null` fields are represented as `Option<()>`, which is
+/// `None` whether the field is absent or `null`. Both are serialized as
+/// `null`.
 ///
 /// ```ignore
 /// { "type": "null" }
@@ -200,20 +794,51 @@ use proc_macro::TokenStream;
 ///
 /// ### Integer
 ///
-/// JSON integers are represented as `i64`s.
+/// JSON integers are represented as `i64`s, unless their `format` is
+/// `"int32"`, `"uint32"` or `"uint64"`, in which case they are represented as
+/// `i32`s, `u32`s or `u64`s respectively. Unrecognized formats fall back to
+/// `i64`. Integers with a `minimum` of zero or more are represented by the
+/// unsigned variant of their type, so `{ "type": "integer", "minimum": 0 }`
+/// becomes a `u64`.
 ///
 /// ```ignore
 /// { "type": "integer" }
 /// ```
 ///
+/// ```ignore
+/// { "type": "integer", "format": "int32" }
+/// ```
+///
+/// Some APIs encode booleans as the integers `0` and `1`. An integer object
+/// property marked with `"x-bool": true` is represented as a `bool` instead,
+/// while still being read from and written to JSON as `0` or `1`.
+///
+/// ```ignore
+/// { "type": "integer", "enum": [0, 1], "x-bool": true }
+/// ```
+///
 /// ### Number
 ///
-/// JSON numbers are represented as `f64`s.
+/// JSON numbers are represented as `f64`s, or as `f32`s if their `format` is
+/// `"float"`. A `"double"` format is the same as omitting the format.
 ///
 /// ```ignore
 /// { "type": "number" }
 /// ```
 ///
+/// ```ignore
+/// { "type": "number", "format": "float" }
+/// ```
+///
+/// Some APIs send numbers as strings, such as `"12.34"`. Integer and number
+/// object properties marked with `"x-coerce": true` accept either a JSON
+/// number or a numeric string when deserializing, and are always serialized
+/// as JSON numbers.
+///
+/// ```ignore
+/// { "type": "number", "x-coerce": true }
+/// ```
+///
 /// ### String
 ///
 /// Strings in JSON correspond to Rust's owned `String`s.
@@ -222,6 +847,56 @@ use proc_macro::TokenStream;
 /// { "type": "string" }
 /// ```
 ///
+/// With the `chrono` feature enabled, strings of format `date-time` (e.g.
+/// `"2024-05-01T13:45:00Z"`) are represented as
+/// `chrono::DateTime<chrono::Utc>` instead, with date-times in other offsets
+/// converted to UTC. Likewise, strings of format `date` (e.g. `"2024-05-01"`)
+/// are represented as `chrono::NaiveDate`, and strings of format `time` (e.g.
+/// `"13:45:00"`) as `chrono::NaiveTime`. Default values for such strings must
+/// be valid for their format. Without the feature, these strings remain
+/// `String`s.
+///
+/// ```ignore
+/// { "type": "string", "format": "date-time" }
+/// ```
+///
+/// ```ignore
+/// schema-struct = { version = "0.1", features = ["chrono"] }
+/// ```
+///
+/// With the `uuid` feature enabled, strings of format `uuid` are represented
+/// as `uuid::Uuid`. Default values for such strings must be valid UUIDs.
+/// Without the feature, these strings remain `String`s.
+///
+/// ```ignore
+/// { "type": "string", "format": "uuid" }
+/// ```
+///
+/// ```ignore
+/// schema-struct = { version = "0.1", features = ["uuid"] }
+/// ```
+///
+/// Strings of the internationalized formats `idn-email`, `idn-hostname` and
+/// `iri` remain `String`s. With [schema validation](#schema-validation)
+/// enabled, they are checked permissively and with Unicode in mind, so that
+/// an address such as `"用户@例子.广告"` is accepted while one without a valid
+/// domain is rejected. These checks apply under every draft that validates
+/// formats, which excludes drafts 2019-09 and 2020-12, where formats are only
+/// annotations.
+///
+/// ```ignore
+/// { "type": "string", "format": "idn-email" }
+/// ```
+///
+/// The `minLength` and `maxLength` of string object properties are checked
+/// when deserializing, even without [schema validation](#schema-validation),
+/// counting the characters of the string. Strings represented by types other
+/// than `String` are not checked.
+///
+/// ```ignore
+/// { "type": "string", "minLength": 1, "maxLength": 64 }
+/// ```
+///
 /// ### Array
 ///
 /// Arrays translate to `Vec`s in Rust. Because of this, arrays are
@@ -239,6 +914,13 @@ use proc_macro::TokenStream;
 ///
 /// The example above would be transformed into a `Vec<i64>`.
 ///
+/// If `minItems` and `maxItems` are equal, the array has a fixed length, and
+/// is transformed into a fixed-size array instead. An array of numbers with
+/// `"minItems": 3` and `"maxItems": 3` would be transformed into a
+/// `[f64; 3]`, and any default value must have exactly three items. Fixed-size
+/// arrays are limited to 32 items, as that is the largest size serde
+/// supports; longer arrays are still transformed into `Vec`s.
+///
 /// ### Object
 ///
 /// Objects are transformed into struct definitions. Struct names and fields
@@ -267,6 +949,45 @@ use proc_macro::TokenStream;
 /// }
 /// ```
 ///
+/// An object property marked with `"x-flatten": true` is still generated as
+/// its own struct, but its fields are read from and written to the parent
+/// object's JSON, using `#[serde(flatten)]`.
+///
+/// ```ignore
+/// {
+///     "address": {
+///         "type": "object",
+///         "x-flatten": true,
+///         "properties": {
+///             "street": { "type": "string" }
+///         }
+///     }
+/// }
+/// ```
+///
+/// Objects that declare no properties but give `additionalProperties` a
+/// schema are represented as maps, e.g. `HashMap<String, i64>` for
+/// `"additionalProperties": { "type": "integer" }`. When an object declares
+/// properties as well, it is generated as a struct with an extra
+/// `additional_properties` map field, using `#[serde(flatten)]` to collect
+/// any properties not declared by the schema. A declared property whose field
+/// would also be named `additional_properties` is reported as an error. An
+/// `additionalProperties` value of `true` or `{}` does not affect the
+/// generated type. A value of `false`
+/// adds `#[serde(deny_unknown_fields)]` to the generated struct, so
+/// deserializing an object with undeclared properties fails. Serde cannot
+/// combine that attribute with flattened fields, so an object with
+/// `x-flatten` properties cannot set `additionalProperties` to `false`.
+///
+/// ```ignore
+/// {
+///     "counts": {
+///         "type": "object",
+///         "additionalProperties": { "type": "integer" }
+///     }
+/// }
+/// ```
+///
 /// ### Enum
 ///
 /// Enums in JSON schemas are represented as one of an arbitrary number of
@@ -292,6 +1013,136 @@ use proc_macro::TokenStream;
 /// }
 /// ```
 ///
+/// If the enum specifies a `"default"` variant, `Default` is also implemented
+/// for the generated enum, returning that variant.
+///
+/// Variant strings that contain nothing usable as an identifier, such as
+/// `"???"`, or that would collide with an earlier variant once renamed, such
+/// as `"N/A"` and `"n/a"`, are named `VariantN` instead, where `N` is the
+/// variant's position in the enum. The original strings are still used when
+/// serializing and deserializing.
+///
+/// Enums may also consist entirely of numbers, such as `{ "enum": [1, 2, 3]
+/// }` or `{ "enum": [1.5, 2.5] }`. Their variants are named after the
+/// numbers, e.g. `Variant1`, `Variant1_5` or `VariantNeg2` for `-2`, and are
+/// serialized and deserialized as the numbers themselves. Enums mixing
+/// strings and numbers are not supported.
+///
+/// ### Const
+///
+/// Schemas with a `const` value, such as a tag identifying the kind of a
+/// message, are represented as unit structs. A constant field always
+/// serializes to its constant, and deserializing fails if the value does not
+/// equal the constant. If the schema also declares a `type`, the constant
+/// must match it.
+///
+/// ```ignore
+/// {
+///     "kind": {
+///         "const": "user"
+///     }
+/// }
+/// ```
+///
+/// The example above would be transformed into:
+///
+/// ```ignore
+/// struct Kind;
+/// ```
+///
+/// Enums with a single string variant, such as `{ "enum": ["only"] }`, can be
+/// represented in the same way by enabling the `unit_enums` option.
+///
+/// ```ignore
+/// schema_struct!(
+///     unit_enums = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### One of
+///
+/// Schemas without a `type` that list alternatives with `oneOf` are
+/// represented as enums with a variant wrapping the type generated for each
+/// alternative. Variants are named after an alternative's `title`, the
+/// subschema it references, or its type, falling back to `VariantN`.
+///
+/// When every alternative is an object with a common property set to a
+/// distinct `const` string, such as a `"kind"` property, that property
+/// becomes the enum's serde tag and is left out of the alternatives' own
+/// structs. Only alternatives declared inline are tagged: alternatives that
+/// are refs to subschemas, which may be used elsewhere, keep the property in
+/// their own types. Otherwise the enum is untagged, and each alternative is
+/// tried in order when deserializing. Tagged enums also get a companion enum of their
+/// tag values, named after the enum with a `Kind` suffix, and a `kind` method
+/// returning the kind of a value.
+///
+/// ```ignore
+/// {
+///     "shape": {
+///         "oneOf": [
+///             {
+///                 "type": "object",
+///                 "properties": {
+///                     "kind": { "const": "circle" },
+///                     "radius": { "type": "number" }
+///                 }
+///             },
+///             {
+///                 "type": "object",
+///                 "properties": {
+///                     "kind": { "const": "square" },
+///                     "side": { "type": "number" }
+///                 }
+///             }
+///         ]
+///     }
+/// }
+/// ```
+///
+/// The example above would be transformed into:
+///
+/// ```ignore
+/// #[serde(tag = "kind")]
+/// enum Shape {
+///     #[serde(rename = "circle")]
+///     Circle(ShapeCircle),
+///     #[serde(rename = "square")]
+///     Square(ShapeSquare),
+/// }
+/// ```
+///
+/// ### Any of
+///
+/// Schemas without a `type` that list alternatives with `anyOf` are
+/// represented as untagged enums, with variants named the same way as for
+/// `oneOf`. Since a value may match several alternatives, the first
+/// alternative that successfully deserializes a value wins, so more specific
+/// alternatives should be listed before more general ones.
+///
+/// ```ignore
+/// {
+///     "value": {
+///         "anyOf": [
+///             { "type": "integer" },
+///             { "type": "number" },
+///             { "type": "string" }
+///         ]
+///     }
+/// }
+/// ```
+///
+/// The example above would be transformed into:
+///
+/// ```ignore
+/// #[serde(untagged)]
+/// enum Value {
+///     Integer(i64),
+///     Number(f64),
+///     String(String),
+/// }
+/// ```
+///
 /// ### Tuple
 ///
 /// JSON schemas represent tuples as an array of JSON values. This corresponds
@@ -322,10 +1173,12 @@ use proc_macro::TokenStream;
 ///
 /// ### Ref
 ///
-/// References are a very useful feature of JSON schemas. They are supported
-/// through the `Box` smart pointer, in order to allow potentially
-/// self-referential data structures. All refs must point to either the root
-/// object itself or a defined subschema.
+/// References are a very useful feature of JSON schemas. Refs may point to
+/// the root object itself, a defined subschema, or any other location in the
+/// schema. A ref that leads back to the type containing it, directly or
+/// through other refs, is wrapped in the `Box` smart pointer so that the
+/// self-referential data structure has a finite size. All other refs use the
+/// referenced type directly.
 ///
 /// A ref to the root object:
 ///
@@ -376,7 +1229,7 @@ use proc_macro::TokenStream;
 /// pub type SchemaWithRefDefMyInteger = i64;
 ///
 /// struct SchemaWithRef {
-///     my_integer_field: Option<Box<SchemaWithRefDefMyInteger>>,
+///     my_integer_field: Option<SchemaWithRefDefMyInteger>,
 /// }
 /// ```
 ///
@@ -384,6 +1237,41 @@ use proc_macro::TokenStream;
 /// non-primitive subschema types, full type definitions will be generated
 /// instead.
 ///
+/// Subschemas may also be declared under the draft-04 `definitions` keyword,
+/// alongside or instead of `$defs`. A subschema name declared under both is
+/// reported as an error.
+///
+/// Refs to other locations in the schema, such as `#/properties/tags/items`
+/// or `#/$defs/outer/properties/inner`, are treated as though the referenced
+/// schema had been declared as a subschema. The referenced type is generated
+/// once, named after its path (e.g. `SchemaWithRefDefPropertiesTagsItems`),
+/// and shared by both the original location and the ref. A ref path that
+/// cannot be resolved results in a compile error naming the segment that
+/// could not be found.
+///
+/// A ref field without a `default` of its own uses the default declared by
+/// the referenced schema, if any, except for refs from a subschema back to
+/// itself. For example, a ref to an enum subschema declaring
+/// `"default": "medium"` defaults to that variant, while a
+/// `"default": "small"` beside the `$ref` takes precedence.
+///
+/// Refs may also point into other local files, such as
+/// `common.json#/$defs/Address`, or to a whole file, such as `common.json`.
+/// Such files are resolved relative to the file containing the ref, or
+/// relative to the crate's manifest directory for schemas given inline. The
+/// referenced location is copied into the schema as a subschema named after
+/// the last segment of its pointer (or the file stem, for whole files), so
+/// `Address` above generates a type like `SchemaWithRefDefAddress`. If that
+/// name is already taken, such as by a subschema of the same name in another
+/// file, it is qualified with the file stem, as in `common_Address`. Refs
+/// within the copied location are resolved relative to its own file. Files
+/// that refer back to a file whose locations are still being resolved are
+/// reported as circular file references.
+///
+/// The `$recursiveRef` and `$dynamicRef` keywords are accepted as well, but
+/// only when they point to the root object (`#`), in which case they behave
+/// exactly like `"$ref": "#"`.
+///
 /// ## Optional fields
 ///
 /// By default, JSON schemas assume that all fields are optional. To mark a
@@ -416,6 +1304,16 @@ use proc_macro::TokenStream;
 /// }
 /// ```
 ///
+/// Fields can also be made nullable with a type array containing `null` and
+/// one other type, as is common in schemas generated from OpenAPI. Such
+/// fields are wrapped in an `Option` even if they are required. A type array
+/// containing only `null` is equivalent to `"type": "null"`, and type arrays
+/// with more than one type other than `null` are not supported.
+///
+/// ```ignore
+/// { "type": ["string", "null"] }
+/// ```
+///
 /// ## Default values
 ///
 /// Default values can be provided for JSON values of any type. If a field is
@@ -494,6 +1392,84 @@ use proc_macro::TokenStream;
 /// does not define a default value, then `null` will be used instead. If the
 /// property is not nullable, an error will be raised.
 ///
+/// ### Default bounds
+///
+/// Integer and number defaults are checked against the `minimum`, `maximum`,
+/// `exclusiveMinimum` and `exclusiveMaximum` properties when the macro is
+/// expanded, accepting both the boolean form of the exclusive bounds from
+/// draft 4 and the numeric form from later drafts. A default outside of the
+/// declared bounds results in a compile error. Likewise, a default that is
+/// not a multiple of the `multipleOf` property results in a compile error.
+///
+/// ```ignore
+/// {
+///     "type": "integer",
+///     "maximum": 5,
+///     "default": 10
+/// }
+/// ```
+///
+/// ### Default refs
+///
+/// A default value may be given as a ref to a value defined elsewhere in the
+/// schema, such as `{ "$ref": "#/$defs/defaultMoney" }`. If the referenced
+/// location is a `const` schema, its constant value is used as the default.
+/// Refs to locations outside of the schema, and refs that lead back to
+/// themselves, result in a compile error.
+///
+/// ```ignore
+/// {
+///     "$defs": {
+///         "defaultMoney": {
+///             "const": { "amount": 5, "currency": "USD" }
+///         }
+///     },
+///     "properties": {
+///         "price": {
+///             "type": "object",
+///             "properties": {
+///                 "amount": { "type": "integer" },
+///                 "currency": { "type": "string" }
+///             },
+///             "default": { "$ref": "#/$defs/defaultMoney" }
+///         }
+///     }
+/// }
+/// ```
+///
+/// ### Default implementations
+///
+/// Default values are applied during deserialization through serde's
+/// `default` attribute. The `default_impl` option additionally implements
+/// `Default` for each generated struct whose fields are all either optional
+/// or given a default value. Optional fields without a default are set to
+/// `None`. Structs with a required field that has no default are left without
+/// an implementation.
+///
+/// ```ignore
+/// schema_struct!(
+///     default_impl = true,
+///     schema = { ... }
+/// );
+/// ```
+///
+/// ### Constructors
+///
+/// The `constructor` option generates a `new` function for each generated
+/// struct, whose parameters are its required fields without a default value,
+/// in the order they are declared. Every other field is initialized to its
+/// default value, or to `None` if it has none. The option is disabled by
+/// default, so that it cannot clash with a `new` function defined by hand.
+///
+/// ```ignore
+/// schema_struct!(
+///     constructor = true,
+///     schema = { ... }
+/// );
+///
+/// let product = Product::new(5, "product name".to_owned(), 12.34);
+/// ```
+///
 /// ## Documentation
 ///
 /// Struct definitions and fields on them can be documented using the
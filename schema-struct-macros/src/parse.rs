@@ -1,38 +1,412 @@
-use crate::schema::JsonSchema;
-use crate::schema_struct::{SchemaStruct, SchemaStructConfig};
+use crate::file_refs::bundle_file_refs;
+use crate::schema::{JsonSchema, JsonSchemaError, SchemaDraft};
+use crate::schema_struct::{SchemaStruct, SchemaStructConfig, SchemaStructError};
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Group, TokenStream as TokenStream2};
 use quote::quote;
 use serde_json::Value;
+use std::env;
 use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 use syn::parse::{Parse, ParseStream};
-use syn::{parse_macro_input, Ident, LitBool, LitStr, Token, Visibility};
+use syn::punctuated::Punctuated;
+use syn::{
+    braced, bracketed, parse_macro_input, Ident, LitBool, LitByteStr, LitInt, LitStr, Macro, Path,
+    Token, Visibility,
+};
 
-/// Parses a JSON schema from a string into a `serde_json::Value`.
-fn parse_schema_from_str(schema: &str) -> Result<Value, String> {
-    match JsonSchema::parse(schema) {
-        Ok(_) => serde_json::from_str::<Value>(schema)
-            .map_err(|e| format!("error parsing schema as JSON: {}", e)),
-        Err(e) => Err(format!("error parsing schema: {:?}", e)),
+/// Parses a JSON schema from a string into a `serde_json::Value`. A leading
+/// byte order mark and any surrounding whitespace are ignored. If a draft is
+/// given, the schema is pinned to it.
+fn parse_schema_from_str(
+    schema: &str,
+    draft: Option<SchemaDraft>,
+) -> Result<Value, SchemaStructError> {
+    let schema = schema.trim_start_matches('\u{feff}').trim();
+
+    match JsonSchema::parse(schema, draft) {
+        Ok(_) => {
+            let mut schema_value = serde_json::from_str::<Value>(schema).map_err(|e| {
+                SchemaStructError::with_source(format!("error parsing schema as JSON: {}", e), e)
+            })?;
+            if let Some(draft) = draft {
+                draft.pin(&mut schema_value)?;
+            }
+            Ok(schema_value)
+        }
+        Err(JsonSchemaError::ParseError(e)) => Err(SchemaStructError::with_source(
+            format!("error parsing schema as JSON: {}", e),
+            e,
+        )),
+        Err(e) => Err(format!("error parsing schema: {}", e).into()),
     }
 }
 
+/// Resolves the path to a schema file. Relative paths are resolved against
+/// the manifest directory of the crate invoking the macro, falling back to
+/// the working directory if no such file exists there.
+fn resolve_schema_path(file: &str) -> PathBuf {
+    let path = PathBuf::from(file);
+
+    if path.is_relative() {
+        if let Some(manifest_dir) = env::var_os("CARGO_MANIFEST_DIR") {
+            let manifest_path = PathBuf::from(manifest_dir).join(&path);
+
+            if manifest_path.exists() {
+                return manifest_path;
+            }
+        }
+    }
+
+    path
+}
+
+/// Resolves the path of a file to write generated code to. Relative paths are
+/// resolved against the manifest directory of the crate invoking the macro.
+fn resolve_output_path(file: &str) -> String {
+    let path = PathBuf::from(file);
+
+    match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(manifest_dir) if path.is_relative() => PathBuf::from(manifest_dir)
+            .join(path)
+            .to_string_lossy()
+            .into_owned(),
+        _ => file.to_owned(),
+    }
+}
+
+/// Gets the directory against which file-relative refs in schemas that do
+/// not come from a file are resolved: the manifest directory of the crate
+/// invoking the macro, or the working directory if it is unknown.
+fn refs_base_dir() -> PathBuf {
+    env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Bundles the locations referenced by the file-relative refs of a schema
+/// that does not come from a file.
+fn with_file_refs_bundled(mut value: Value) -> Result<Value, SchemaStructError> {
+    bundle_file_refs(&mut value, None, &refs_base_dir(), &[])?;
+    Ok(value)
+}
+
 /// Parses a JSON schema that exists in a file.
-fn parse_schema_from_file(file: &str) -> Result<Value, String> {
-    match fs::read_to_string(file) {
-        Ok(value) => parse_schema_from_str(&value),
-        Err(e) => Err(e.to_string()),
+fn parse_schema_from_file(
+    file: &str,
+    draft: Option<SchemaDraft>,
+) -> Result<Value, SchemaStructError> {
+    parse_schema_from_path(&resolve_schema_path(file), draft, &[])
+}
+
+/// Parses a JSON schema file at a resolved path, bundling the locations
+/// referenced by its file-relative refs. Refs to whole files listed in
+/// `shared_files` are left as they are.
+fn parse_schema_from_path(
+    path: &std::path::Path,
+    draft: Option<SchemaDraft>,
+    shared_files: &[PathBuf],
+) -> Result<Value, SchemaStructError> {
+    let mut value = match fs::read_to_string(path) {
+        Ok(value) => parse_schema_from_str(&value, draft)?,
+        Err(e) => return Err(SchemaStructError::with_source(e.to_string(), e)),
+    };
+    bundle_file_refs(&mut value, Some(path), &refs_base_dir(), shared_files)?;
+
+    Ok(value)
+}
+
+/// Parses a JSON schema from a UTF-8 encoded byte slice.
+fn parse_schema_from_bytes(
+    bytes: &[u8],
+    draft: Option<SchemaDraft>,
+) -> Result<Value, SchemaStructError> {
+    match std::str::from_utf8(bytes) {
+        Ok(value) => parse_schema_from_str(value, draft),
+        Err(e) => Err(SchemaStructError::with_source(
+            format!("schema bytes are not valid UTF-8: {}", e),
+            e,
+        )),
     }
 }
 
-/// Parses a JSON schema that exists at a URL.
-fn parse_schema_from_url(url: &str) -> Result<Value, String> {
-    match reqwest::blocking::get(url) {
-        Ok(res) => match res.text() {
-            Ok(value) => parse_schema_from_str(&value),
-            Err(e) => Err(e.to_string()),
+/// Reads the bytes of a schema from either a byte string literal or an
+/// `include_bytes!` invocation. As with the standard macro, paths passed to
+/// `include_bytes!` are resolved relative to the source file containing the
+/// invocation.
+fn read_schema_bytes(tokens: TokenStream2) -> Result<Vec<u8>, String> {
+    if let Ok(lit) = syn::parse2::<LitByteStr>(tokens.clone()) {
+        return Ok(lit.value());
+    }
+
+    match syn::parse2::<Macro>(tokens) {
+        Ok(mac) if mac.path.is_ident("include_bytes") => {
+            let file_lit = mac.parse_body::<LitStr>().map_err(|e| e.to_string())?;
+            let file = file_lit.value();
+            let source_file = file_lit.span().unwrap().local_file().ok_or(format!(
+                "cannot resolve `include_bytes!(\"{}\")`, as the invoking source file is unknown",
+                file
+            ))?;
+            let path = source_file
+                .parent()
+                .map(|source_dir| source_dir.join(&file))
+                .unwrap_or_else(|| PathBuf::from(&file));
+
+            fs::read(&path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))
+        }
+        _ => Err("expected a byte string literal or an `include_bytes!` invocation".to_owned()),
+    }
+}
+
+/// How long a schema fetched from a URL is reused from the cache before it is
+/// fetched again.
+const URL_CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Gets the directory in which schemas fetched from URLs are cached. This is
+/// the invoking crate's `OUT_DIR` if it has one, the directory given by
+/// `CARGO_TARGET_DIR` if it is set, and otherwise a directory private to the
+/// current user in the system's temporary directory, so that cached schemas
+/// are never read from a location other users can write to. Returns `None`
+/// if no such directory can be used.
+fn url_cache_dir() -> Option<PathBuf> {
+    match env::var_os("OUT_DIR").or_else(|| env::var_os("CARGO_TARGET_DIR")) {
+        Some(dir) => Some(PathBuf::from(dir).join("schema-struct-url-cache")),
+        None => {
+            let user = env::var("USER")
+                .or_else(|_| env::var("USERNAME"))
+                .unwrap_or_default();
+            let dir = env::temp_dir().join(format!("schema-struct-url-cache-{}", user));
+            create_private_dir(&dir).ok().map(|()| dir)
+        }
+    }
+}
+
+/// Creates a directory only the current user can access, or checks that an
+/// existing one cannot be accessed by other users. A directory created by
+/// another user with the same permissions cannot be read or written, so it
+/// only ever causes cache misses.
+#[cfg(unix)]
+fn create_private_dir(dir: &std::path::Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            let metadata = fs::symlink_metadata(dir)?;
+
+            if metadata.is_dir() && metadata.permissions().mode() & 0o077 == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "cache directory is accessible to other users",
+                ))
+            }
+        }
+        result => result,
+    }
+}
+
+/// Creates a directory for the current user. The system's temporary
+/// directory is already private to each user on other platforms.
+#[cfg(not(unix))]
+fn create_private_dir(dir: &std::path::Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+/// Hashes a string with 64-bit FNV-1a. Unlike the standard library's hashers,
+/// its output is the same across Rust releases.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Gets the path of the cached copy of the schema at a URL, keyed by a hash
+/// of the URL.
+fn url_cache_path(cache_dir: &std::path::Path, url: &str) -> PathBuf {
+    cache_dir.join(format!("{:016x}.json", fnv1a(url)))
+}
+
+/// Writes a file in the cache. The text is written to a temporary file in the
+/// same directory, which is then renamed into place, so that a concurrent
+/// build reading the cache never sees a partially written file.
+fn write_cache_file(cache_path: &std::path::Path, text: &str) -> io::Result<()> {
+    static TEMP_FILE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let temp_path = cache_path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        TEMP_FILE_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    fs::write(&temp_path, text)
+        .and_then(|()| fs::rename(&temp_path, cache_path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+}
+
+/// Fetches the text of a schema at a URL through a cache directory. Cached
+/// copies younger than `URL_CACHE_MAX_AGE` are used without fetching, and
+/// older copies are used, with a warning, if fetching fails. Only fetched
+/// text that parses as JSON is cached.
+fn fetch_url_cached<F>(
+    url: &str,
+    cache_dir: &std::path::Path,
+    fetch: F,
+) -> Result<String, SchemaStructError>
+where
+    F: FnOnce(&str) -> Result<String, SchemaStructError>,
+{
+    let cache_path = url_cache_path(cache_dir, url);
+    let cache_age = fs::metadata(&cache_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+
+    if cache_age.is_some_and(|age| age < URL_CACHE_MAX_AGE) {
+        if let Ok(cached) = fs::read_to_string(&cache_path) {
+            return Ok(cached);
+        }
+    }
+
+    match fetch(url) {
+        Ok(value) => {
+            // The cache is only an optimization, so failing to write it is
+            // not an error.
+            if serde_json::from_str::<Value>(&value).is_ok() {
+                let _ = fs::create_dir_all(cache_dir)
+                    .and_then(|()| write_cache_file(&cache_path, &value));
+            }
+
+            Ok(value)
+        }
+        Err(e) => match fs::read_to_string(&cache_path) {
+            Ok(cached) => {
+                // The macro may be expanded many times in one compiler
+                // process, so each URL is only warned about once.
+                static WARNED_URLS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+                let mut warned_urls = WARNED_URLS.lock().unwrap_or_else(|e| e.into_inner());
+                if !warned_urls.iter().any(|warned_url| warned_url == url) {
+                    warned_urls.push(url.to_owned());
+                    eprintln!(
+                        "warning: failed to fetch schema from {} ({}), using cached copy",
+                        url, e
+                    );
+                }
+
+                Ok(cached)
+            }
+            Err(_) => Err(e),
         },
-        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parses every `.json` schema file in a directory, in file name order.
+/// Relative directories are resolved in the same way as `file` paths.
+fn parse_schemas_from_dir(
+    dir: &str,
+    draft: Option<SchemaDraft>,
+) -> Result<Vec<(String, Value)>, SchemaStructError> {
+    let dir_path = resolve_schema_path(dir);
+    let mut paths = fs::read_dir(&dir_path)
+        .map_err(|e| SchemaStructError::with_source(e.to_string(), e))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| SchemaStructError::with_source(e.to_string(), e))?;
+    paths.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"));
+    paths.sort();
+
+    if paths.is_empty() {
+        return Err(format!("no `.json` schema files found in '{}'", dir).into());
+    }
+
+    // Whole-file refs between the schemas are replaced by their generated
+    // types rather than bundled.
+    let shared_files = paths
+        .iter()
+        .map(fs::canonicalize)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| SchemaStructError::with_source(e.to_string(), e))?;
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let file_name = path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let value = parse_schema_from_path(&path, draft, &shared_files)
+                .map_err(|e| format!("{}: {}", file_name, e))?;
+
+            Ok((file_name, value))
+        })
+        .collect()
+}
+
+/// Parses a JSON schema that exists at a URL. Unless `cache` is `false`,
+/// fetched schemas are cached and reused on later builds.
+fn parse_schema_from_url(
+    url: &str,
+    draft: Option<SchemaDraft>,
+    cache: bool,
+) -> Result<Value, SchemaStructError> {
+    let value = match url_cache_dir() {
+        Some(cache_dir) if cache => fetch_url_cached(url, &cache_dir, fetch_url)?,
+        _ => fetch_url(url)?,
+    };
+
+    parse_schema_from_str(&value, draft)
+}
+
+/// Fetches the text at a URL with `ureq`.
+#[cfg(feature = "ureq")]
+fn fetch_url(url: &str) -> Result<String, SchemaStructError> {
+    match ureq::get(url).call() {
+        Ok(res) => res
+            .into_string()
+            .map_err(|e| SchemaStructError::with_source(e.to_string(), e)),
+        Err(e) => Err(SchemaStructError::with_source(e.to_string(), e)),
+    }
+}
+
+/// Fetches the text at a URL with `reqwest`.
+#[cfg(all(feature = "reqwest", not(feature = "ureq")))]
+fn fetch_url(url: &str) -> Result<String, SchemaStructError> {
+    match reqwest::blocking::get(url) {
+        Ok(res) => res
+            .text()
+            .map_err(|e| SchemaStructError::with_source(e.to_string(), e)),
+        Err(e) => Err(SchemaStructError::with_source(e.to_string(), e)),
+    }
+}
+
+/// Reports that no HTTP client is available to fetch a schema from a URL.
+#[cfg(not(any(feature = "reqwest", feature = "ureq")))]
+fn fetch_url(_url: &str) -> Result<String, SchemaStructError> {
+    Err("fetching schemas from URLs requires either the `reqwest` or `ureq` feature".into())
+}
+
+/// Deep-merges a JSON value onto another. Objects are merged key by key,
+/// while all other values in `extension` replace those in `base`.
+fn merge_json(base: &mut Value, extension: Value) {
+    match (base, extension) {
+        (Value::Object(base), Value::Object(extension)) => {
+            for (key, value) in extension {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_json(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, extension) => *base = extension,
     }
 }
 
@@ -42,7 +416,44 @@ impl Parse for SchemaStructConfig {
         let mut schema_ident = None;
         let mut schema_def = None;
         let mut schema_validate = None;
+        let mut schema_validate_on_serialize = None;
         let mut schema_debug = None;
+        let mut schema_debug_file = None;
+        let mut schema_generate_tests = None;
+        let mut schema_required_by_default = None;
+        let mut schema_ordered_enum = None;
+        let mut schema_internal_path = None;
+        let mut schema_auto_rename = None;
+        let mut schema_boxed_slices = None;
+        let mut schema_compat = None;
+        let mut schema_defs = None;
+        let mut schema_flatten_refs = None;
+        let mut schema_raw_idents = None;
+        let mut schema_custom_serde = None;
+        let mut schema_prune_unused_defs = None;
+        let mut schema_serde = None;
+        let mut schema_external_types = None;
+        let mut schema_doc_table = None;
+        let mut schema_unit_enums = None;
+        let mut schema_strict = None;
+        let mut schema_parse_method = None;
+        let mut schema_emit_method = None;
+        let mut schema_derives = None;
+        let mut schema_skip_none = None;
+        let mut schema_default_impl = None;
+        let mut schema_diff = None;
+        let mut schema_repr = None;
+        let mut schema_vec_type = None;
+        let mut schema_map_type = None;
+        let mut schema_rename_all = None;
+        let mut schema_float_precision = None;
+        let mut schema_output = None;
+        let mut schema_cache = None;
+        let mut schema_dir = None;
+        let mut schema_omit_empty_collections = None;
+        let mut schema_constructor = None;
+        let mut schema_draft = None;
+        let mut schema_extend = None;
 
         let schema_value = loop {
             let keyword = input.parse::<Ident>()?;
@@ -61,23 +472,183 @@ impl Parse for SchemaStructConfig {
                 "validate" => {
                     schema_validate = Some(input.parse::<LitBool>()?.value);
                 }
+                "validate_on_serialize" => {
+                    schema_validate_on_serialize = Some(input.parse::<LitBool>()?.value);
+                }
                 "debug" => {
                     schema_debug = Some(input.parse::<LitBool>()?.value);
                 }
+                "debug_file" => {
+                    schema_debug_file =
+                        Some(resolve_output_path(&input.parse::<LitStr>()?.value()));
+                }
+                "generate_tests" => {
+                    schema_generate_tests = Some(input.parse::<LitBool>()?.value);
+                }
+                "required_by_default" => {
+                    schema_required_by_default = Some(input.parse::<LitBool>()?.value);
+                }
+                "ordered_enum" => {
+                    schema_ordered_enum = Some(input.parse::<LitBool>()?.value);
+                }
+                "internal_path" => {
+                    schema_internal_path = Some(input.parse::<Path>()?);
+                }
+                "auto_rename" => {
+                    schema_auto_rename = Some(input.parse::<LitBool>()?.value);
+                }
+                "boxed_slices" => {
+                    schema_boxed_slices = Some(input.parse::<LitBool>()?.value);
+                }
+                "compat" => {
+                    let content;
+                    bracketed!(content in input);
+                    let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                    schema_compat = Some(paths.into_iter().collect());
+                }
+                "defs" => {
+                    let content;
+                    bracketed!(content in input);
+                    let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    schema_defs = Some(names.iter().map(LitStr::value).collect());
+                }
+                "flatten_refs" => {
+                    schema_flatten_refs = Some(input.parse::<LitBool>()?.value);
+                }
+                "raw_idents" => {
+                    schema_raw_idents = Some(input.parse::<LitBool>()?.value);
+                }
+                "custom_serde" => {
+                    schema_custom_serde = Some(input.parse::<LitBool>()?.value);
+                }
+                "prune_unused_defs" => {
+                    schema_prune_unused_defs = Some(input.parse::<LitBool>()?.value);
+                }
+                "serde" => {
+                    schema_serde = Some(input.parse::<LitStr>()?.value());
+                }
+                "external_types" => {
+                    let content;
+                    braced!(content in input);
+                    let entries =
+                        Punctuated::<_, Token![,]>::parse_terminated_with(&content, |input| {
+                            let ref_path = input.parse::<LitStr>()?.value();
+                            input.parse::<Token![:]>()?;
+                            let ty = input.parse::<Path>()?;
+                            Ok((ref_path, ty))
+                        })?;
+                    schema_external_types = Some(entries.into_iter().collect());
+                }
+                "doc_table" => {
+                    schema_doc_table = Some(input.parse::<LitBool>()?.value);
+                }
+                "unit_enums" => {
+                    schema_unit_enums = Some(input.parse::<LitBool>()?.value);
+                }
+                "strict" => {
+                    schema_strict = Some(input.parse::<LitBool>()?.value);
+                }
+                "parse_method" => {
+                    schema_parse_method = Some(input.parse::<Ident>()?);
+                }
+                "emit_method" => {
+                    schema_emit_method = Some(input.parse::<Ident>()?);
+                }
+                "derives" => {
+                    let content;
+                    bracketed!(content in input);
+                    let paths = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                    schema_derives = Some(paths.into_iter().collect());
+                }
+                "skip_none" => {
+                    schema_skip_none = Some(input.parse::<LitBool>()?.value);
+                }
+                "default_impl" => {
+                    schema_default_impl = Some(input.parse::<LitBool>()?.value);
+                }
+                "diff" => {
+                    schema_diff = Some(input.parse::<LitBool>()?.value);
+                }
+                "repr" => {
+                    schema_repr = Some(input.parse::<Ident>()?);
+                }
+                "vec_type" => {
+                    schema_vec_type = Some(input.parse::<LitStr>()?.value());
+                }
+                "map_type" => {
+                    schema_map_type = Some(input.parse::<LitStr>()?.value());
+                }
+                "rename_all" => {
+                    schema_rename_all = Some(input.parse::<LitStr>()?.value());
+                }
+                "float_precision" => {
+                    schema_float_precision = Some(input.parse::<LitInt>()?.base10_parse()?);
+                }
+                "output" => {
+                    schema_output = Some(resolve_output_path(&input.parse::<LitStr>()?.value()));
+                }
+                "cache" => {
+                    schema_cache = Some(input.parse::<LitBool>()?.value);
+                }
+                "omit_empty_collections" => {
+                    schema_omit_empty_collections = Some(input.parse::<LitBool>()?.value);
+                }
+                "constructor" => {
+                    schema_constructor = Some(input.parse::<LitBool>()?.value);
+                }
+                "draft" => {
+                    let draft_lit = input.parse::<LitStr>()?;
+                    let draft = SchemaDraft::from_name(&draft_lit.value())
+                        .map_err(|e| syn::Error::new_spanned(&draft_lit, e))?;
+                    schema_draft = Some(draft);
+                }
+                "extend" => {
+                    let extend_tokens = input.parse::<Group>()?;
+                    let extension = serde_json::from_str::<Value>(&extend_tokens.to_string())
+                        .map_err(|e| {
+                            syn::Error::new_spanned(
+                                &extend_tokens,
+                                format!("error parsing schema extension as JSON: {}", e),
+                            )
+                        })?;
+                    schema_extend = Some(extension);
+                }
                 "schema" => {
                     let schema_tokens = input.parse::<TokenStream2>()?.to_string();
-                    break parse_schema_from_str(&schema_tokens)
+                    break parse_schema_from_str(&schema_tokens, schema_draft)
+                        .and_then(with_file_refs_bundled)
                         .map_err(|e| syn::Error::new_spanned(schema_tokens, e));
                 }
                 "file" => {
                     let schema_file = input.parse::<LitStr>()?.value();
-                    break parse_schema_from_file(&schema_file)
+                    break parse_schema_from_file(&schema_file, schema_draft)
                         .map_err(|e| syn::Error::new_spanned(schema_file, e));
                 }
+                "bytes" => {
+                    let schema_tokens = input.parse::<TokenStream2>()?;
+                    break read_schema_bytes(schema_tokens.clone())
+                        .map_err(SchemaStructError::from)
+                        .and_then(|bytes| parse_schema_from_bytes(&bytes, schema_draft))
+                        .and_then(with_file_refs_bundled)
+                        .map_err(|e| syn::Error::new_spanned(schema_tokens, e));
+                }
+                "dir" => {
+                    let dir = input.parse::<LitStr>()?.value();
+                    break parse_schemas_from_dir(&dir, schema_draft)
+                        .map(|files| {
+                            schema_dir = Some(files);
+                            Value::Null
+                        })
+                        .map_err(|e| syn::Error::new_spanned(dir, e));
+                }
                 "url" => {
                     let schema_url = input.parse::<LitStr>()?.value();
-                    break parse_schema_from_url(&schema_url)
-                        .map_err(|e| syn::Error::new_spanned(schema_url, e));
+                    break parse_schema_from_url(
+                        &schema_url,
+                        schema_draft,
+                        schema_cache.unwrap_or(true),
+                    )
+                    .map_err(|e| syn::Error::new_spanned(schema_url, e));
                 }
                 unknown_keyword => {
                     break Err(syn::Error::new_spanned(
@@ -90,12 +661,59 @@ impl Parse for SchemaStructConfig {
             input.parse::<Token![,]>()?;
         }?;
 
+        let (schema_value, schema_dir) = match schema_extend {
+            Some(extension) => {
+                let mut schema_value = schema_value;
+                let mut schema_dir = schema_dir;
+                merge_json(&mut schema_value, extension.clone());
+                for (_, file_value) in schema_dir.iter_mut().flatten() {
+                    merge_json(file_value, extension.clone());
+                }
+                (schema_value, schema_dir)
+            }
+            None => (schema_value, schema_dir),
+        };
+
         Ok(Self {
             vis: schema_vis,
             ident: schema_ident,
             def: schema_def,
             validate: schema_validate,
+            validate_on_serialize: schema_validate_on_serialize,
             debug: schema_debug,
+            debug_file: schema_debug_file,
+            generate_tests: schema_generate_tests,
+            required_by_default: schema_required_by_default,
+            ordered_enum: schema_ordered_enum,
+            internal_path: schema_internal_path,
+            auto_rename: schema_auto_rename,
+            boxed_slices: schema_boxed_slices,
+            compat: schema_compat,
+            defs: schema_defs,
+            flatten_refs: schema_flatten_refs,
+            raw_idents: schema_raw_idents,
+            custom_serde: schema_custom_serde,
+            prune_unused_defs: schema_prune_unused_defs,
+            serde: schema_serde,
+            external_types: schema_external_types,
+            doc_table: schema_doc_table,
+            unit_enums: schema_unit_enums,
+            strict: schema_strict,
+            parse_method: schema_parse_method,
+            emit_method: schema_emit_method,
+            derives: schema_derives,
+            skip_none: schema_skip_none,
+            default_impl: schema_default_impl,
+            diff: schema_diff,
+            repr: schema_repr,
+            vec_type: schema_vec_type,
+            map_type: schema_map_type,
+            rename_all: schema_rename_all,
+            float_precision: schema_float_precision,
+            output: schema_output,
+            dir: schema_dir,
+            omit_empty_collections: schema_omit_empty_collections,
+            constructor: schema_constructor,
             schema: schema_value,
         })
     }
@@ -120,8 +738,132 @@ pub fn parse_from_schema(input: TokenStream) -> TokenStream {
     let schema_input = input.clone();
     let schema_config = parse_macro_input!(schema_input as SchemaStructConfig);
 
-    let schema = throw_on_err!(SchemaStruct::from_schema(schema_config), input);
-    let def = throw_on_err!(schema.to_struct(), input);
+    let schema_configs = throw_on_err!(schema_config.into_configs(), input);
+    let mut defs = Vec::new();
+
+    for schema_config in schema_configs {
+        let schema = throw_on_err!(SchemaStruct::from_schema(schema_config), input.clone());
+        let def = throw_on_err!(schema.to_struct(), input.clone());
+        throw_on_err!(def.write_debug_file(), input.clone());
+        defs.push(def);
+    }
+
+    quote!(#(#defs)*).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_parse_error_source() {
+        let err = parse_schema_from_str("{ \"type\": ", None).unwrap_err();
+        let source = err.source().expect("parse error should carry a source");
+        assert!(source.is::<serde_json::Error>());
+        assert_eq!(
+            err.to_string(),
+            format!("error parsing schema as JSON: {}", source)
+        );
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a("foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    /// Creates an empty cache directory unique to a test.
+    fn test_cache_dir(name: &str) -> PathBuf {
+        let cache_dir = env::temp_dir().join(format!(
+            "schema-struct-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&cache_dir);
+        cache_dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_cache_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let cache_dir = test_cache_dir("private");
+
+        create_private_dir(&cache_dir).unwrap();
+        let mode = fs::metadata(&cache_dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        create_private_dir(&cache_dir).unwrap();
+
+        fs::set_permissions(&cache_dir, fs::Permissions::from_mode(0o777)).unwrap();
+        let err = create_private_dir(&cache_dir).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_url_cache_reuses_fetched_schema() {
+        let cache_dir = test_cache_dir("reuse");
+        let url = "https://example.com/schema.json";
+
+        let fetched = fetch_url_cached(url, &cache_dir, |_| Ok("{\"type\":\"object\"}".into()));
+        assert_eq!(fetched.unwrap(), "{\"type\":\"object\"}");
+
+        let cached = fetch_url_cached(url, &cache_dir, |_| panic!("schema should be cached"));
+        assert_eq!(cached.unwrap(), "{\"type\":\"object\"}");
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
 
-    quote!(#def).into()
+    #[test]
+    fn test_url_cache_refetches_stale_schema() {
+        let cache_dir = test_cache_dir("stale");
+        let url = "https://example.com/schema.json";
+        let cache_path = url_cache_path(&cache_dir, url);
+
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(&cache_path, "{\"type\":\"string\"}").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&cache_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - URL_CACHE_MAX_AGE * 2)
+            .unwrap();
+
+        let fetched = fetch_url_cached(url, &cache_dir, |_| Ok("{\"type\":\"object\"}".into()));
+        assert_eq!(fetched.unwrap(), "{\"type\":\"object\"}");
+        assert_eq!(
+            fs::read_to_string(&cache_path).unwrap(),
+            "{\"type\":\"object\"}"
+        );
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_url_cache_falls_back_when_offline() {
+        let cache_dir = test_cache_dir("offline");
+        let url = "https://example.com/schema.json";
+        let cache_path = url_cache_path(&cache_dir, url);
+
+        let err = fetch_url_cached(url, &cache_dir, |_| Err("offline".into())).unwrap_err();
+        assert_eq!(err.to_string(), "offline");
+
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(&cache_path, "{\"type\":\"string\"}").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&cache_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - URL_CACHE_MAX_AGE * 2)
+            .unwrap();
+
+        let cached = fetch_url_cached(url, &cache_dir, |_| Err("offline".into()));
+        assert_eq!(cached.unwrap(), "{\"type\":\"string\"}");
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
 }
@@ -1,7 +1,8 @@
 use jsonschema::error::ValidationErrorKind;
 use jsonschema::paths::JSONPointer;
-use jsonschema::JSONSchema;
-use serde_json::Value;
+use jsonschema::{Draft, JSONSchema};
+use serde_json::{Map, Value};
+use std::fmt;
 use std::ops::{Deref, DerefMut};
 
 /// A validation error, modeled after `jsonschema::ValidationError`.
@@ -23,25 +24,261 @@ pub enum JsonSchemaError {
     /// The JSON schema isn't valid JSON.
     ParseError(serde_json::Error),
     /// The JSON schema isn't a valid schema.
-    ValidationError(ValidationError),
+    ValidationError(Box<ValidationError>),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at '{}' is invalid: {:?} (schema path '{}')",
+            self.instance, self.instance_path, self.kind, self.schema_path
+        )
+    }
+}
+
+impl fmt::Display for JsonSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParseError(e) => write!(f, "{}", e),
+            Self::ValidationError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// A JSON schema draft that a schema can be pinned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaDraft {
+    /// Draft 4.
+    Draft4,
+    /// Draft 6.
+    Draft6,
+    /// Draft 7.
+    Draft7,
+    /// Draft 2019-09.
+    Draft201909,
+    /// Draft 2020-12.
+    Draft202012,
+}
+
+impl SchemaDraft {
+    /// All drafts, from oldest to newest.
+    const ALL: [Self; 5] = [
+        Self::Draft4,
+        Self::Draft6,
+        Self::Draft7,
+        Self::Draft201909,
+        Self::Draft202012,
+    ];
+
+    /// Parses a draft from its name, e.g. `draft-07` or `2020-12`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|draft| draft.name() == name)
+            .ok_or_else(|| {
+                let names = Self::ALL.map(|draft| format!("\"{}\"", draft.name()));
+                format!(
+                    "unknown schema draft \"{}\", expected one of {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// The name of the draft.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Draft4 => "draft-04",
+            Self::Draft6 => "draft-06",
+            Self::Draft7 => "draft-07",
+            Self::Draft201909 => "2019-09",
+            Self::Draft202012 => "2020-12",
+        }
+    }
+
+    /// The meta-schema URI identifying the draft, in the form recognized by
+    /// `jsonschema`.
+    fn uri(self) -> &'static str {
+        match self {
+            Self::Draft4 => "http://json-schema.org/draft-04/schema#",
+            Self::Draft6 => "http://json-schema.org/draft-06/schema#",
+            Self::Draft7 => "http://json-schema.org/draft-07/schema#",
+            Self::Draft201909 => "https://json-schema.org/draft/2019-09/schema#",
+            Self::Draft202012 => "https://json-schema.org/draft/2020-12/schema#",
+        }
+    }
+
+    /// Identifies a draft from a `$schema` URI, ignoring the scheme and any
+    /// trailing empty fragment.
+    fn from_uri(uri: &str) -> Option<Self> {
+        let normalize = |uri: &str| {
+            let uri = uri.trim_end_matches('#');
+            uri.strip_prefix("https://")
+                .or_else(|| uri.strip_prefix("http://"))
+                .unwrap_or(uri)
+                .to_owned()
+        };
+        let uri = normalize(uri);
+
+        Self::ALL
+            .into_iter()
+            .find(|draft| normalize(draft.uri()) == uri)
+    }
+
+    /// The equivalent `jsonschema` draft.
+    fn to_draft(self) -> Draft {
+        match self {
+            Self::Draft4 => Draft::Draft4,
+            Self::Draft6 => Draft::Draft6,
+            Self::Draft7 => Draft::Draft7,
+            Self::Draft201909 => Draft::Draft201909,
+            Self::Draft202012 => Draft::Draft202012,
+        }
+    }
+
+    /// The draft that introduced a keyword, if it was introduced after
+    /// draft 4.
+    fn introducing(keyword: &str) -> Option<Self> {
+        match keyword {
+            "$id" | "const" | "contains" | "propertyNames" | "examples" => Some(Self::Draft6),
+            "if" | "then" | "else" | "$comment" | "contentMediaType" | "contentEncoding"
+            | "readOnly" | "writeOnly" => Some(Self::Draft7),
+            "$defs"
+            | "$anchor"
+            | "$recursiveRef"
+            | "$recursiveAnchor"
+            | "$vocabulary"
+            | "dependentRequired"
+            | "dependentSchemas"
+            | "unevaluatedProperties"
+            | "unevaluatedItems"
+            | "minContains"
+            | "maxContains"
+            | "contentSchema"
+            | "deprecated" => Some(Self::Draft201909),
+            "prefixItems" | "$dynamicRef" | "$dynamicAnchor" => Some(Self::Draft202012),
+            _ => None,
+        }
+    }
+
+    /// Pins a schema to the draft. Fails if the schema declares a different
+    /// draft through `$schema` or uses keywords introduced in a later draft.
+    /// Otherwise, `$schema` is set to the draft so that validation at both
+    /// compile time and runtime uses it.
+    pub fn pin(self, schema: &mut Value) -> Result<(), String> {
+        if let Some(declared) = schema.get("$schema").and_then(Value::as_str) {
+            if Self::from_uri(declared) != Some(self) {
+                return Err(format!(
+                    "schema declares `$schema` \"{}\", but is pinned to draft \"{}\"",
+                    declared,
+                    self.name()
+                ));
+            }
+        }
+
+        self.check_keywords(schema, "#")?;
+
+        if let Value::Object(object) = schema {
+            object.insert("$schema".to_owned(), Value::String(self.uri().to_owned()));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a schema and its subschemas use no keywords introduced in
+    /// a later draft.
+    fn check_keywords(self, schema: &Value, path: &str) -> Result<(), String> {
+        let object = match schema.as_object() {
+            Some(object) => object,
+            None => return Ok(()),
+        };
+
+        for (keyword, value) in object {
+            if let Some(introduced) = Self::introducing(keyword) {
+                if introduced > self {
+                    return Err(format!(
+                        "keyword `{}` at `{}` was introduced in draft \"{}\", but the schema is pinned to draft \"{}\"",
+                        keyword,
+                        path,
+                        introduced.name(),
+                        self.name()
+                    ));
+                }
+            }
+
+            let keyword_path = format!("{}/{}", path, keyword);
+
+            match keyword.as_str() {
+                "properties" | "patternProperties" | "$defs" | "definitions"
+                | "dependentSchemas" | "dependencies" => {
+                    self.check_keywords_in_map(value.as_object(), &keyword_path)?;
+                }
+                "items" | "prefixItems" | "allOf" | "anyOf" | "oneOf" => match value {
+                    Value::Array(values) => {
+                        for (index, value) in values.iter().enumerate() {
+                            self.check_keywords(value, &format!("{}/{}", keyword_path, index))?;
+                        }
+                    }
+                    value => self.check_keywords(value, &keyword_path)?,
+                },
+                "additionalProperties"
+                | "additionalItems"
+                | "not"
+                | "if"
+                | "then"
+                | "else"
+                | "contains"
+                | "propertyNames"
+                | "unevaluatedProperties"
+                | "unevaluatedItems"
+                | "contentSchema" => {
+                    self.check_keywords(value, &keyword_path)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the keywords of each subschema in a map of subschemas.
+    fn check_keywords_in_map(
+        self,
+        subschemas: Option<&Map<String, Value>>,
+        path: &str,
+    ) -> Result<(), String> {
+        for (name, subschema) in subschemas.into_iter().flatten() {
+            self.check_keywords(subschema, &format!("{}/{}", path, name))?;
+        }
+
+        Ok(())
+    }
 }
 
 /// A wrapper around `jsonschema::JSONSchema`.
 pub struct JsonSchema(JSONSchema);
 
 impl JsonSchema {
-    /// Parses a JSON schema, checking the validity of the schema.
-    pub fn parse(schema: &str) -> Result<Self, JsonSchemaError> {
+    /// Parses a JSON schema, checking the validity of the schema. If a draft
+    /// is given, the schema is compiled with that draft rather than the one
+    /// detected from the schema.
+    pub fn parse(schema: &str, draft: Option<SchemaDraft>) -> Result<Self, JsonSchemaError> {
         let schema_value: Value =
             serde_json::from_str(schema).map_err(JsonSchemaError::ParseError)?;
 
-        let schema_parsed = JSONSchema::compile(&schema_value).map_err(|e| {
-            JsonSchemaError::ValidationError(ValidationError {
-                instance: schema_value.clone(),
+        let mut options = JSONSchema::options();
+        if let Some(draft) = draft {
+            options.with_draft(draft.to_draft());
+        }
+
+        let schema_parsed = options.compile(&schema_value).map_err(|e| {
+            JsonSchemaError::ValidationError(Box::new(ValidationError {
+                instance: e.instance.into_owned(),
                 kind: e.kind,
                 instance_path: e.instance_path,
                 schema_path: e.schema_path,
-            })
+            }))
         })?;
 
         Ok(Self(schema_parsed))
@@ -30,9 +30,60 @@ macro_rules! impl_from_schema_primitive {
 
 impl_from_schema_primitive!(NullField, "null");
 impl_from_schema_primitive!(BooleanField, "boolean");
-impl_from_schema_primitive!(IntegerField, "integer");
-impl_from_schema_primitive!(NumberField, "number");
-impl_from_schema_primitive!(StringField, "string");
+
+impl FromSchema for IntegerField {
+    fn from_schema(value: &Value, _info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+        assert_value_type(value, "integer")?;
+
+        let default = value.get("default").map(ToOwned::to_owned);
+        let bounds = get_number_bounds(value)?;
+        let multiple_of = get_prop_number(value, "multipleOf")?;
+        let format = get_prop_str(value, "format")?.map(|s| s.to_owned());
+
+        Ok(Self {
+            default,
+            bounds,
+            multiple_of,
+            format,
+        })
+    }
+}
+
+impl FromSchema for NumberField {
+    fn from_schema(value: &Value, _info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+        assert_value_type(value, "number")?;
+
+        let default = value.get("default").map(ToOwned::to_owned);
+        let bounds = get_number_bounds(value)?;
+        let multiple_of = get_prop_number(value, "multipleOf")?;
+        let format = get_prop_str(value, "format")?.map(|s| s.to_owned());
+
+        Ok(Self {
+            default,
+            bounds,
+            multiple_of,
+            format,
+        })
+    }
+}
+
+impl FromSchema for StringField {
+    fn from_schema(value: &Value, _info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+        assert_value_type(value, "string")?;
+
+        let default = value.get("default").map(ToOwned::to_owned);
+        let format = get_prop_str(value, "format")?.map(|s| s.to_owned());
+        let min_length = get_prop_length(value, "minLength")?;
+        let max_length = get_prop_length(value, "maxLength")?;
+
+        Ok(Self {
+            default,
+            format,
+            min_length,
+            max_length,
+        })
+    }
+}
 
 impl FromSchema for ArrayField {
     fn from_schema(value: &Value, info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
@@ -48,13 +99,24 @@ impl FromSchema for ArrayField {
         };
         let items = Field::from_schema(items_value, &mut items_info)?;
         let default = value.get("default").map(ToOwned::to_owned);
+        let min_items = get_prop_length(value, "minItems")?;
+        let max_items = get_prop_length(value, "maxItems")?;
 
-        Ok(Self { items, default })
+        if let Some(keyword) = items.serde_with_keyword() {
+            return Err(format!("`{}` cannot be applied to array items", keyword).into());
+        }
+
+        Ok(Self {
+            items,
+            default,
+            min_items,
+            max_items,
+        })
     }
 }
 
 impl FromSchema for ObjectField {
-    fn from_schema(value: &Value, _info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+    fn from_schema(value: &Value, info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
         assert_value_type(value, "object")?;
 
         let empty_map = Map::new();
@@ -86,9 +148,53 @@ impl FromSchema for ObjectField {
             })
             .collect::<Result<IndexMap<_, _>, _>>()?;
 
+        // Only a schema gives additional properties a type. Booleans and the
+        // empty schema leave them untyped, so they are ignored.
+        let additional_properties = match value.get("additionalProperties") {
+            Some(additional @ Value::Object(obj)) if !obj.is_empty() => {
+                let mut values_info = FieldInfo {
+                    name: "value".to_owned(),
+                    description: None,
+                    required: true,
+                    subschema: false,
+                };
+                let values = Field::from_schema(additional, &mut values_info)?;
+
+                if let Some(keyword) = values.serde_with_keyword() {
+                    return Err(format!(
+                        "`{}` cannot be applied to additional properties of '{}'",
+                        keyword, info.name
+                    )
+                    .into());
+                }
+
+                Some(values)
+            }
+            _ => None,
+        };
+
+        let deny_unknown_fields = value.get("additionalProperties") == Some(&Value::Bool(false));
+
+        // Serde does not support denying unknown fields alongside flattened
+        // fields, whose keys it cannot tell apart from unknown ones.
+        if deny_unknown_fields {
+            if let Some((name, _)) = fields.iter().find(|(_, field)| field.flatten) {
+                return Err(format!(
+                    "`x-flatten` property '{}' cannot be used in '{}', which sets `additionalProperties` to `false`",
+                    name, info.name
+                )
+                .into());
+            }
+        }
+
         let default = value.get("default").map(ToOwned::to_owned);
 
-        Ok(Self { fields, default })
+        Ok(Self {
+            fields,
+            additional_properties,
+            deny_unknown_fields,
+            default,
+        })
     }
 }
 
@@ -96,19 +202,38 @@ impl FromSchema for EnumField {
     fn from_schema(value: &Value, _info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
         let variant_values = get_prop_array(value, "enum")?.ok_or("no enum variants specified")?;
 
+        let numeric = match variant_values.first() {
+            Some(Value::Number(_)) => true,
+            Some(Value::String(_)) | None => false,
+            Some(variant) => {
+                return Err(format!(
+                    "enum variants must be strings or numbers, found {}",
+                    variant
+                )
+                .into());
+            }
+        };
+
         let variants = variant_values
             .iter()
-            .map(|variant| {
-                variant
-                    .as_str()
-                    .map(|s| s.to_owned())
-                    .ok_or("enum variants must be strings")
+            .enumerate()
+            .map(|(index, variant)| match variant {
+                Value::String(s) if !numeric => Ok(s.to_owned()),
+                Value::Number(n) if numeric => Ok(n.to_string()),
+                _ => Err(format!(
+                    "enum variants must be either all strings or all numbers, found {} at index {}",
+                    variant, index
+                )),
             })
             .collect::<Result<Vec<_>, _>>()?;
 
         let default = value.get("default").map(ToOwned::to_owned);
 
-        Ok(Self { variants, default })
+        Ok(Self {
+            variants,
+            numeric,
+            default,
+        })
     }
 }
 
@@ -133,6 +258,10 @@ impl FromSchema for TupleField {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        if let Some(keyword) = items.iter().find_map(Field::serde_with_keyword) {
+            return Err(format!("`{}` cannot be applied to tuple items", keyword).into());
+        }
+
         let default = value.get("default").map(ToOwned::to_owned);
 
         Ok(Self { items, default })
@@ -141,16 +270,221 @@ impl FromSchema for TupleField {
 
 impl FromSchema for RefField {
     fn from_schema(value: &Value, _info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
-        let ref_path = get_prop_str(value, "$ref")?.ok_or("refs must specify `$ref` property")?;
-        let ty = RefType::from_path(ref_path)?;
+        let ty = match get_prop_str(value, "$ref")? {
+            Some(ref_path) => RefType::from_path(ref_path)?,
+            None => {
+                // Only the common self-recursive case of dynamic refs is
+                // supported, which behaves like a ref to the root object.
+                let (keyword, ref_path) = None
+                    .or(get_prop_str(value, "$recursiveRef")?.map(|path| ("$recursiveRef", path)))
+                    .or(get_prop_str(value, "$dynamicRef")?.map(|path| ("$dynamicRef", path)))
+                    .ok_or("refs must specify `$ref` property")?;
+
+                if ref_path != "#" {
+                    return Err(format!(
+                        "`{}` is only supported when referencing the root object (`#`), found '{}'",
+                        keyword, ref_path
+                    )
+                    .into());
+                }
+
+                RefType::Root
+            }
+        };
         let default = value.get("default").map(ToOwned::to_owned);
 
         Ok(Self { ty, default })
     }
 }
 
+impl FromSchema for ConstField {
+    fn from_schema(value: &Value, _info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+        let const_value = value
+            .get("const")
+            .map(ToOwned::to_owned)
+            .ok_or("constant must specify `const` property")?;
+
+        if let Some(ty) = get_type_name(value)? {
+            let matches_type = match ty {
+                "null" => const_value.is_null(),
+                "boolean" => const_value.is_boolean(),
+                "integer" => const_value.is_i64() || const_value.is_u64(),
+                "number" => const_value.is_number(),
+                "string" => const_value.is_string(),
+                "array" => const_value.is_array(),
+                "object" => const_value.is_object(),
+                _ => false,
+            };
+
+            if !matches_type {
+                return Err(format!(
+                    "constant value {} does not match the declared type `{}`",
+                    const_value, ty
+                )
+                .into());
+            }
+        }
+
+        let default = value.get("default").map(ToOwned::to_owned);
+
+        Ok(Self {
+            value: const_value,
+            default,
+        })
+    }
+}
+
+impl FromSchema for OneOfField {
+    fn from_schema(value: &Value, info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+        let (branch_values, mut variants) = parse_alternatives(value, "oneOf", info)?;
+
+        let (tag, tag_values) = match one_of_tag(&variants) {
+            Some((tag, tag_values)) => {
+                // The tag is represented by the enum itself, so it is removed
+                // from the variants' own fields.
+                for variant in &mut variants {
+                    if let FieldType::Object(object) = &mut *variant.ty {
+                        object.fields.shift_remove(&tag);
+                    }
+                }
+
+                (Some(tag), tag_values)
+            }
+            None => (None, Vec::new()),
+        };
+
+        name_alternatives(branch_values, &mut variants, &tag_values);
+        let default = value.get("default").map(ToOwned::to_owned);
+
+        Ok(Self {
+            variants,
+            tag,
+            tag_values,
+            default,
+        })
+    }
+}
+
+impl FromSchema for AnyOfField {
+    fn from_schema(value: &Value, info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+        let (branch_values, mut variants) = parse_alternatives(value, "anyOf", info)?;
+        name_alternatives(branch_values, &mut variants, &[]);
+        let default = value.get("default").map(ToOwned::to_owned);
+
+        Ok(Self { variants, default })
+    }
+}
+
+/// Parses the subschemas listed by a `oneOf` or `anyOf` keyword.
+fn parse_alternatives<'a>(
+    value: &'a Value,
+    keyword: &str,
+    info: &FieldInfo,
+) -> Result<(&'a Vec<Value>, Vec<Field>), SchemaStructError> {
+    let branch_values =
+        get_prop_array(value, keyword)?.ok_or(format!("no `{}` subschemas specified", keyword))?;
+
+    if branch_values.is_empty() {
+        return Err(format!("`{}` must have at least one subschema", keyword).into());
+    }
+
+    let variants = branch_values
+        .iter()
+        .enumerate()
+        .map(|(index, branch_value)| {
+            let mut branch_info = FieldInfo {
+                name: format!("{}{}", info.name, index),
+                description: None,
+                required: true,
+                subschema: false,
+            };
+            Field::from_schema(branch_value, &mut branch_info)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(serde_keyword) = variants.iter().find_map(Field::serde_with_keyword) {
+        return Err(format!(
+            "`{}` cannot be applied to `{}` subschemas",
+            serde_keyword, keyword
+        )
+        .into());
+    }
+
+    Ok((branch_values, variants))
+}
+
+/// Names each alternative after the enum variant that will wrap it, using
+/// its title, its tag value, the subschema it references or its type, in
+/// that order of preference.
+fn name_alternatives(branch_values: &[Value], variants: &mut [Field], tag_values: &[String]) {
+    let variant_names = branch_values
+        .iter()
+        .enumerate()
+        .map(|(index, branch_value)| {
+            None.or(get_prop_str(branch_value, "title").ok().flatten())
+                .or(tag_values.get(index).map(String::as_str))
+                .or(get_prop_str(branch_value, "$ref")
+                    .ok()
+                    .flatten()
+                    .and_then(|ref_path| ref_path.rsplit('/').next()))
+                .or(get_prop_str(branch_value, "type").ok().flatten())
+                .unwrap_or_default()
+                .to_owned()
+        })
+        .collect::<Vec<_>>();
+
+    for (variant, (variant_name, _)) in variants
+        .iter_mut()
+        .zip(renamed_enum_variants(&variant_names))
+    {
+        variant.info.name = variant_name;
+    }
+}
+
+/// Finds a property that discriminates between the subschemas of a `oneOf`,
+/// along with its value in each subschema. This is only possible when every
+/// subschema is an inline object with the property set to a distinct
+/// constant string. Refs to subschemas are not followed, since the tag
+/// property cannot be left out of types that are shared with other fields.
+fn one_of_tag(variants: &[Field]) -> Option<(String, Vec<String>)> {
+    let objects = variants
+        .iter()
+        .map(|variant| match &*variant.ty {
+            FieldType::Object(object) => Some(object),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let tag_value = |object: &ObjectField, name: &str| match object.fields.get(name) {
+        Some(field) => match &*field.ty {
+            FieldType::Const(ConstField {
+                value: Value::String(value),
+                ..
+            }) => Some(value.clone()),
+            _ => None,
+        },
+        None => None,
+    };
+
+    objects.first()?.fields.keys().find_map(|name| {
+        let tag_values = objects
+            .iter()
+            .map(|object| tag_value(object, name))
+            .collect::<Option<Vec<_>>>()?;
+        let distinct = tag_values.iter().collect::<HashSet<_>>().len() == tag_values.len();
+
+        distinct.then(|| (name.clone(), tag_values))
+    })
+}
+
 impl FromSchema for FieldType {
     fn from_schema(value: &Value, info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
+        // A type array such as `["string", "null"]` makes the field optional,
+        // even when it is required.
+        if is_nullable_type_array(value) {
+            info.required = false;
+        }
+
         Ok(match parse_value_type(value)? {
             ValueType::Null => Self::Null(NullField::from_schema(value, info)?),
             ValueType::Boolean => Self::Boolean(BooleanField::from_schema(value, info)?),
@@ -162,6 +496,9 @@ impl FromSchema for FieldType {
             ValueType::Enum => Self::Enum(EnumField::from_schema(value, info)?),
             ValueType::Tuple => Self::Tuple(TupleField::from_schema(value, info)?),
             ValueType::Ref => Self::Ref(RefField::from_schema(value, info)?),
+            ValueType::Const => Self::Const(ConstField::from_schema(value, info)?),
+            ValueType::OneOf => Self::OneOf(OneOfField::from_schema(value, info)?),
+            ValueType::AnyOf => Self::AnyOf(AnyOfField::from_schema(value, info)?),
         })
     }
 }
@@ -174,18 +511,47 @@ impl FromSchema for Field {
             ..info.clone()
         };
         let field_ty = FieldType::from_schema(value, &mut field_info)?;
+        let flatten = get_prop_bool(value, "x-flatten")?.unwrap_or(false);
+
+        if flatten && !matches!(field_ty, FieldType::Object(_) | FieldType::Ref(_)) {
+            return Err("`x-flatten` can only be applied to object properties".into());
+        }
+
+        let int_bool = get_prop_bool(value, "x-bool")?.unwrap_or(false);
+
+        if int_bool && !matches!(field_ty, FieldType::Integer(_)) {
+            return Err("`x-bool` can only be applied to integer properties".into());
+        }
+
+        let coerce = get_prop_bool(value, "x-coerce")?.unwrap_or(false);
+
+        if coerce && !matches!(field_ty, FieldType::Integer(_) | FieldType::Number(_)) {
+            return Err("`x-coerce` can only be applied to integer and number properties".into());
+        }
+
+        if int_bool && coerce {
+            return Err("`x-bool` and `x-coerce` cannot be combined".into());
+        }
 
         Ok(Self {
             info: field_info,
             ty: Box::new(field_ty),
+            flatten,
+            int_bool,
+            coerce,
+            key_required: info.required,
         })
     }
 }
 
 impl FromSchema for Subschema {
     fn from_schema(value: &Value, info: &mut FieldInfo) -> Result<Self, SchemaStructError> {
-        Ok(Self {
-            schema: Field::from_schema(value, info)?,
-        })
+        let schema = Field::from_schema(value, info)?;
+
+        if let Some(keyword) = schema.serde_with_keyword() {
+            return Err(format!("`{}` cannot be applied to subschemas", keyword).into());
+        }
+
+        Ok(Self { schema })
     }
 }
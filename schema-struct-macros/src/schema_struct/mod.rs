@@ -3,4 +3,4 @@ mod to_struct;
 mod types;
 mod util;
 
-pub use types::{SchemaStruct, SchemaStructConfig, SchemaStructDef};
+pub use types::{SchemaStruct, SchemaStructConfig, SchemaStructError};
@@ -1,8 +1,8 @@
 use super::types::*;
 use super::util::*;
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
-use serde_json::Value;
+use serde_json::{Number, Value};
 
 /// Generates Rust type definitions.
 pub trait ToStruct {
@@ -28,7 +28,7 @@ impl ToStruct for NullField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
         let field_ty = maybe_optional(quote!(()), info.required);
         let mut defs = Vec::new();
 
@@ -68,8 +68,10 @@ impl ToStruct for NullField {
         &self,
         value: Option<&Value>,
         info: &FieldInfo,
-        _ctx: &FieldContext,
+        ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         value
             .map(|default| {
                 default
@@ -87,7 +89,7 @@ impl ToStruct for BooleanField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
         let field_ty = maybe_optional(quote!(bool), info.required);
         let mut defs = Vec::new();
 
@@ -122,8 +124,10 @@ impl ToStruct for BooleanField {
         &self,
         value: Option<&Value>,
         info: &FieldInfo,
-        _ctx: &FieldContext,
+        ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         value
             .map(|default| {
                 default
@@ -141,8 +145,16 @@ impl ToStruct for IntegerField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
-        let field_ty = maybe_optional(quote!(i64), info.required);
+        check_format(
+            self.format.as_deref(),
+            INTEGER_FORMATS,
+            &info.name,
+            ctx.schema.strict,
+        )?;
+
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+        let inner_ty = self.rust_type();
+        let field_ty = maybe_optional(inner_ty.clone(), info.required);
         let mut defs = Vec::new();
 
         let field_default =
@@ -150,7 +162,7 @@ impl ToStruct for IntegerField {
                 .map(|default_value| {
                     let field_default = default_fn_name(&ctx.name_prefix, &info.name);
                     let field_default_ident = format_ident!("{}", field_default);
-                    let fn_return = maybe_optional(quote!(i64), info.required);
+                    let fn_return = maybe_optional(inner_ty, info.required);
 
                     defs.push(quote! {
                         fn #field_default_ident() -> #fn_return {
@@ -176,27 +188,149 @@ impl ToStruct for IntegerField {
         &self,
         value: Option<&Value>,
         info: &FieldInfo,
-        _ctx: &FieldContext,
+        ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         value
             .map(|default| {
-                default
-                    .as_i64()
-                    .ok_or("expected default value to be an integer".into())
-                    .map(|val| maybe_optional_value(quote!(#val), info.required))
+                if !default.is_i64() && !default.is_u64() {
+                    return Err("expected default value to be an integer".into());
+                }
+
+                check_default_bounds(default, &self.bounds)?;
+                check_default_multiple(default, self.multiple_of)?;
+
+                let out_of_range = || {
+                    format!(
+                        "default value {} is out of range for integer format `{}`",
+                        default,
+                        self.resolved_format()
+                    )
+                };
+                let default_value = match self.resolved_format() {
+                    "int32" => {
+                        let val = default.as_i64().and_then(|val| i32::try_from(val).ok());
+                        let val = val.ok_or_else(out_of_range)?;
+                        quote!(#val)
+                    }
+                    "uint32" => {
+                        let val = default.as_u64().and_then(|val| u32::try_from(val).ok());
+                        let val = val.ok_or_else(out_of_range)?;
+                        quote!(#val)
+                    }
+                    "uint64" => {
+                        let val = default.as_u64().ok_or_else(out_of_range)?;
+                        quote!(#val)
+                    }
+                    _ => {
+                        let val = default.as_i64().ok_or_else(out_of_range)?;
+                        quote!(#val)
+                    }
+                };
+
+                Ok(maybe_optional_value(default_value, info.required))
             })
             .invert()
     }
 }
 
+impl IntegerField {
+    /// Gets the Rust type used to represent the integer, depending on its
+    /// format.
+    fn rust_type(&self) -> TokenStream {
+        match self.resolved_format() {
+            "int32" => quote!(i32),
+            "uint32" => quote!(u32),
+            "uint64" => quote!(u64),
+            _ => quote!(i64),
+        }
+    }
+
+    /// Resolves the integer's format to one of `int32`, `int64`, `uint32` or
+    /// `uint64`. Integers with a non-negative minimum use the unsigned
+    /// variant of their format, and unrecognized formats fall back to
+    /// `int64`.
+    fn resolved_format(&self) -> &'static str {
+        let unsigned = self
+            .bounds
+            .minimum
+            .as_ref()
+            .and_then(Number::as_f64)
+            .is_some_and(|minimum| minimum >= 0.0);
+
+        match (self.format.as_deref(), unsigned) {
+            (Some("int32"), false) => "int32",
+            (Some("int32") | Some("uint32"), _) => "uint32",
+            (Some("uint64"), _) | (_, true) => "uint64",
+            _ => "int64",
+        }
+    }
+
+    /// Generates a `bool` field for an integer field marked with `x-bool`.
+    fn to_int_bool_struct(
+        &self,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<FieldDef, SchemaStructError> {
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+        let field_ty = maybe_optional(quote!(bool), info.required);
+        let mut defs = Vec::new();
+
+        let field_default = self
+            .default
+            .as_ref()
+            .map(|default| match default.as_i64() {
+                Some(0) => Ok(maybe_optional_value(quote!(false), info.required)),
+                Some(1) => Ok(maybe_optional_value(quote!(true), info.required)),
+                _ => Err(format!(
+                    "expected default value of an `x-bool` field to be 0 or 1, found {}",
+                    default
+                )),
+            })
+            .invert()?
+            .map(|default_value| {
+                let field_default = default_fn_name(&ctx.name_prefix, &info.name);
+                let field_default_ident = format_ident!("{}", field_default);
+                let fn_return = maybe_optional(quote!(bool), info.required);
+
+                defs.push(quote! {
+                    fn #field_default_ident() -> #fn_return {
+                        #default_value
+                    }
+                });
+
+                field_default
+            });
+
+        Ok(FieldDef {
+            field_name,
+            field_rename,
+            field_default,
+            field_doc: info.description.clone(),
+            field_ty,
+            defs,
+            defs_doc: Vec::new(),
+        })
+    }
+}
+
 impl ToStruct for NumberField {
     fn to_struct(
         &self,
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
-        let field_ty = maybe_optional(quote!(f64), info.required);
+        check_format(
+            self.format.as_deref(),
+            NUMBER_FORMATS,
+            &info.name,
+            ctx.schema.strict,
+        )?;
+
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+        let inner_ty = self.rust_type();
+        let field_ty = maybe_optional(inner_ty.clone(), info.required);
         let mut defs = Vec::new();
 
         let field_default =
@@ -204,7 +338,7 @@ impl ToStruct for NumberField {
                 .map(|default_value| {
                     let field_default = default_fn_name(&ctx.name_prefix, &info.name);
                     let field_default_ident = format_ident!("{}", field_default);
-                    let fn_return = maybe_optional(quote!(f64), info.required);
+                    let fn_return = maybe_optional(inner_ty, info.required);
 
                     defs.push(quote! {
                         fn #field_default_ident() -> #fn_return {
@@ -230,27 +364,75 @@ impl ToStruct for NumberField {
         &self,
         value: Option<&Value>,
         info: &FieldInfo,
-        _ctx: &FieldContext,
+        ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         value
             .map(|default| {
-                default
+                let val = default
                     .as_f64()
-                    .ok_or("expected default value to be a number".into())
-                    .map(|val| maybe_optional_value(quote!(#val), info.required))
+                    .ok_or("expected default value to be a number")?;
+                check_default_bounds(default, &self.bounds)?;
+                check_default_multiple(default, self.multiple_of)?;
+                let default_value = match self.format.as_deref() {
+                    Some("float") => {
+                        let val = val as f32;
+                        if val.is_infinite() {
+                            return Err(format!(
+                                "default value {} is out of range for number format `float`",
+                                default
+                            )
+                            .into());
+                        }
+                        quote!(#val)
+                    }
+                    _ => quote!(#val),
+                };
+                Ok(maybe_optional_value(default_value, info.required))
             })
             .invert()
     }
 }
 
+impl NumberField {
+    /// Gets the Rust type used to represent the number, depending on its
+    /// format.
+    fn rust_type(&self) -> TokenStream {
+        match self.format.as_deref() {
+            Some("float") => quote!(f32),
+            _ => quote!(f64),
+        }
+    }
+}
+
 impl ToStruct for StringField {
     fn to_struct(
         &self,
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
-        let field_ty = maybe_optional(quote!(String), info.required);
+        check_format(
+            self.format.as_deref(),
+            STRING_FORMATS,
+            &info.name,
+            ctx.schema.strict,
+        )?;
+
+        // Arbitrary values cannot be generated for the types of formatted
+        // strings.
+        if ctx.schema.generate_tests && self.format_type(ctx).is_some() {
+            return Err(format!(
+                "round-trip tests do not support field '{}' of format '{}'",
+                info.name,
+                self.format.as_deref().unwrap_or_default()
+            )
+            .into());
+        }
+
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+        let inner_ty = self.rust_type(ctx);
+        let field_ty = maybe_optional(inner_ty.clone(), info.required);
         let mut defs = Vec::new();
 
         let field_default =
@@ -258,7 +440,7 @@ impl ToStruct for StringField {
                 .map(|default_value| {
                     let field_default = default_fn_name(&ctx.name_prefix, &info.name);
                     let field_default_ident = format_ident!("{}", field_default);
-                    let fn_return = maybe_optional(quote!(String), info.required);
+                    let fn_return = maybe_optional(inner_ty, info.required);
 
                     defs.push(quote! {
                         fn #field_default_ident() -> #fn_return {
@@ -284,26 +466,111 @@ impl ToStruct for StringField {
         &self,
         value: Option<&Value>,
         info: &FieldInfo,
-        _ctx: &FieldContext,
+        ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         value
             .map(|default| {
-                default
+                let val = default
                     .as_str()
-                    .ok_or("expected default value to be a string".into())
-                    .map(|val| maybe_optional_value(quote!(#val.to_owned()), info.required))
+                    .ok_or("expected default value to be a string")?;
+
+                let default_value = match self.format.as_deref() {
+                    Some(format @ ("date-time" | "date" | "time")) if cfg!(feature = "chrono") => {
+                        check_default_format(format, val)?;
+                        let ty = self.rust_type(ctx);
+                        quote!(<#ty as ::core::str::FromStr>::from_str(#val).unwrap())
+                    }
+                    Some("uuid") if cfg!(feature = "uuid") => {
+                        check_default_uuid(val)?;
+                        let ty = self.rust_type(ctx);
+                        quote!(<#ty as ::core::str::FromStr>::from_str(#val).unwrap())
+                    }
+                    _ => quote!(#val.to_owned()),
+                };
+
+                Ok(maybe_optional_value(default_value, info.required))
             })
             .invert()
     }
 }
 
+impl StringField {
+    /// Gets the Rust type used to represent the string, depending on its
+    /// format.
+    fn rust_type(&self, ctx: &FieldContext) -> TokenStream {
+        self.format_type(ctx).unwrap_or(quote!(String))
+    }
+
+    /// Gets the Rust type used in place of `String` for the string's format,
+    /// if any.
+    fn format_type(&self, ctx: &FieldContext) -> Option<TokenStream> {
+        let internal_path = &ctx.internal_path;
+
+        match self.format.as_deref() {
+            Some("date-time") if cfg!(feature = "chrono") => {
+                Some(quote!(#internal_path::chrono::DateTime<#internal_path::chrono::Utc>))
+            }
+            Some("date") if cfg!(feature = "chrono") => {
+                Some(quote!(#internal_path::chrono::NaiveDate))
+            }
+            Some("time") if cfg!(feature = "chrono") => {
+                Some(quote!(#internal_path::chrono::NaiveTime))
+            }
+            Some("uuid") if cfg!(feature = "uuid") => Some(quote!(#internal_path::uuid::Uuid)),
+            _ => None,
+        }
+    }
+
+    /// Gets the bounds on the string's length to check when deserializing,
+    /// if any. Strings represented by types other than `String` are not
+    /// checked.
+    fn length_bounds(&self, ctx: &FieldContext) -> Option<(TokenStream, TokenStream)> {
+        if (self.min_length.is_none() && self.max_length.is_none())
+            || self.format_type(ctx).is_some()
+        {
+            return None;
+        }
+
+        let bound = |length: Option<u64>| match length {
+            Some(length) => {
+                let length = length as usize;
+                quote!(Some(#length))
+            }
+            None => quote!(None),
+        };
+
+        Some((bound(self.min_length), bound(self.max_length)))
+    }
+
+    /// Gets a `proptest` strategy generating strings within the string's
+    /// length bounds, for use in round-trip tests.
+    fn length_strategy(&self, required: bool) -> String {
+        let min_length = self.min_length.unwrap_or(0);
+        let max_length = self.max_length.unwrap_or(min_length + 32);
+        let strategy = format!(
+            "::proptest::strategy::Strategy::prop_map(\
+             ::proptest::collection::vec(::proptest::char::any(), {}..={}), \
+             |chars| chars.into_iter().collect::<String>())",
+            min_length, max_length
+        );
+
+        if required {
+            strategy
+        } else {
+            format!("::proptest::option::of({})", strategy)
+        }
+    }
+}
+
 impl ToStruct for ArrayField {
     fn to_struct(
         &self,
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
 
         let inner_name_prefix = format!("{}Items", ctx.name_prefix);
         let inner_ctx = FieldContext {
@@ -313,7 +580,17 @@ impl ToStruct for ArrayField {
 
         let inner_field_def = self.items.to_struct(info, &inner_ctx)?;
         let inner_field_ty = &inner_field_def.field_ty;
-        let field_ty = maybe_optional(quote!(Vec<#inner_field_ty>), info.required);
+        let array_ty = if let Some(len) = self.fixed_len() {
+            quote!([#inner_field_ty; #len])
+        } else if let Some(capacity) = ctx.schema.small_vec_capacity {
+            let internal_path = &ctx.internal_path;
+            quote!(#internal_path::smallvec::SmallVec<[#inner_field_ty; #capacity]>)
+        } else if ctx.schema.boxed_slices {
+            quote!(Box<[#inner_field_ty]>)
+        } else {
+            quote!(Vec<#inner_field_ty>)
+        };
+        let field_ty = maybe_optional(array_ty.clone(), info.required);
         let mut defs = inner_field_def.defs;
 
         let field_default =
@@ -321,7 +598,7 @@ impl ToStruct for ArrayField {
                 .map(|default_value| {
                     let field_default = default_fn_name(&ctx.name_prefix, &info.name);
                     let field_default_ident = format_ident!("{}", field_default);
-                    let fn_return = maybe_optional(quote!(Vec<#inner_field_ty>), info.required);
+                    let fn_return = maybe_optional(array_ty, info.required);
 
                     defs.push(quote! {
                         fn #field_default_ident() -> #fn_return {
@@ -349,6 +626,8 @@ impl ToStruct for ArrayField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         let inner_name_prefix = format!("{}Items", ctx.name_prefix);
         let inner_ctx = FieldContext {
             name_prefix: inner_name_prefix,
@@ -360,6 +639,15 @@ impl ToStruct for ArrayField {
                 default
                     .as_array()
                     .ok_or("expected default value to be an array".into())
+                    .and_then(|values| match self.fixed_len() {
+                        Some(len) if values.len() != len => Err(format!(
+                            "expected default value to be an array of {} items, found {}",
+                            len,
+                            values.len()
+                        )
+                        .into()),
+                        _ => Ok(values),
+                    })
                     .and_then(|values| {
                         values
                             .iter()
@@ -371,7 +659,20 @@ impl ToStruct for ArrayField {
                                     .map(|default| default.clone().unwrap_or(quote!(None)))
                                     .collect::<Vec<_>>();
 
-                                maybe_optional_value(quote!(vec![#(#defaults),*]), info.required)
+                                let array_value = if self.fixed_len().is_some() {
+                                    quote!([#(#defaults),*])
+                                } else if ctx.schema.small_vec_capacity.is_some() {
+                                    let internal_path = &ctx.internal_path;
+                                    quote! {
+                                        #internal_path::smallvec::SmallVec::from_vec(vec![#(#defaults),*])
+                                    }
+                                } else if ctx.schema.boxed_slices {
+                                    quote!(vec![#(#defaults),*].into_boxed_slice())
+                                } else {
+                                    quote!(vec![#(#defaults),*])
+                                };
+
+                                maybe_optional_value(array_value, info.required)
                             })
                     })
             })
@@ -379,13 +680,36 @@ impl ToStruct for ArrayField {
     }
 }
 
+impl ArrayField {
+    /// The largest fixed-size array supported by serde.
+    const MAX_FIXED_LEN: u64 = 32;
+
+    /// Gets the length of the array if `minItems` and `maxItems` are equal,
+    /// in which case it is represented by a fixed-size array rather than a
+    /// `Vec`. Lengths beyond what serde supports for arrays are not fixed.
+    pub fn fixed_len(&self) -> Option<usize> {
+        match (self.min_items, self.max_items) {
+            (Some(min_items), Some(max_items))
+                if min_items == max_items && max_items <= Self::MAX_FIXED_LEN =>
+            {
+                Some(max_items as usize)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl ToStruct for ObjectField {
     fn to_struct(
         &self,
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
+        if let Some(values) = self.map_values(info, ctx) {
+            return self.map_to_struct(values, info, ctx);
+        }
+
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
         let struct_name_without_prefix = renamed_struct(&info.name);
         let struct_name = format!("{}{}", ctx.name_prefix, struct_name_without_prefix);
         let struct_ident = format_ident!("{}", struct_name);
@@ -403,49 +727,26 @@ impl ToStruct for ObjectField {
             ..ctx.clone()
         };
 
-        let (mut defs, mut defs_doc, field_tokens, field_tokens_doc) =
-            self.fields.values().try_fold(
-                (Vec::new(), Vec::new(), Vec::new(), Vec::new()),
-                |(mut defs, mut defs_doc, mut field_tokens, mut field_tokens_doc), inner_field| {
-                    let FieldDef {
-                        field_name: inner_field_name,
-                        field_rename: inner_field_rename,
-                        field_default: inner_field_default,
-                        field_doc: inner_field_doc,
-                        field_ty: inner_field_ty,
-                        defs: inner_defs,
-                        defs_doc: inner_defs_doc,
-                    } = inner_field.to_struct(info, &inner_ctx)?;
-
-                    defs.extend(inner_defs);
-                    defs_doc.extend(inner_defs_doc);
-
-                    let doc_attr = doc_attribute(inner_field_doc.as_deref());
-                    let renamed_attr = rename_attribute(inner_field_rename.as_deref());
-                    let default_attr = default_attribute(inner_field_default.as_deref());
-
-                    let inner_field_ident = format_ident!("{}", inner_field_name);
-
-                    field_tokens.push(quote! {
-                        #doc_attr
-                        #renamed_attr
-                        #default_attr
-                        pub #inner_field_ident: #inner_field_ty,
-                    });
-
-                    field_tokens_doc.push(quote! {
-                        #doc_attr
-                        pub #inner_field_ident: #inner_field_ty,
-                    });
+        let mut fields = StructFields::new();
+        for inner_field in self.fields.values() {
+            fields.push_field(inner_field, info, ctx, &inner_ctx)?;
+        }
+        if let Some(values) = &self.additional_properties {
+            fields.push_additional_properties(self, values, info, ctx, &inner_ctx)?;
+        }
 
-                    Result::<_, SchemaStructError>::Ok((
-                        defs,
-                        defs_doc,
-                        field_tokens,
-                        field_tokens_doc,
-                    ))
-                },
-            )?;
+        let StructFields {
+            mut defs,
+            mut defs_doc,
+            field_tokens,
+            field_tokens_doc,
+            wire_name_arms,
+            de_fields,
+            default_fields,
+            debug_fields,
+            new_params,
+            new_fields,
+        } = fields;
 
         let field_default =
             self.parse_default(self.default.as_ref(), info, ctx)?
@@ -464,39 +765,147 @@ impl ToStruct for ObjectField {
                 });
 
         let doc_attr = doc_attribute(info.description.as_deref());
-
-        defs.push(quote! {
-            #doc_attr
-            #[derive(#internal_path::Serialize, #internal_path::Deserialize, Debug, Clone, PartialEq)]
-            #vis struct #struct_ident {
-                #(#field_tokens)*
+        let arbitrary_attr = arbitrary_attribute(ctx.schema.generate_tests);
+        let rename_all_attr = ctx
+            .schema
+            .rename_all
+            .map(|rule| quote!(#[serde(rename_all = #rule)]));
+
+        let deny_unknown_fields = self.deny_unknown_fields;
+        let deny_unknown_attr = deny_unknown_fields.then(|| quote!(#[serde(deny_unknown_fields)]));
+        let deny_unknown_check = deny_unknown_fields.then(|| {
+            let known_keys = self.fields.keys();
+
+            quote! {
+                #internal_path::de::deny_unknown_fields::<D::Error>(&map, &[#(#known_keys),*])?;
             }
         });
 
         defs.push(quote! {
             impl #struct_ident {
-                /// Deserializes a JSON string into this type.
-                pub fn from_str(json: &str) -> #internal_path::Result<Self> {
-                    #internal_path::deserialize(json)
+                /// Gets the JSON key that a field is serialized as, given the
+                /// name of the field in Rust.
+                pub fn wire_name(rust_field: &str) -> Option<&'static str> {
+                    match rust_field {
+                        #(#wire_name_arms)*
+                        _ => None,
+                    }
+                }
+            }
+        });
+
+        if ctx.schema.constructor {
+            defs.push(quote! {
+                impl #struct_ident {
+                    /// Creates a value from its required fields, initializing
+                    /// every other field to its default value or `None`.
+                    #[allow(clippy::too_many_arguments)]
+                    pub fn new(#(#new_params),*) -> Self {
+                        Self {
+                            #(#new_fields)*
+                        }
+                    }
                 }
+            });
+        }
+
+        let default_fields = default_fields.filter(|_| ctx.schema.default_impl);
+        // A `Default` implementation generated from the schema takes the
+        // place of a derived one.
+        let fixed_derives = if default_fields.is_some() {
+            &["Debug", "Clone", "PartialEq", "Default"][..]
+        } else {
+            &["Debug", "Clone", "PartialEq"][..]
+        };
 
-                /// Serializes this type into a JSON string.
-                pub fn to_str(&self) -> #internal_path::Result<String> {
-                    #internal_path::serialize(self)
+        if let Some(fields) = default_fields {
+            defs.push(quote! {
+                impl ::core::default::Default for #struct_ident {
+                    fn default() -> Self {
+                        Self {
+                            #(#fields)*
+                        }
+                    }
                 }
+            });
+        }
 
-                /// Deserializes a JSON value into this type.
-                pub fn from_value(value: &#internal_path::Value) -> #internal_path::Result<Self> {
-                    #internal_path::deserialize_from_value(value.to_owned())
+        let serialize_derive = ctx
+            .schema
+            .serde
+            .serialize()
+            .then(|| quote!(#internal_path::Serialize,));
+        let deserialize_derive = if !ctx.schema.serde.deserialize() {
+            quote!()
+        } else if ctx.schema.custom_serde {
+            let type_name = struct_ident.to_string();
+
+            defs.push(quote! {
+                impl<'de> #internal_path::Deserialize<'de> for #struct_ident {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: #internal_path::Deserializer<'de>,
+                    {
+                        let map = #internal_path::de::object(deserializer, #type_name)?;
+                        #deny_unknown_check
+
+                        Ok(Self {
+                            #(#de_fields)*
+                        })
+                    }
                 }
+            });
+
+            quote!()
+        } else {
+            quote!(#internal_path::Deserialize,)
+        };
 
-                /// Serializes this type into a JSON value.
-                pub fn to_value(&self) -> #internal_path::Result<#internal_path::Value> {
-                    #internal_path::serialize_to_value(self)
+        let has_number_fields = self
+            .fields
+            .values()
+            .chain(&self.additional_properties)
+            .any(|field| holds_floats(&field.ty, ctx, &mut Vec::new()));
+        let debug_derive = if ctx.schema.float_precision.is_some() && has_number_fields {
+            let debug_struct_name = struct_ident.to_string();
+
+            defs.push(quote! {
+                impl ::core::fmt::Debug for #struct_ident {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.debug_struct(#debug_struct_name)
+                            #(#debug_fields)*
+                            .finish()
+                    }
                 }
+            });
+
+            quote!()
+        } else {
+            quote!(Debug,)
+        };
+
+        let extra_derives = extra_derives(&ctx.schema.derives, fixed_derives);
+
+        defs.push(quote! {
+            #doc_attr
+            #[derive(#serialize_derive #deserialize_derive #debug_derive Clone, PartialEq #extra_derives)]
+            #arbitrary_attr
+            #rename_all_attr
+            #deny_unknown_attr
+            #vis struct #struct_ident {
+                #(#field_tokens)*
             }
         });
 
+        defs.push(impl_serde_methods(
+            &struct_ident,
+            internal_path,
+            ctx.schema.serde,
+            &ctx.schema.method_names,
+            None,
+            None,
+        ));
+
         defs_doc.push(quote! {
             #doc_attr
             #vis struct #struct_ident {
@@ -521,6 +930,22 @@ impl ToStruct for ObjectField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
+        if let Some(values) = self.map_values(info, ctx) {
+            let values_ctx = self.inner_ctx(info, ctx);
+
+            return value
+                .map(|default| {
+                    default
+                        .as_object()
+                        .ok_or("expected default value to be an object".into())
+                        .and_then(|entries| map_default(values, entries, info, &values_ctx))
+                        .map(|map_value| maybe_optional_value(map_value, info.required))
+                })
+                .invert();
+        }
+
         let struct_name_without_prefix = renamed_struct(&info.name);
         let struct_name = format!("{}{}", ctx.name_prefix, struct_name_without_prefix);
         let struct_ident = format_ident!("{}", struct_name);
@@ -544,7 +969,7 @@ impl ToStruct for ObjectField {
                         self.fields
                             .iter()
                             .map(|(field_name, field)| {
-                                let (renamed_field_name, _) = renamed_field(field_name);
+                                let (renamed_field_name, _) = renamed_field(field_name, ctx.schema.raw_idents);
 
                                 match values.get(field_name) {
                                     Some(field_value) => {
@@ -577,6 +1002,16 @@ impl ToStruct for ObjectField {
                                     quote!(#field_ident: #value_tokens,)
                                 })
                             })
+                            .chain(self.additional_properties.as_ref().map(|additional| {
+                                // Properties not declared by the schema are
+                                // collected into the catch-all map.
+                                let entries = values
+                                    .iter()
+                                    .filter(|(key, _)| !self.fields.contains_key(*key));
+
+                                map_default(additional, entries, info, &inner_ctx)
+                                    .map(|map_value| quote!(additional_properties: #map_value,))
+                            }))
                             .collect::<Result<Vec<_>, _>>()
                             .map(|defaults| {
                                 maybe_optional_value(
@@ -594,50 +1029,94 @@ impl ToStruct for ObjectField {
     }
 }
 
-impl ToStruct for EnumField {
-    fn to_struct(
-        &self,
-        info: &FieldInfo,
-        ctx: &FieldContext,
-    ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
-        let enum_name_without_prefix = renamed_enum(&info.name);
-        let enum_name = format!("{}{}", ctx.name_prefix, enum_name_without_prefix);
-        let enum_ident = format_ident!("{}", enum_name);
-        let vis = &ctx.vis;
-        let internal_path = &ctx.internal_path;
-        let field_ty = maybe_optional(quote!(#enum_ident), info.required);
+impl ObjectField {
+    /// Gets the type of the object's values, if the object is represented as
+    /// a map. The root object is always represented as a struct.
+    fn map_values(&self, info: &FieldInfo, ctx: &FieldContext) -> Option<&Field> {
+        let is_root = ctx.name_prefix.is_empty() && !info.subschema;
 
-        let (variant_tokens, variant_tokens_doc) = self.variants.iter().fold(
-            (Vec::new(), Vec::new()),
-            |(mut variant_tokens, mut variant_tokens_doc), variant| {
-                let (variant_name, variant_rename) = renamed_enum_variant(variant);
-                let variant_ident = format_ident!("{}", variant_name);
+        self.additional_properties
+            .as_ref()
+            .filter(|_| self.is_map() && !is_root)
+    }
 
-                let renamed_attr = rename_attribute(variant_rename.as_deref());
+    /// Gets the keys of the properties the object declares, including those of
+    /// the objects flattened into it, which take their properties from the
+    /// same JSON object. Refs already visited are skipped.
+    fn known_keys(&self, ctx: &FieldContext, visited: &mut Vec<String>) -> Vec<String> {
+        self.fields
+            .iter()
+            .flat_map(|(key, field)| {
+                if !field.flatten {
+                    return vec![key.clone()];
+                }
 
-                variant_tokens.push(quote! {
-                    #renamed_attr
-                    #variant_ident,
-                });
+                let flattened = match &*field.ty {
+                    FieldType::Object(object_field) => Some(object_field),
+                    FieldType::Ref(ref_field)
+                        if !visited.iter().any(|key| key == ref_field.ty.type_key()) =>
+                    {
+                        visited.push(ref_field.ty.type_key().to_owned());
+
+                        match &ref_field.ty {
+                            RefType::Root => Some(&ctx.schema.root),
+                            RefType::Subschema(subschema_name) => ctx
+                                .schema
+                                .subschemas
+                                .get(subschema_name)
+                                .and_then(|subschema| match &*subschema.schema.ty {
+                                    FieldType::Object(object_field) => Some(object_field),
+                                    _ => None,
+                                }),
+                        }
+                    }
+                    _ => None,
+                };
 
-                variant_tokens_doc.push(quote! {
-                    #variant_ident,
-                });
+                flattened
+                    .map(|object_field| object_field.known_keys(ctx, visited))
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
 
-                (variant_tokens, variant_tokens_doc)
-            },
-        );
+    /// Gets the context in which the object's inner types are generated.
+    fn inner_ctx<'a>(&self, info: &FieldInfo, ctx: &FieldContext<'a>) -> FieldContext<'a> {
+        let inner_name_prefix = if ctx.name_prefix.is_empty() {
+            info.name.clone()
+        } else {
+            format!("{}{}", ctx.name_prefix, renamed_struct(&info.name))
+        };
 
-        let mut defs = Vec::new();
-        let mut defs_doc = Vec::new();
+        FieldContext {
+            name_prefix: inner_name_prefix,
+            ..ctx.clone()
+        }
+    }
+
+    /// Generates a map field for an object with typed additional properties
+    /// and no declared properties.
+    fn map_to_struct(
+        &self,
+        values: &Field,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<FieldDef, SchemaStructError> {
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+
+        let values_def = values.to_struct(info, &self.inner_ctx(info, ctx))?;
+        let values_ty = &values_def.field_ty;
+        let map_path = ctx.schema.map_path();
+        let map_ty = quote!(#map_path<String, #values_ty>);
+        let field_ty = maybe_optional(map_ty.clone(), info.required);
+        let mut defs = values_def.defs;
 
         let field_default =
             self.parse_default(self.default.as_ref(), info, ctx)?
                 .map(|default_value| {
                     let field_default = default_fn_name(&ctx.name_prefix, &info.name);
                     let field_default_ident = format_ident!("{}", field_default);
-                    let fn_return = maybe_optional(quote!(#enum_ident), info.required);
+                    let fn_return = maybe_optional(map_ty, info.required);
 
                     defs.push(quote! {
                         fn #field_default_ident() -> #fn_return {
@@ -648,47 +1127,6 @@ impl ToStruct for EnumField {
                     field_default
                 });
 
-        let doc_attr = doc_attribute(info.description.as_deref());
-
-        defs.push(quote! {
-            #doc_attr
-            #[derive(#internal_path::Serialize, #internal_path::Deserialize, Debug, Clone, Copy, PartialEq)]
-            #vis enum #enum_ident {
-                #(#variant_tokens)*
-            }
-        });
-
-        defs.push(quote! {
-            impl #enum_ident {
-                /// Deserializes a JSON string into this type.
-                pub fn from_str(json: &str) -> #internal_path::Result<Self> {
-                    #internal_path::deserialize(json)
-                }
-
-                /// Serializes this type into a JSON string.
-                pub fn to_str(&self) -> #internal_path::Result<String> {
-                    #internal_path::serialize(self)
-                }
-
-                /// Deserializes a JSON value into this type.
-                pub fn from_value(value: &#internal_path::Value) -> #internal_path::Result<Self> {
-                    #internal_path::deserialize_from_value(value.to_owned())
-                }
-
-                /// Serializes this type into a JSON value.
-                pub fn to_value(&self) -> #internal_path::Result<#internal_path::Value> {
-                    #internal_path::serialize_to_value(self)
-                }
-            }
-        });
-
-        defs_doc.push(quote! {
-            #doc_attr
-            #vis enum #enum_ident {
-                #(#variant_tokens_doc)*
-            }
-        });
-
         Ok(FieldDef {
             field_name,
             field_rename,
@@ -696,45 +1134,726 @@ impl ToStruct for EnumField {
             field_doc: info.description.clone(),
             field_ty,
             defs,
-            defs_doc,
+            defs_doc: values_def.defs_doc,
         })
     }
+}
 
-    fn parse_default(
-        &self,
-        value: Option<&Value>,
-        info: &FieldInfo,
-        ctx: &FieldContext,
-    ) -> Result<Option<TokenStream>, SchemaStructError> {
-        let enum_name_without_prefix = renamed_enum(&info.name);
-        let enum_name = format!("{}{}", ctx.name_prefix, enum_name_without_prefix);
-        let enum_ident = format_ident!("{}", enum_name);
+/// The generated pieces of an object's struct, accumulated one field at a
+/// time.
+struct StructFields {
+    /// Type definitions used by the fields.
+    defs: Vec<TokenStream>,
+    /// Simplified type definitions to be used in documentation.
+    defs_doc: Vec<TokenStream>,
+    /// The struct's field declarations.
+    field_tokens: Vec<TokenStream>,
+    /// Simplified field declarations to be used in documentation.
+    field_tokens_doc: Vec<TokenStream>,
+    /// The match arms mapping Rust field names to JSON keys.
+    wire_name_arms: Vec<TokenStream>,
+    /// The field initializers of a custom `Deserialize` implementation.
+    de_fields: Vec<TokenStream>,
+    /// The values of the fields in a `Default` implementation, if every
+    /// field can be defaulted.
+    default_fields: Option<Vec<TokenStream>>,
+    /// The fields in a `Debug` implementation formatting numbers with a
+    /// fixed precision.
+    debug_fields: Vec<TokenStream>,
+    /// The parameters of a constructor taking the required fields.
+    new_params: Vec<TokenStream>,
+    /// The values the constructor initializes every field to.
+    new_fields: Vec<TokenStream>,
+}
 
-        value
-            .map(|default| {
-                default
-                    .as_str()
-                    .ok_or("expected default value to be an enum variant string".into())
-                    .map(|variant| {
-                        let (variant_name, _) = renamed_enum_variant(variant);
-                        let variant_ident = format_ident!("{}", variant_name);
-                        maybe_optional_value(quote!(#enum_ident::#variant_ident), info.required)
-                    })
-            })
-            .invert()
+impl StructFields {
+    /// Creates an empty set of fields.
+    fn new() -> Self {
+        Self {
+            defs: Vec::new(),
+            defs_doc: Vec::new(),
+            field_tokens: Vec::new(),
+            field_tokens_doc: Vec::new(),
+            wire_name_arms: Vec::new(),
+            de_fields: Vec::new(),
+            default_fields: Some(Vec::new()),
+            debug_fields: Vec::new(),
+            new_params: Vec::new(),
+            new_fields: Vec::new(),
+        }
     }
-}
 
-impl ToStruct for TupleField {
-    fn to_struct(
-        &self,
+    /// Adds a property declared by the object.
+    fn push_field(
+        &mut self,
+        inner_field: &Field,
         info: &FieldInfo,
         ctx: &FieldContext,
-    ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
+        inner_ctx: &FieldContext,
+    ) -> Result<(), SchemaStructError> {
+        let internal_path = &ctx.internal_path;
 
-        let inner_info = FieldInfo {
-            required: true,
+        let FieldDef {
+            field_name: inner_field_name,
+            field_rename: inner_field_rename,
+            field_default: inner_field_default,
+            field_doc: inner_field_doc,
+            field_ty: inner_field_ty,
+            defs: inner_defs,
+            defs_doc: inner_defs_doc,
+        } = inner_field.to_struct(info, inner_ctx)?;
+
+        self.defs.extend(inner_defs);
+        self.defs_doc.extend(inner_defs_doc);
+
+        let doc_attr = doc_attribute(inner_field_doc.as_deref());
+
+        let wire_name = inner_field_rename
+            .clone()
+            .unwrap_or(inner_field_name.clone());
+
+        // Flattened fields have no key of their own.
+        if !inner_field.flatten {
+            self.wire_name_arms.push(quote! {
+                #inner_field_name => Some(#wire_name),
+            });
+        }
+
+        let inner_field_rename = match ctx.schema.rename_all {
+            Some(rule) => {
+                let key = inner_field_rename.unwrap_or(inner_field_name.clone());
+                // Serde applies the rule to raw identifiers without their
+                // `r#` prefix.
+                let unraw_name = inner_field_name.trim_start_matches("r#");
+                (apply_rename_rule(unraw_name, rule) != key).then_some(key)
+            }
+            None => inner_field_rename,
+        };
+
+        let renamed_attr = if inner_field.flatten {
+            quote!(#[serde(flatten)])
+        } else {
+            rename_attribute(inner_field_rename.as_deref())
+        };
+        let default_attr = default_attribute(inner_field_default.as_deref());
+        let is_collection = match &*inner_field.ty {
+            FieldType::Array(array_field) => array_field.fixed_len().is_none(),
+            FieldType::Object(object_field) => object_field
+                .map_values(&inner_field.info, inner_ctx)
+                .is_some(),
+            _ => false,
+        };
+        let skip_fn = match (
+            ctx.schema.skip_none && !inner_field.key_required,
+            ctx.schema.omit_empty_collections && is_collection,
+            inner_field.info.required,
+        ) {
+            (true, true, _) => Some(format!("{}::empty::is_none_or_empty", internal_path)),
+            (true, false, _) => Some("Option::is_none".to_owned()),
+            (false, true, true) => Some(format!("{}::empty::is_empty", internal_path)),
+            (false, true, false) => Some(format!("{}::empty::is_some_empty", internal_path)),
+            (false, false, _) => None,
+        };
+        let skip_attr = skip_fn.map(|skip_fn| quote!(#[serde(skip_serializing_if = #skip_fn)]));
+        let with_module = if inner_field.int_bool {
+            Some("int_bool")
+        } else if inner_field.coerce {
+            Some("coerce")
+        } else {
+            None
+        };
+        let with_attr = with_module.map(|module| {
+            serde_with_attribute(
+                internal_path,
+                module,
+                inner_field.info.required,
+                inner_field_default.is_some(),
+            )
+        });
+
+        // String fields with length bounds are deserialized
+        // through a function checking their length.
+        let length_fn = match &*inner_field.ty {
+            FieldType::String(string_field) => string_field.length_bounds(ctx),
+            _ => None,
+        }
+        .map(|(min_length, max_length)| {
+            let length_fn =
+                length_fn_name(&inner_ctx.name_prefix, &inner_field.info.name);
+            let length_fn_ident = format_ident!("{}", length_fn);
+            let (deserialize_fn, string_ty) = if inner_field.info.required {
+                (quote!(deserialize), quote!(String))
+            } else {
+                (quote!(deserialize_option), quote!(Option<String>))
+            };
+
+            self.defs.push(quote! {
+                fn #length_fn_ident<'de, D>(deserializer: D) -> ::core::result::Result<#string_ty, D::Error>
+                where
+                    D: #internal_path::Deserializer<'de>,
+                {
+                    #internal_path::length::#deserialize_fn(deserializer, #min_length, #max_length)
+                }
+            });
+
+            length_fn
+        });
+        let length_attr = length_fn.as_deref().map(|length_fn| {
+            deserialize_with_attribute(
+                length_fn,
+                inner_field.info.required,
+                inner_field_default.is_some(),
+            )
+        });
+
+        // Round-trip tests may only generate values that deserialize back
+        // unchanged, so optional nulls are never `Some(())` and strings are
+        // generated within their length bounds.
+        let arbitrary_field_attr = match &*inner_field.ty {
+            _ if !ctx.schema.generate_tests => None,
+            FieldType::Null(_) if !inner_field.info.required => {
+                Some(quote!(#[cfg_attr(test, proptest(value = "None"))]))
+            }
+            FieldType::String(string_field) if length_fn.is_some() => {
+                let strategy = string_field.length_strategy(inner_field.info.required);
+                Some(quote!(#[cfg_attr(test, proptest(strategy = #strategy))]))
+            }
+            _ => None,
+        };
+
+        let inner_field_ident = format_ident!("{}", inner_field_name);
+
+        let default_value = match (&inner_field_default, inner_field.info.required) {
+            (Some(default_fn), _) => {
+                let default_ident = format_ident!("{}", default_fn);
+                Some(quote!(#default_ident()))
+            }
+            (None, false) => Some(quote!(None)),
+            (None, true) => None,
+        };
+        match &default_value {
+            Some(value) => self.new_fields.push(quote!(#inner_field_ident: #value,)),
+            None => {
+                self.new_params
+                    .push(quote!(#inner_field_ident: #inner_field_ty));
+                self.new_fields.push(quote!(#inner_field_ident,));
+            }
+        }
+        self.default_fields =
+            self.default_fields
+                .take()
+                .zip(default_value)
+                .map(|(mut fields, value)| {
+                    fields.push(quote!(#inner_field_ident: #value,));
+                    fields
+                });
+
+        let debug_name = inner_field_name.trim_start_matches("r#");
+        let debug_value = match ctx.schema.float_precision {
+            Some(precision) if holds_floats(&inner_field.ty, ctx, &mut Vec::new()) => quote! {
+                &#internal_path::PreciseFloat(&self.#inner_field_ident, #precision)
+            },
+            _ => quote!(&self.#inner_field_ident),
+        };
+        self.debug_fields
+            .push(quote!(.field(#debug_name, #debug_value)));
+
+        self.field_tokens.push(quote! {
+            #doc_attr
+            #renamed_attr
+            #default_attr
+            #skip_attr
+            #with_attr
+            #length_attr
+            #arbitrary_field_attr
+            pub #inner_field_ident: #inner_field_ty,
+        });
+
+        self.field_tokens_doc.push(quote! {
+            #doc_attr
+            pub #inner_field_ident: #inner_field_ty,
+        });
+
+        if ctx.schema.custom_serde {
+            let expected = inner_field.ty.schema_type();
+            let value = if inner_field.flatten {
+                quote! {
+                    #internal_path::de::flattened::<_, D::Error>(&map, #wire_name, #expected)?
+                }
+            } else {
+                let lookup = match (with_module, &length_fn) {
+                    (_, Some(length_fn)) => {
+                        let length_fn_ident = format_ident!("{}", length_fn);
+                        quote! {
+                            #internal_path::de::field_with::<_, D::Error, _>(
+                                &map,
+                                #wire_name,
+                                #expected,
+                                #length_fn_ident,
+                            )?
+                        }
+                    }
+                    (Some(module), None) => {
+                        let module = if inner_field.info.required {
+                            format_ident!("{}", module)
+                        } else {
+                            format_ident!("{}_option", module)
+                        };
+                        quote! {
+                            #internal_path::de::field_with::<_, D::Error, _>(
+                                &map,
+                                #wire_name,
+                                #expected,
+                                #internal_path::#module::deserialize,
+                            )?
+                        }
+                    }
+                    (None, None) => quote! {
+                        #internal_path::de::field::<_, D::Error>(&map, #wire_name, #expected)?
+                    },
+                };
+
+                match (&inner_field_default, inner_field.info.required) {
+                    (Some(default_fn), _) => {
+                        let default_ident = format_ident!("{}", default_fn);
+                        quote!(#lookup.unwrap_or_else(#default_ident))
+                    }
+                    (None, true) => quote! {
+                        #lookup.ok_or_else(|| {
+                            #internal_path::de::missing_field::<D::Error>(#wire_name)
+                        })?
+                    },
+                    (None, false) => quote!(#lookup.unwrap_or_default()),
+                }
+            };
+
+            self.de_fields.push(quote! {
+                #inner_field_ident: #value,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Adds the map collecting any properties not declared by the object.
+    fn push_additional_properties(
+        &mut self,
+        object: &ObjectField,
+        values: &Field,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+        inner_ctx: &FieldContext,
+    ) -> Result<(), SchemaStructError> {
+        let internal_path = &ctx.internal_path;
+
+        if let Some(key) = object
+            .fields
+            .keys()
+            .find(|key| renamed_field(key, ctx.schema.raw_idents).0 == "additional_properties")
+        {
+            return Err(format!(
+                "property `{}` conflicts with the `additional_properties` field holding the object's additional properties",
+                key
+            )
+            .into());
+        }
+
+        let values_def = values.to_struct(info, inner_ctx)?;
+        self.defs.extend(values_def.defs);
+        self.defs_doc.extend(values_def.defs_doc);
+
+        let values_ty = values_def.field_ty;
+        let map_path = ctx.schema.map_path();
+        let map_ty = quote!(#map_path<String, #values_ty>);
+
+        self.field_tokens.push(quote! {
+            /// Any properties not declared by the schema.
+            #[serde(flatten)]
+            pub additional_properties: #map_ty,
+        });
+        self.field_tokens_doc.push(quote! {
+            /// Any properties not declared by the schema.
+            pub additional_properties: #map_ty,
+        });
+        let debug_value = match ctx.schema.float_precision {
+            Some(precision) if holds_floats(&values.ty, ctx, &mut Vec::new()) => quote! {
+                &#internal_path::PreciseFloat(&self.additional_properties, #precision)
+            },
+            _ => quote!(&self.additional_properties),
+        };
+        self.debug_fields
+            .push(quote!(.field("additional_properties", #debug_value)));
+
+        self.new_fields
+            .push(quote!(additional_properties: #map_path::new(),));
+        if let Some(fields) = &mut self.default_fields {
+            fields.push(quote!(additional_properties: #map_path::new(),));
+        }
+
+        if ctx.schema.custom_serde {
+            let expected = values.ty.schema_type();
+            let known_keys = object.known_keys(ctx, &mut Vec::new());
+
+            self.de_fields.push(quote! {
+                additional_properties: #internal_path::de::additional::<_, _, D::Error>(
+                    &map,
+                    &[#(#known_keys),*],
+                    #expected,
+                )?,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks whether the debug output of a type includes numbers formatted by
+/// `PreciseFloat`: numbers held directly, in arrays or maps, or through refs to
+/// subschemas aliasing such types. Numbers in other types are formatted by
+/// those types' own `Debug` implementations, or not at all in the case of
+/// tuples. Subschemas already visited are skipped.
+fn holds_floats(ty: &FieldType, ctx: &FieldContext, visited: &mut Vec<String>) -> bool {
+    match ty {
+        FieldType::Number(_) => true,
+        FieldType::Array(array_field) => holds_floats(&array_field.items.ty, ctx, visited),
+        FieldType::Object(object_field) if object_field.is_map() => object_field
+            .additional_properties
+            .as_ref()
+            .is_some_and(|values| holds_floats(&values.ty, ctx, visited)),
+        FieldType::Ref(RefField {
+            ty: RefType::Subschema(subschema_name),
+            ..
+        }) if !visited.contains(subschema_name) => {
+            visited.push(subschema_name.clone());
+            ctx.schema
+                .subschemas
+                .get(subschema_name)
+                .is_some_and(|subschema| holds_floats(&subschema.schema.ty, ctx, visited))
+        }
+        _ => false,
+    }
+}
+
+/// Generates a map from the entries of a default object, given the type of
+/// the map's values.
+fn map_default<'v>(
+    values: &Field,
+    entries: impl IntoIterator<Item = (&'v String, &'v Value)>,
+    info: &FieldInfo,
+    ctx: &FieldContext,
+) -> Result<TokenStream, SchemaStructError> {
+    let entries = entries
+        .into_iter()
+        .map(|(key, value)| {
+            values.parse_default(Some(value), info, ctx).map(|value| {
+                let value = value.unwrap_or(quote!(None));
+                quote!((String::from(#key), #value))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let map_path = ctx.schema.map_path();
+
+    Ok(quote!(#map_path::from([#(#entries),*])))
+}
+
+impl ToStruct for EnumField {
+    fn to_struct(
+        &self,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<FieldDef, SchemaStructError> {
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+        let enum_name_without_prefix = renamed_enum(&info.name);
+        let enum_name = format!("{}{}", ctx.name_prefix, enum_name_without_prefix);
+        let enum_ident = format_ident!("{}", enum_name);
+        let vis = &ctx.vis;
+        let internal_path = &ctx.internal_path;
+        let field_ty = maybe_optional(quote!(#enum_ident), info.required);
+
+        let discriminants = match &ctx.schema.repr {
+            Some(repr) => self.discriminants(repr)?,
+            None => vec![quote!(); self.variants.len()],
+        };
+        let repr_attr = ctx.schema.repr.as_ref().map(|repr| quote!(#[repr(#repr)]));
+
+        let (variant_tokens, variant_tokens_doc) =
+            self.variant_names().into_iter().zip(discriminants).fold(
+                (Vec::new(), Vec::new()),
+                |(mut variant_tokens, mut variant_tokens_doc),
+                 ((variant_name, variant_rename), discriminant)| {
+                    let variant_ident = format_ident!("{}", variant_name);
+
+                    let renamed_attr = rename_attribute(variant_rename.as_deref());
+
+                    variant_tokens.push(quote! {
+                        #renamed_attr
+                        #variant_ident #discriminant,
+                    });
+
+                    variant_tokens_doc.push(quote! {
+                        #variant_ident,
+                    });
+
+                    (variant_tokens, variant_tokens_doc)
+                },
+            );
+
+        let mut defs = Vec::new();
+        let mut defs_doc = Vec::new();
+
+        let field_default =
+            self.parse_default(self.default.as_ref(), info, ctx)?
+                .map(|default_value| {
+                    let field_default = default_fn_name(&ctx.name_prefix, &info.name);
+                    let field_default_ident = format_ident!("{}", field_default);
+                    let fn_return = maybe_optional(quote!(#enum_ident), info.required);
+
+                    defs.push(quote! {
+                        fn #field_default_ident() -> #fn_return {
+                            #default_value
+                        }
+                    });
+
+                    field_default
+                });
+
+        let doc_attr = doc_attribute(info.description.as_deref());
+        let arbitrary_attr = arbitrary_attribute(ctx.schema.generate_tests);
+        let required_info = FieldInfo {
+            required: true,
+            ..info.clone()
+        };
+        let default_variant = self.parse_default(self.default.as_ref(), &required_info, ctx)?;
+
+        let (ordered_derives, mut fixed_derives) = if ctx.schema.ordered_enum {
+            (
+                quote!(, Eq, PartialOrd, Ord),
+                vec![
+                    "Debug",
+                    "Clone",
+                    "Copy",
+                    "PartialEq",
+                    "Eq",
+                    "PartialOrd",
+                    "Ord",
+                ],
+            )
+        } else {
+            (quote!(), vec!["Debug", "Clone", "Copy", "PartialEq"])
+        };
+        // A `Default` implementation generated from the schema takes the
+        // place of a derived one.
+        if default_variant.is_some() {
+            fixed_derives.push("Default");
+        }
+        let extra_derives = extra_derives(&ctx.schema.derives, &fixed_derives);
+        // Serde can only rename variants to strings, so numeric enums get
+        // hand-written implementations instead.
+        let serialize_derive = (ctx.schema.serde.serialize() && !self.numeric)
+            .then(|| quote!(#internal_path::Serialize,));
+        let deserialize_derive = (ctx.schema.serde.deserialize() && !self.numeric)
+            .then(|| quote!(#internal_path::Deserialize,));
+
+        defs.push(quote! {
+            #doc_attr
+            #[derive(#serialize_derive #deserialize_derive Debug, Clone, Copy, PartialEq #ordered_derives #extra_derives)]
+            #repr_attr
+            #arbitrary_attr
+            #vis enum #enum_ident {
+                #(#variant_tokens)*
+            }
+        });
+
+        if self.numeric {
+            defs.extend(self.numeric_serde_impls(&enum_ident, ctx));
+        }
+
+        if let Some(default_variant) = default_variant {
+            defs.push(quote! {
+                impl ::core::default::Default for #enum_ident {
+                    fn default() -> Self {
+                        #default_variant
+                    }
+                }
+            });
+        }
+
+        defs.push(impl_serde_methods(
+            &enum_ident,
+            internal_path,
+            ctx.schema.serde,
+            &ctx.schema.method_names,
+            None,
+            None,
+        ));
+
+        defs_doc.push(quote! {
+            #doc_attr
+            #vis enum #enum_ident {
+                #(#variant_tokens_doc)*
+            }
+        });
+
+        Ok(FieldDef {
+            field_name,
+            field_rename,
+            field_default,
+            field_doc: info.description.clone(),
+            field_ty,
+            defs,
+            defs_doc,
+        })
+    }
+
+    fn parse_default(
+        &self,
+        value: Option<&Value>,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
+        let enum_name_without_prefix = renamed_enum(&info.name);
+        let enum_name = format!("{}{}", ctx.name_prefix, enum_name_without_prefix);
+        let enum_ident = format_ident!("{}", enum_name);
+
+        value
+            .map(|default| {
+                let variant = match default {
+                    Value::Number(number) if self.numeric => number.to_string(),
+                    Value::String(variant) if !self.numeric => variant.clone(),
+                    _ if self.numeric => {
+                        return Err("expected default value to be an enum variant number".into())
+                    }
+                    _ => return Err("expected default value to be an enum variant string".into()),
+                };
+                let variant_index =
+                    self.variants
+                        .iter()
+                        .position(|v| *v == variant)
+                        .ok_or(format!(
+                            "default value '{}' is not an enum variant",
+                            variant
+                        ))?;
+                let (variant_name, _) = &self.variant_names()[variant_index];
+                let variant_ident = format_ident!("{}", variant_name);
+                Ok(maybe_optional_value(
+                    quote!(#enum_ident::#variant_ident),
+                    info.required,
+                ))
+            })
+            .invert()
+    }
+}
+
+impl EnumField {
+    /// Gets the names of the enum's variants, along with the strings to
+    /// rename them to when serializing and deserializing, if any.
+    fn variant_names(&self) -> Vec<(String, Option<String>)> {
+        if self.numeric {
+            numeric_enum_variants(&self.variants)
+                .into_iter()
+                .map(|variant_name| (variant_name, None))
+                .collect()
+        } else {
+            renamed_enum_variants(&self.variants)
+        }
+    }
+
+    /// Gets the explicit discriminants of the enum's variants when it is
+    /// represented as a primitive integer type. Numeric enums use their
+    /// values, while string enums number their variants in declaration order.
+    fn discriminants(&self, repr: &Ident) -> Result<Vec<TokenStream>, SchemaStructError> {
+        let (min, max) =
+            repr_range(&repr.to_string()).ok_or(format!("unsupported enum repr '{}'", repr))?;
+
+        self.variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                let value = if self.numeric {
+                    variant.parse::<i128>().map_err(|_| {
+                        format!(
+                            "enum value {} cannot be used as a `{}` discriminant",
+                            variant, repr
+                        )
+                    })?
+                } else {
+                    index as i128
+                };
+
+                if value < min || value > max {
+                    return Err(
+                        format!("enum value {} is out of range for `{}`", variant, repr).into(),
+                    );
+                }
+
+                let literal = proc_macro2::Literal::i128_unsuffixed(value);
+                Ok(quote!(= #literal))
+            })
+            .collect()
+    }
+
+    /// Generates `Serialize` and `Deserialize` implementations for a numeric
+    /// enum, representing each variant as its number.
+    fn numeric_serde_impls(&self, enum_ident: &Ident, ctx: &FieldContext) -> Vec<TokenStream> {
+        let internal_path = &ctx.internal_path;
+        let variant_idents = self
+            .variant_names()
+            .into_iter()
+            .map(|(variant_name, _)| format_ident!("{}", variant_name))
+            .collect::<Vec<_>>();
+        let numbers = &self.variants;
+        let indices = 0..self.variants.len();
+        let mut impls = Vec::new();
+
+        if ctx.schema.serde.serialize() {
+            impls.push(quote! {
+                impl #internal_path::Serialize for #enum_ident {
+                    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                    where
+                        S: #internal_path::Serializer,
+                    {
+                        let json = match self {
+                            #(Self::#variant_idents => #numbers,)*
+                        };
+                        #internal_path::constant::serialize(json, serializer)
+                    }
+                }
+            });
+        }
+
+        if ctx.schema.serde.deserialize() {
+            impls.push(quote! {
+                impl<'de> #internal_path::Deserialize<'de> for #enum_ident {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: #internal_path::Deserializer<'de>,
+                    {
+                        match #internal_path::constant::deserialize_one_of(&[#(#numbers),*], deserializer)? {
+                            #(#indices => Ok(Self::#variant_idents),)*
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+            });
+        }
+
+        impls
+    }
+}
+
+impl ToStruct for TupleField {
+    fn to_struct(
+        &self,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<FieldDef, SchemaStructError> {
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+
+        let inner_info = FieldInfo {
+            required: true,
             ..info.clone()
         };
 
@@ -795,6 +1914,8 @@ impl ToStruct for TupleField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         let inner_info = FieldInfo {
             required: true,
             ..info.clone()
@@ -847,25 +1968,47 @@ impl ToStruct for RefField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<FieldDef, SchemaStructError> {
-        let (field_name, field_rename) = renamed_field(&info.name);
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+
+        if let RefType::Subschema(subschema_name) = &self.ty {
+            if let Some(external_ty) = ctx.schema.external_types.get(subschema_name) {
+                return Ok(FieldDef {
+                    field_name,
+                    field_rename,
+                    field_default: None,
+                    field_doc: info.description.clone(),
+                    field_ty: maybe_optional(quote!(#external_ty), info.required),
+                    defs: vec![],
+                    defs_doc: vec![],
+                });
+            }
+        }
+
         let inner_schema_name = self.ty.name(&ctx.root_name);
         let inner_schema_ident = format_ident!("{}", inner_schema_name);
-        let field_ty = maybe_optional(quote!(Box<#inner_schema_ident>), info.required);
+        let inner_ty = if self.is_cyclic(ctx) {
+            quote!(Box<#inner_schema_ident>)
+        } else {
+            quote!(#inner_schema_ident)
+        };
+        let field_ty = maybe_optional(inner_ty.clone(), info.required);
         let mut defs = Vec::new();
 
-        let field_default = self.parse_default(None, info, ctx)?.map(|default_value| {
-            let field_default = default_fn_name(&ctx.name_prefix, &info.name);
-            let field_default_ident = format_ident!("{}", field_default);
-            let fn_return = maybe_optional(quote!(Box<#inner_schema_ident>), info.required);
+        let field_default =
+            self.parse_default(self.default.as_ref(), info, ctx)?
+                .map(|default_value| {
+                    let field_default = default_fn_name(&ctx.name_prefix, &info.name);
+                    let field_default_ident = format_ident!("{}", field_default);
+                    let fn_return = maybe_optional(inner_ty, info.required);
 
-            defs.push(quote! {
-                fn #field_default_ident() -> #fn_return {
-                    #default_value
-                }
-            });
+                    defs.push(quote! {
+                        fn #field_default_ident() -> #fn_return {
+                            #default_value
+                        }
+                    });
 
-            field_default
-        });
+                    field_default
+                });
 
         Ok(FieldDef {
             field_name,
@@ -884,6 +2027,8 @@ impl ToStruct for RefField {
         info: &FieldInfo,
         ctx: &FieldContext,
     ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
         Ok(match &self.ty {
             RefType::Root => {
                 let inner_info = FieldInfo {
@@ -894,6 +2039,7 @@ impl ToStruct for RefField {
                 };
                 let inner_ctx = FieldContext {
                     name_prefix: String::new(),
+                    container: "#".to_owned(),
                     ..ctx.clone()
                 };
 
@@ -916,6 +2062,7 @@ impl ToStruct for RefField {
                 };
                 let inner_ctx = FieldContext {
                     name_prefix: String::new(),
+                    container: subschema_name.clone(),
                     ..ctx.clone()
                 };
 
@@ -924,8 +2071,15 @@ impl ToStruct for RefField {
                     .get(subschema_name)
                     .ok_or(format!("unknown subschema '{}'", subschema_name).into())
                     .and_then(|subschema| {
+                        // A subschema's own default is not used for refs
+                        // leading back to it, as the default would otherwise
+                        // nest a value that is never in the input.
+                        let target_default = (!self.is_cyclic(ctx))
+                            .then(|| subschema.schema.ty.inner_default())
+                            .flatten();
+
                         value
-                            .or(subschema.schema.ty.inner_default())
+                            .or(target_default)
                             .map(|default| {
                                 subschema
                                     .parse_default(Some(default), &inner_info, &inner_ctx)
@@ -935,7 +2089,367 @@ impl ToStruct for RefField {
                     })
             }
         }?
-        .map(|inner_default| maybe_optional_value(quote!(Box::new(#inner_default)), info.required)))
+        .map(|inner_default| {
+            let inner_value = if self.is_cyclic(ctx) {
+                quote!(Box::new(#inner_default))
+            } else {
+                inner_default
+            };
+
+            maybe_optional_value(inner_value, info.required)
+        }))
+    }
+}
+
+impl RefField {
+    /// Does the ref lead back to the type containing it? Only such refs
+    /// need to be boxed.
+    fn is_cyclic(&self, ctx: &FieldContext) -> bool {
+        ctx.schema
+            .cyclic_refs
+            .contains(&(ctx.container.clone(), self.ty.type_key().to_owned()))
+    }
+}
+
+impl ToStruct for ConstField {
+    fn to_struct(
+        &self,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<FieldDef, SchemaStructError> {
+        let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+        let struct_name_without_prefix = renamed_struct(&info.name);
+        let struct_name = format!("{}{}", ctx.name_prefix, struct_name_without_prefix);
+        let struct_ident = format_ident!("{}", struct_name);
+        let vis = &ctx.vis;
+        let internal_path = &ctx.internal_path;
+        let field_ty = maybe_optional(quote!(#struct_ident), info.required);
+        let const_json = self.value.to_string();
+
+        let mut defs = Vec::new();
+        let mut defs_doc = Vec::new();
+
+        let field_default =
+            self.parse_default(self.default.as_ref(), info, ctx)?
+                .map(|default_value| {
+                    let field_default = default_fn_name(&ctx.name_prefix, &info.name);
+                    let field_default_ident = format_ident!("{}", field_default);
+                    let fn_return = maybe_optional(quote!(#struct_ident), info.required);
+
+                    defs.push(quote! {
+                        fn #field_default_ident() -> #fn_return {
+                            #default_value
+                        }
+                    });
+
+                    field_default
+                });
+
+        let doc = match &info.description {
+            Some(description) => format!("{}\n\nAlways `{}`.", description, const_json),
+            None => format!("Always `{}`.", const_json),
+        };
+        let doc_attr = doc_attribute(Some(&doc));
+        let arbitrary_attr = arbitrary_attribute(ctx.schema.generate_tests);
+        let extra_derives = extra_derives(
+            &ctx.schema.derives,
+            &["Debug", "Clone", "Copy", "PartialEq", "Eq", "Default"],
+        );
+
+        defs.push(quote! {
+            #doc_attr
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Default #extra_derives)]
+            #arbitrary_attr
+            #vis struct #struct_ident;
+        });
+
+        if ctx.schema.serde.serialize() {
+            defs.push(quote! {
+                impl #internal_path::Serialize for #struct_ident {
+                    fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                    where
+                        S: #internal_path::Serializer,
+                    {
+                        #internal_path::constant::serialize(#const_json, serializer)
+                    }
+                }
+            });
+        }
+
+        if ctx.schema.serde.deserialize() {
+            defs.push(quote! {
+                impl<'de> #internal_path::Deserialize<'de> for #struct_ident {
+                    fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                    where
+                        D: #internal_path::Deserializer<'de>,
+                    {
+                        #internal_path::constant::deserialize(#const_json, deserializer).map(|()| Self)
+                    }
+                }
+            });
+        }
+
+        defs.push(impl_serde_methods(
+            &struct_ident,
+            internal_path,
+            ctx.schema.serde,
+            &ctx.schema.method_names,
+            None,
+            None,
+        ));
+
+        defs_doc.push(quote! {
+            #doc_attr
+            #vis struct #struct_ident;
+        });
+
+        Ok(FieldDef {
+            field_name,
+            field_rename,
+            field_default,
+            field_doc: info.description.clone(),
+            field_ty,
+            defs,
+            defs_doc,
+        })
+    }
+
+    fn parse_default(
+        &self,
+        value: Option<&Value>,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
+        let struct_name_without_prefix = renamed_struct(&info.name);
+        let struct_name = format!("{}{}", ctx.name_prefix, struct_name_without_prefix);
+        let struct_ident = format_ident!("{}", struct_name);
+
+        value
+            .map(|default| {
+                if *default == self.value {
+                    Ok(maybe_optional_value(quote!(#struct_ident), info.required))
+                } else {
+                    Err(format!(
+                        "expected default value to be the constant {}, found {}",
+                        self.value, default
+                    )
+                    .into())
+                }
+            })
+            .invert()
+    }
+}
+
+/// Generates an enum with a variant wrapping each alternative of a `oneOf`
+/// or `anyOf` field. If a tag is given, the enum is internally tagged, with
+/// each variant renamed to its tag value. Otherwise, it is untagged.
+fn alternatives_to_struct(
+    variants: &[Field],
+    tag: Option<&str>,
+    tag_values: &[String],
+    info: &FieldInfo,
+    ctx: &FieldContext,
+) -> Result<FieldDef, SchemaStructError> {
+    let (field_name, field_rename) = renamed_field(&info.name, ctx.schema.raw_idents);
+    let enum_name_without_prefix = renamed_enum(&info.name);
+    let enum_name = format!("{}{}", ctx.name_prefix, enum_name_without_prefix);
+    let enum_ident = format_ident!("{}", enum_name);
+    let vis = &ctx.vis;
+    let internal_path = &ctx.internal_path;
+    let field_ty = maybe_optional(quote!(#enum_ident), info.required);
+
+    let inner_ctx = FieldContext {
+        name_prefix: enum_name,
+        ..ctx.clone()
+    };
+
+    let (mut defs, mut defs_doc, variant_tokens, variant_tokens_doc) =
+        variants.iter().enumerate().try_fold(
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+            |(mut defs, mut defs_doc, mut variant_tokens, mut variant_tokens_doc),
+             (index, variant)| {
+                let FieldDef {
+                    field_ty: variant_ty,
+                    defs: variant_defs,
+                    defs_doc: variant_defs_doc,
+                    ..
+                } = variant.to_struct(info, &inner_ctx)?;
+
+                defs.extend(variant_defs);
+                defs_doc.extend(variant_defs_doc);
+
+                let variant_ident = format_ident!("{}", variant.info.name);
+                let rename_attr = rename_attribute(tag_values.get(index).map(String::as_str));
+
+                variant_tokens.push(quote! {
+                    #rename_attr
+                    #variant_ident(#variant_ty),
+                });
+                variant_tokens_doc.push(quote! {
+                    #variant_ident(#variant_ty),
+                });
+
+                Result::<_, SchemaStructError>::Ok((
+                    defs,
+                    defs_doc,
+                    variant_tokens,
+                    variant_tokens_doc,
+                ))
+            },
+        )?;
+
+    let doc_attr = doc_attribute(info.description.as_deref());
+    let arbitrary_attr = arbitrary_attribute(ctx.schema.generate_tests);
+    let serialize_derive = ctx
+        .schema
+        .serde
+        .serialize()
+        .then(|| quote!(#internal_path::Serialize,));
+    let deserialize_derive = ctx
+        .schema
+        .serde
+        .deserialize()
+        .then(|| quote!(#internal_path::Deserialize,));
+    let serde_attr = match tag {
+        Some(tag) => quote!(#[serde(tag = #tag)]),
+        None => quote!(#[serde(untagged)]),
+    };
+    let extra_derives = extra_derives(&ctx.schema.derives, &["Debug", "Clone", "PartialEq"]);
+
+    defs.push(quote! {
+        #doc_attr
+        #[derive(#serialize_derive #deserialize_derive Debug, Clone, PartialEq #extra_derives)]
+        #serde_attr
+        #arbitrary_attr
+        #vis enum #enum_ident {
+            #(#variant_tokens)*
+        }
+    });
+
+    defs.push(impl_serde_methods(
+        &enum_ident,
+        internal_path,
+        ctx.schema.serde,
+        &ctx.schema.method_names,
+        None,
+        None,
+    ));
+
+    defs_doc.push(quote! {
+        #doc_attr
+        #vis enum #enum_ident {
+            #(#variant_tokens_doc)*
+        }
+    });
+
+    // Tagged enums get a companion enum of their tag values, so that the
+    // kind of a value can be matched on without its payload.
+    if let Some(tag) = tag {
+        let kind_ident = format_ident!("{}Kind", enum_ident);
+        let kind_doc = format!(
+            " The possible values of the `{}` tag of [`{}`].",
+            tag, enum_ident
+        );
+        let kind_method_doc = format!(
+            " Gets the kind of this value, as given by its `{}` tag.",
+            tag
+        );
+        let variant_idents = variants
+            .iter()
+            .map(|variant| format_ident!("{}", variant.info.name))
+            .collect::<Vec<_>>();
+
+        defs.push(quote! {
+            #[doc = #kind_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #vis enum #kind_ident {
+                #(#variant_idents,)*
+            }
+
+            impl #enum_ident {
+                #[doc = #kind_method_doc]
+                pub fn kind(&self) -> #kind_ident {
+                    match self {
+                        #(Self::#variant_idents(_) => #kind_ident::#variant_idents,)*
+                    }
+                }
+            }
+        });
+
+        defs_doc.push(quote! {
+            #[doc = #kind_doc]
+            #vis enum #kind_ident {
+                #(#variant_idents,)*
+            }
+        });
+    }
+
+    Ok(FieldDef {
+        field_name,
+        field_rename,
+        field_default: None,
+        field_doc: info.description.clone(),
+        field_ty,
+        defs,
+        defs_doc,
+    })
+}
+
+impl ToStruct for OneOfField {
+    fn to_struct(
+        &self,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<FieldDef, SchemaStructError> {
+        self.parse_default(self.default.as_ref(), info, ctx)?;
+        alternatives_to_struct(
+            &self.variants,
+            self.tag.as_deref(),
+            &self.tag_values,
+            info,
+            ctx,
+        )
+    }
+
+    fn parse_default(
+        &self,
+        value: Option<&Value>,
+        _info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
+        match value {
+            Some(_) => Err("default values are not supported for `oneOf` fields".into()),
+            None => Ok(None),
+        }
+    }
+}
+
+impl ToStruct for AnyOfField {
+    fn to_struct(
+        &self,
+        info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<FieldDef, SchemaStructError> {
+        self.parse_default(self.default.as_ref(), info, ctx)?;
+        alternatives_to_struct(&self.variants, None, &[], info, ctx)
+    }
+
+    fn parse_default(
+        &self,
+        value: Option<&Value>,
+        _info: &FieldInfo,
+        ctx: &FieldContext,
+    ) -> Result<Option<TokenStream>, SchemaStructError> {
+        let value = resolve_default(value, &ctx.schema.source)?;
+
+        match value {
+            Some(_) => Err("default values are not supported for `anyOf` fields".into()),
+            None => Ok(None),
+        }
     }
 }
 
@@ -956,6 +2470,9 @@ impl ToStruct for FieldType {
             Self::Enum(field) => field.to_struct(info, ctx),
             Self::Tuple(field) => field.to_struct(info, ctx),
             Self::Ref(field) => field.to_struct(info, ctx),
+            Self::Const(field) => field.to_struct(info, ctx),
+            Self::OneOf(field) => field.to_struct(info, ctx),
+            Self::AnyOf(field) => field.to_struct(info, ctx),
         }
     }
 
@@ -976,6 +2493,9 @@ impl ToStruct for FieldType {
             Self::Enum(field) => field.parse_default(value, info, ctx),
             Self::Tuple(field) => field.parse_default(value, info, ctx),
             Self::Ref(field) => field.parse_default(value, info, ctx),
+            Self::Const(field) => field.parse_default(value, info, ctx),
+            Self::OneOf(field) => field.parse_default(value, info, ctx),
+            Self::AnyOf(field) => field.parse_default(value, info, ctx),
         }
     }
 }
@@ -995,7 +2515,10 @@ impl ToStruct for Field {
             self.info.clone()
         };
 
-        self.ty.to_struct(&info, ctx)
+        match &*self.ty {
+            FieldType::Integer(integer) if self.int_bool => integer.to_int_bool_struct(&info, ctx),
+            ty => ty.to_struct(&info, ctx),
+        }
     }
 
     fn parse_default(
@@ -5,9 +5,13 @@ use indexmap::IndexMap;
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_crate::{crate_name, FoundCrate};
 use quote::{format_ident, quote, ToTokens, TokenStreamExt};
-use serde_json::Value;
+use serde_json::{Number, Value};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
 use std::fmt::Display;
-use syn::Visibility;
+use std::fs;
+use std::sync::Arc;
+use syn::{Path, Visibility};
 
 /// A JSON value type.
 #[derive(Debug, Clone, Copy)]
@@ -22,6 +26,9 @@ pub enum ValueType {
     Enum,
     Tuple,
     Ref,
+    Const,
+    OneOf,
+    AnyOf,
 }
 
 impl ValueType {
@@ -38,6 +45,9 @@ impl ValueType {
             "enum" => Self::Enum,
             "tuple" => Self::Tuple,
             "ref" => Self::Ref,
+            "const" => Self::Const,
+            "oneOf" => Self::OneOf,
+            "anyOf" => Self::AnyOf,
             unknown_ty => {
                 return Err(format!("unknown JSON type `{}`", unknown_ty).into());
             }
@@ -58,6 +68,51 @@ pub struct FieldInfo {
     pub subschema: bool,
 }
 
+/// The serde traits implemented by generated types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerdeTraits {
+    /// Both `Serialize` and `Deserialize`.
+    Both,
+    /// Only `Serialize`.
+    Serialize,
+    /// Only `Deserialize`.
+    Deserialize,
+}
+
+impl SerdeTraits {
+    /// Parses the serde traits from a string.
+    pub fn from_str(s: &str) -> Result<Self, SchemaStructError> {
+        match s {
+            "serialize" => Ok(Self::Serialize),
+            "deserialize" => Ok(Self::Deserialize),
+            other => Err(format!(
+                "expected `serde` to be \"serialize\" or \"deserialize\", found \"{}\"",
+                other
+            )
+            .into()),
+        }
+    }
+
+    /// Whether `Serialize` is implemented.
+    pub fn serialize(self) -> bool {
+        matches!(self, Self::Both | Self::Serialize)
+    }
+
+    /// Whether `Deserialize` is implemented.
+    pub fn deserialize(self) -> bool {
+        matches!(self, Self::Both | Self::Deserialize)
+    }
+}
+
+/// The names of the generated methods converting to and from JSON strings.
+#[derive(Debug, Clone)]
+pub struct MethodNames {
+    /// The method deserializing a JSON string, `from_str` by default.
+    pub parse: Ident,
+    /// The method serializing into a JSON string, `to_str` by default.
+    pub emit: Ident,
+}
+
 /// A reference type.
 #[derive(Debug, Clone)]
 pub enum RefType {
@@ -87,6 +142,15 @@ impl RefType {
         }
     }
 
+    /// Gets the name identifying the referenced type when detecting cyclic
+    /// refs: `#` for the root object, or the subschema name.
+    pub fn type_key(&self) -> &str {
+        match self {
+            Self::Root => "#",
+            Self::Subschema(subschema_name) => subschema_name,
+        }
+    }
+
     /// Gets the name of the referenced type.
     pub fn name(&self, root_name: &str) -> String {
         match self {
@@ -112,11 +176,30 @@ pub struct BooleanField {
     pub default: Option<Value>,
 }
 
+/// The bounds declared on an integer or number field.
+#[derive(Debug, Clone, Default)]
+pub struct NumberBounds {
+    /// The inclusive lower bound.
+    pub minimum: Option<Number>,
+    /// The exclusive lower bound.
+    pub exclusive_minimum: Option<Number>,
+    /// The inclusive upper bound.
+    pub maximum: Option<Number>,
+    /// The exclusive upper bound.
+    pub exclusive_maximum: Option<Number>,
+}
+
 /// An integer field.
 #[derive(Debug, Clone)]
 pub struct IntegerField {
     /// The default value.
     pub default: Option<Value>,
+    /// The lower and upper bounds.
+    pub bounds: NumberBounds,
+    /// The number that values must be a multiple of.
+    pub multiple_of: Option<f64>,
+    /// The format of the integer, e.g. `int32`.
+    pub format: Option<String>,
 }
 
 /// A number field.
@@ -124,6 +207,12 @@ pub struct IntegerField {
 pub struct NumberField {
     /// The default value.
     pub default: Option<Value>,
+    /// The lower and upper bounds.
+    pub bounds: NumberBounds,
+    /// The number that values must be a multiple of.
+    pub multiple_of: Option<f64>,
+    /// The format of the number, e.g. `float`.
+    pub format: Option<String>,
 }
 
 /// A string field.
@@ -131,6 +220,12 @@ pub struct NumberField {
 pub struct StringField {
     /// The default value.
     pub default: Option<Value>,
+    /// The format of the string, e.g. `date-time`.
+    pub format: Option<String>,
+    /// The minimum length of the string in characters.
+    pub min_length: Option<u64>,
+    /// The maximum length of the string in characters.
+    pub max_length: Option<u64>,
 }
 
 /// An array field.
@@ -140,6 +235,10 @@ pub struct ArrayField {
     pub items: Field,
     /// The default value.
     pub default: Option<Value>,
+    /// The minimum number of items in the array.
+    pub min_items: Option<u64>,
+    /// The maximum number of items in the array.
+    pub max_items: Option<u64>,
 }
 
 /// An object field.
@@ -147,15 +246,33 @@ pub struct ArrayField {
 pub struct ObjectField {
     /// A mapping of the object's field names to values.
     pub fields: IndexMap<String, Field>,
+    /// The type of the values of any additional properties, if
+    /// `additionalProperties` is given as a schema.
+    pub additional_properties: Option<Field>,
+    /// Whether additional properties are disallowed, i.e.
+    /// `additionalProperties` is `false`.
+    pub deny_unknown_fields: bool,
     /// The default value.
     pub default: Option<Value>,
 }
 
+impl ObjectField {
+    /// Is this object represented as a map rather than a struct? This is the
+    /// case for objects with typed additional properties and no declared
+    /// properties.
+    pub fn is_map(&self) -> bool {
+        self.fields.is_empty() && self.additional_properties.is_some()
+    }
+}
+
 /// An enum field.
 #[derive(Debug, Clone)]
 pub struct EnumField {
-    /// The names of the enum's variants.
+    /// The names of the enum's variants. For numeric enums, these are the
+    /// JSON representations of the numbers.
     pub variants: Vec<String>,
+    /// Whether the enum's variants are numbers rather than strings.
+    pub numeric: bool,
     /// The default value.
     pub default: Option<Value>,
 }
@@ -178,6 +295,38 @@ pub struct RefField {
     pub default: Option<Value>,
 }
 
+/// A constant field.
+#[derive(Debug, Clone)]
+pub struct ConstField {
+    /// The constant value.
+    pub value: Value,
+    /// The default value.
+    pub default: Option<Value>,
+}
+
+/// A `oneOf` field, represented as an enum with a variant per subschema.
+#[derive(Debug, Clone)]
+pub struct OneOfField {
+    /// The subschemas, each named after the enum variant wrapping it.
+    pub variants: Vec<Field>,
+    /// The property discriminating between the variants, if they share one.
+    pub tag: Option<String>,
+    /// The values of the discriminating property, one per variant.
+    pub tag_values: Vec<String>,
+    /// The default value.
+    pub default: Option<Value>,
+}
+
+/// An `anyOf` field, represented as an untagged enum with a variant per
+/// subschema.
+#[derive(Debug, Clone)]
+pub struct AnyOfField {
+    /// The subschemas, each named after the enum variant wrapping it.
+    pub variants: Vec<Field>,
+    /// The default value.
+    pub default: Option<Value>,
+}
+
 /// The type of a field.
 #[derive(Debug, Clone)]
 pub enum FieldType {
@@ -191,12 +340,18 @@ pub enum FieldType {
     Enum(EnumField),
     Tuple(TupleField),
     Ref(RefField),
+    Const(ConstField),
+    OneOf(OneOfField),
+    AnyOf(AnyOfField),
 }
 
 impl FieldType {
     /// Does this field type define new types?
     pub fn creates_defs(&self) -> bool {
-        matches!(self, Self::Object(_) | Self::Enum(_))
+        matches!(
+            self,
+            Self::Enum(_) | Self::Const(_) | Self::OneOf(_) | Self::AnyOf(_)
+        ) || matches!(self, Self::Object(field) if !field.is_map())
     }
 
     /// Gets the inner default value of this field.
@@ -212,6 +367,28 @@ impl FieldType {
             Self::Enum(field) => field.default.as_ref(),
             Self::Tuple(field) => field.default.as_ref(),
             Self::Ref(field) => field.default.as_ref(),
+            Self::Const(field) => field.default.as_ref(),
+            Self::OneOf(field) => field.default.as_ref(),
+            Self::AnyOf(field) => field.default.as_ref(),
+        }
+    }
+
+    /// Gets the name of the schema type of this field, for use in error
+    /// messages.
+    pub fn schema_type(&self) -> &'static str {
+        match self {
+            Self::Null(_) => "null",
+            Self::Boolean(_) => "boolean",
+            Self::Integer(_) => "integer",
+            Self::Number(_) => "number",
+            Self::String(_) => "string",
+            Self::Array(_) | Self::Tuple(_) => "array",
+            Self::Object(_) => "object",
+            Self::Enum(_) => "enum",
+            Self::Ref(_) => "ref",
+            Self::Const(_) => "const",
+            Self::OneOf(_) => "oneOf",
+            Self::AnyOf(_) => "anyOf",
         }
     }
 }
@@ -223,6 +400,31 @@ pub struct Field {
     pub info: FieldInfo,
     /// The field's type info.
     pub ty: Box<FieldType>,
+    /// Whether the field's own fields are flattened into its parent's.
+    pub flatten: bool,
+    /// Whether the integer field is represented as a `bool`, serialized as
+    /// `0` or `1`.
+    pub int_bool: bool,
+    /// Whether the numeric field also accepts numbers encoded as strings.
+    pub coerce: bool,
+    /// Whether the field's key must be present in its object. This differs
+    /// from `info.required` for required fields that are nullable.
+    pub key_required: bool,
+}
+
+impl Field {
+    /// Gets the extension keyword that gives the field a custom serde
+    /// representation, if any. Such fields are only supported as object
+    /// properties.
+    pub fn serde_with_keyword(&self) -> Option<&'static str> {
+        if self.int_bool {
+            Some("x-bool")
+        } else if self.coerce {
+            Some("x-coerce")
+        } else {
+            None
+        }
+    }
 }
 
 /// A subschema within a schema.
@@ -237,6 +439,21 @@ pub struct Subschema {
 pub struct SchemaStructError {
     /// The error message.
     pub message: String,
+    /// The underlying error that caused this one, if any.
+    pub source: Option<Arc<dyn Error + Send + Sync>>,
+}
+
+impl SchemaStructError {
+    /// Creates an error caused by another, underlying error.
+    pub fn with_source<S>(message: impl Into<String>, source: S) -> Self
+    where
+        S: Error + Send + Sync + 'static,
+    {
+        Self {
+            message: message.into(),
+            source: Some(Arc::new(source)),
+        }
+    }
 }
 
 impl Display for SchemaStructError {
@@ -245,19 +462,29 @@ impl Display for SchemaStructError {
     }
 }
 
-impl std::error::Error for SchemaStructError {}
+impl Error for SchemaStructError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
 
 impl From<&str> for SchemaStructError {
     fn from(value: &str) -> Self {
         Self {
             message: value.to_owned(),
+            source: None,
         }
     }
 }
 
 impl From<String> for SchemaStructError {
     fn from(value: String) -> Self {
-        Self { message: value }
+        Self {
+            message: value,
+            source: None,
+        }
     }
 }
 
@@ -295,6 +522,9 @@ pub struct FieldContext<'a> {
     pub vis: Visibility,
     /// The path to the internal module.
     pub internal_path: TokenStream,
+    /// The type containing the current field, named `#` for the root object
+    /// and by name for subschemas.
+    pub container: String,
 }
 
 /// Configuration of a schema-defined struct.
@@ -312,12 +542,169 @@ pub struct SchemaStructConfig {
     pub def: Option<bool>,
     /// Whether to validate JSON values against the schema when deserializing.
     pub validate: Option<bool>,
+    /// Whether to validate JSON values against the schema when serializing.
+    pub validate_on_serialize: Option<bool>,
     /// Whether to log generated items to stdout.
     pub debug: Option<bool>,
+    /// A file to write the formatted generated items to.
+    pub debug_file: Option<String>,
+    /// Whether to generate round-trip property tests for the generated types.
+    pub generate_tests: Option<bool>,
+    /// Whether to treat properties as required unless their types allow
+    /// `null`, rather than only when listed in `"required"`.
+    pub required_by_default: Option<bool>,
+    /// Whether to derive `PartialOrd` and `Ord` on enums, ordering variants
+    /// by their declaration order in the schema.
+    pub ordered_enum: Option<bool>,
+    /// The path to the `schema_struct` crate, overriding the automatically
+    /// detected path.
+    pub internal_path: Option<Path>,
+    /// Whether to detect the predominant property naming style and apply it
+    /// with a single `rename_all` attribute.
+    pub auto_rename: Option<bool>,
+    /// Whether to represent arrays as `Box<[T]>` rather than `Vec<T>`.
+    pub boxed_slices: Option<bool>,
+    /// Other generated types to implement `TryFrom` conversions from.
+    pub compat: Option<Vec<Path>>,
+    /// Names of the subschema definitions to generate. Definitions not
+    /// listed, and not referenced by a listed definition or the root object,
+    /// are skipped.
+    pub defs: Option<Vec<String>>,
+    /// Whether to inline subschemas that are referenced exactly once and are
+    /// not part of a reference cycle.
+    pub flatten_refs: Option<bool>,
+    /// Whether to use raw identifiers (e.g. `r#type`) for fields named after
+    /// Rust keywords, rather than appending an underscore.
+    pub raw_idents: Option<bool>,
+    /// Whether to generate `Deserialize` implementations for structs that
+    /// report the offending field and its expected schema type on failure,
+    /// rather than deriving them.
+    pub custom_serde: Option<bool>,
+    /// Whether to skip subschema definitions that are not referenced,
+    /// directly or indirectly, by the root object.
+    pub prune_unused_defs: Option<bool>,
+    /// Which serde traits to implement, either `"serialize"` or
+    /// `"deserialize"`. If not specified, both are implemented.
+    pub serde: Option<String>,
+    /// Existing Rust types to use in place of generated ones, keyed by the
+    /// ref path of the subschema they replace.
+    pub external_types: Option<Vec<(String, Path)>>,
+    /// Whether to append a table of the top-level fields to the doc
+    /// comment.
+    pub doc_table: Option<bool>,
+    /// Whether to represent enums with a single string variant as constants.
+    pub unit_enums: Option<bool>,
+    /// Whether to reject schemas using unrecognized formats.
+    pub strict: Option<bool>,
+    /// The name of the method deserializing a JSON string. If not
+    /// specified, `from_str` will be used.
+    pub parse_method: Option<Ident>,
+    /// The name of the method serializing into a JSON string. If not
+    /// specified, `to_str` will be used.
+    pub emit_method: Option<Ident>,
+    /// Additional traits to derive on generated structs and enums.
+    pub derives: Option<Vec<Path>>,
+    /// Whether to omit optional fields set to `None` when serializing,
+    /// rather than serializing them as `null`.
+    pub skip_none: Option<bool>,
+    /// Whether to implement `Default` for structs whose fields are all
+    /// optional or have default values.
+    pub default_impl: Option<bool>,
+    /// Whether to generate a `diff` method comparing two values of the
+    /// top-level type.
+    pub diff: Option<bool>,
+    /// The primitive integer type to represent enums as, giving their
+    /// variants explicit discriminants.
+    pub repr: Option<Ident>,
+    /// The type to represent arrays as instead of `Vec`, given as
+    /// `SmallVec<[_; N]>`.
+    pub vec_type: Option<String>,
+    /// The type to represent maps as, either `HashMap` or `BTreeMap`.
+    pub map_type: Option<String>,
+    /// The serde `rename_all` rule to apply to generated structs, e.g.
+    /// `"camelCase"`.
+    pub rename_all: Option<String>,
+    /// The number of decimal places to show when debug formatting number
+    /// fields, replacing the derived `Debug` implementation.
+    pub float_precision: Option<usize>,
+    /// A file to write the formatted generated items to, resolved against
+    /// the manifest directory.
+    pub output: Option<String>,
+    /// Schemas loaded from a directory, keyed by file name, each generating
+    /// its own type. When specified, `schema` is not used.
+    pub dir: Option<Vec<(String, Value)>>,
+    /// Whether to omit empty array and map fields when serializing.
+    pub omit_empty_collections: Option<bool>,
+    /// Whether to generate a `new` constructor taking the required fields of
+    /// each struct.
+    pub constructor: Option<bool>,
     /// The schema itself, in `serde_json::Value` representation.
     pub schema: Value,
 }
 
+impl SchemaStructConfig {
+    /// Splits a configuration loading schemas from a directory into one
+    /// configuration per schema file. Each type is named after its schema's
+    /// `"title"` property, or its file stem if it has none, and refs to other
+    /// files in the directory are replaced by the types generated for them.
+    /// Other configurations are returned unchanged.
+    pub fn into_configs(mut self) -> Result<Vec<Self>, SchemaStructError> {
+        let files = match self.dir.take() {
+            Some(files) => files,
+            None => return Ok(vec![self]),
+        };
+
+        if self.ident.is_some() {
+            return Err("`ident` cannot be combined with `dir`".into());
+        }
+
+        if self.debug_file.is_some() || self.output.is_some() {
+            return Err("`debug_file` and `output` cannot be combined with `dir`".into());
+        }
+
+        let file_names = files
+            .iter()
+            .map(|(file_name, _)| file_name.clone())
+            .collect::<Vec<_>>();
+        let names = files
+            .iter()
+            .map(|(file_name, schema)| {
+                let name = match get_prop_str(schema, "title")? {
+                    Some(title) => title.to_owned(),
+                    None => {
+                        let stem = file_name.strip_suffix(".json").unwrap_or(file_name);
+                        renamed_struct(stem)
+                    }
+                };
+
+                Ok((file_name.clone(), name))
+            })
+            .collect::<Result<HashMap<_, _>, SchemaStructError>>()?;
+
+        files
+            .into_iter()
+            .map(|(file_name, schema)| {
+                let (schema, file_refs) = with_file_refs_as_subschemas(&schema, &file_names)
+                    .map_err(|e| format!("{}: {}", file_name, e))?;
+
+                let mut external_types = self.external_types.clone().unwrap_or_default();
+                for (ref_path, ref_file_name) in file_refs {
+                    let ty = syn::parse_str::<Path>(&names[&ref_file_name])
+                        .map_err(|e| format!("invalid type name for '{}': {}", ref_file_name, e))?;
+                    external_types.push((ref_path, ty));
+                }
+
+                Ok(Self {
+                    ident: Some(format_ident!("{}", names[&file_name])),
+                    external_types: Some(external_types),
+                    schema,
+                    ..self.clone()
+                })
+            })
+            .collect()
+    }
+}
+
 /// A definition of a high-level schema struct definition.
 #[derive(Debug, Clone)]
 pub struct SchemaStructDef {
@@ -331,111 +718,194 @@ pub struct SchemaStructDef {
     pub defs: Vec<TokenStream>,
     /// Simplified type definitions to be used in documentation.
     pub defs_doc: Option<Vec<TokenStream>>,
+    /// A Markdown table of the top-level fields to be used in documentation.
+    pub doc_table: Option<String>,
+    /// The `$schema` URI declared by the schema, if any.
+    pub schema_version: Option<String>,
     /// An optional schema to validate JSON values against when deserializing.
     pub validate: Option<Value>,
+    /// An optional schema to validate JSON values against when serializing.
+    pub validate_on_serialize: Option<Value>,
     /// Whether to log generated items to stdout.
     pub debug: bool,
+    /// Whether to generate round-trip property tests.
+    pub generate_tests: bool,
+    /// An optional file to write the generated items to.
+    pub debug_file: Option<String>,
+    /// Paths to other generated types to implement `TryFrom` conversions
+    /// from.
+    pub compat: Vec<TokenStream>,
+    /// Whether to generate a `diff` method.
+    pub diff: bool,
+    /// The serde traits implemented by generated types.
+    pub serde: SerdeTraits,
+    /// The names of the generated JSON string methods.
+    pub method_names: MethodNames,
     /// The path to the internal module.
     pub internal_path: TokenStream,
 }
 
-impl ToTokens for SchemaStructDef {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+impl SchemaStructDef {
+    /// Writes the formatted generated items to the debug file, if one was
+    /// specified, creating its parent directories if they do not exist.
+    pub fn write_debug_file(&self) -> Result<(), SchemaStructError> {
+        match &self.debug_file {
+            Some(debug_file) => std::path::Path::new(debug_file)
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(debug_file, pretty_print_token_stream(&self.items())))
+                .map_err(|e| {
+                    format!("failed to write generated code to '{}': {}", debug_file, e).into()
+                }),
+            None => Ok(()),
+        }
+    }
+
+    /// Gets all generated items, in the order in which they are emitted.
+    fn items(&self) -> Vec<TokenStream> {
         let struct_ident = &self.ident;
         let internal_path = &self.internal_path;
 
-        let doc_description = self
-            .description
-            .as_ref()
-            .map(|s| format!("{}\n\n", s))
-            .unwrap_or_default();
-        let doc = self
-            .defs_doc
-            .as_ref()
-            .map(|doc| {
+        let doc_sections = [
+            self.description.clone(),
+            self.doc_table
+                .as_ref()
+                .map(|table| format!("# Fields\n\n{}", table)),
+            self.defs_doc.as_ref().map(|doc| {
                 format!(
-                    "{}# Full definition\n\n```\n{}\n```",
-                    doc_description,
+                    "# Full definition\n\n```\n{}\n```",
                     pretty_print_token_stream(doc)
                 )
-            })
-            .or(self.description.clone());
+            }),
+        ];
+        let doc_sections = doc_sections.into_iter().flatten().collect::<Vec<_>>();
+        let doc = (!doc_sections.is_empty()).then(|| doc_sections.join("\n\n"));
 
         let doc_attr = doc_attribute(doc.as_deref());
 
         let (_main_impl, rest) = self.defs.split_last().unwrap();
         let (main_def, pre_defs) = rest.split_last().unwrap();
 
-        let main_impl = match &self.validate {
-            None => quote! {
-                impl #struct_ident {
-                    /// Deserializes a JSON string into this type.
-                    pub fn from_str(json: &str) -> #internal_path::Result<Self> {
-                        #internal_path::deserialize(json)
-                    }
+        let main_impl = impl_serde_methods(
+            struct_ident,
+            internal_path,
+            self.serde,
+            &self.method_names,
+            self.validate.as_ref(),
+            self.validate_on_serialize.as_ref(),
+        );
+        let MethodNames { parse, emit } = &self.method_names;
 
-                    /// Serializes this type into a JSON string.
-                    pub fn to_str(&self) -> #internal_path::Result<String> {
-                        #internal_path::serialize(self)
-                    }
+        let tests = if self.generate_tests {
+            let tests_mod_ident =
+                format_ident!("{}_round_trip_tests", renamed_function(&self.name));
 
-                    /// Deserializes a JSON value into this type.
-                    pub fn from_value(value: &#internal_path::Value) -> #internal_path::Result<Self> {
-                        #internal_path::deserialize_from_value(value.to_owned())
-                    }
+            quote! {
+                #[cfg(test)]
+                mod #tests_mod_ident {
+                    use super::#struct_ident;
+
+                    ::proptest::proptest! {
+                        #[test]
+                        fn round_trip(value in ::proptest::prelude::any::<#struct_ident>()) {
+                            // Non-finite numbers serialize to `null` and cannot be read back.
+                            ::proptest::prop_assume!(#internal_path::round_trip::is_finite(&value));
 
-                    /// Serializes this type into a JSON value.
-                    pub fn to_value(&self) -> #internal_path::Result<#internal_path::Value> {
-                        #internal_path::serialize_to_value(self)
+                            let json = value.#emit().unwrap();
+                            let parsed = #struct_ident::#parse(&json);
+                            ::proptest::prop_assert!(parsed.is_ok(), "{:?}", parsed);
+                            ::proptest::prop_assert_eq!(parsed.unwrap(), value);
+                        }
                     }
                 }
-            },
-            Some(schema) => {
-                let schema_str = schema.to_string();
-
-                quote! {
-                    impl #struct_ident {
-                        /// Deserializes a JSON string into this type.
-                        pub fn from_str(json: &str) -> #internal_path::Result<Self> {
-                            #internal_path::deserialize_validate(json, #schema_str)
-                        }
+            }
+        } else {
+            quote!()
+        };
 
-                        /// Serializes this type into a JSON string.
-                        pub fn to_str(&self) -> #internal_path::Result<String> {
-                            #internal_path::serialize(self)
-                        }
+        let schema_version = match &self.schema_version {
+            Some(uri) => quote!(Some(#uri)),
+            None => quote!(None),
+        };
+        let title = &self.name;
+        let description = match &self.description {
+            Some(description) => quote!(Some(#description)),
+            None => quote!(None),
+        };
+        let metadata_impl = quote! {
+            impl #struct_ident {
+                /// The schema title, or the struct identifier if one was given.
+                pub const TITLE: &'static str = #title;
 
-                        /// Deserializes a JSON value into this type.
-                        pub fn from_value(value: &#internal_path::Value) -> #internal_path::Result<Self> {
-                            #internal_path::deserialize_from_value_validate(value.to_owned(), #schema_str)
-                        }
+                /// The schema description, if any.
+                pub const DESCRIPTION: Option<&'static str> = #description;
 
-                        /// Serializes this type into a JSON value.
-                        pub fn to_value(&self) -> #internal_path::Result<#internal_path::Value> {
-                            #internal_path::serialize_to_value(self)
-                        }
-                    }
+                /// Gets the `$schema` URI declared by the schema, if any.
+                pub fn schema_version() -> Option<&'static str> {
+                    #schema_version
                 }
             }
         };
 
-        let def = quote! {
-            #(#pre_defs)*
-
+        let mut items = pre_defs.to_vec();
+        items.push(quote! {
             #doc_attr
             #main_def
+        });
+        items.push(main_impl);
+        items.push(metadata_impl);
+        if self.validate.is_some() && self.serde.deserialize() {
+            items.push(quote! {
+                impl #struct_ident {
+                    /// Checks whether a JSON string both deserializes into this type and
+                    /// validates against the schema, discarding the details of any error.
+                    pub fn is_valid(json: &str) -> bool {
+                        Self::#parse(json).is_ok()
+                    }
+                }
+            });
+        }
+        if self.diff {
+            items.push(quote! {
+                impl #struct_ident {
+                    /// Compares this value with another, returning a JSON object of the
+                    /// fields whose serialized values differ, taken from `other`. Nested
+                    /// objects produce nested diffs.
+                    #[must_use = "computing a diff has no effect other than returning it"]
+                    pub fn diff(&self, other: &Self) -> #internal_path::Result<#internal_path::Value> {
+                        #internal_path::diff(self, other)
+                    }
+                }
+            });
+        }
+        items.extend(self.compat.iter().map(|other| {
+            quote! {
+                impl ::std::convert::TryFrom<#other> for #struct_ident {
+                    type Error = #internal_path::JsonSchemaError;
 
-            #main_impl
-        };
+                    fn try_from(value: #other) -> #internal_path::Result<Self> {
+                        Self::from_value(&#internal_path::serialize_to_value(&value)?)
+                    }
+                }
+            }
+        }));
+        if self.generate_tests {
+            items.push(tests);
+        }
+
+        items
+    }
+}
+
+impl ToTokens for SchemaStructDef {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let items = self.items();
 
         if self.debug {
-            let mut all = pre_defs.to_vec();
-            all.push(main_def.clone());
-            all.push(main_impl);
-            println!("{}", pretty_print_token_stream(&all));
+            println!("{}", pretty_print_token_stream(&items));
         }
 
-        tokens.append_all(def);
+        tokens.append_all(items);
     }
 }
 
@@ -449,8 +919,70 @@ pub struct SchemaStruct {
     pub def: bool,
     /// An optional schema to validate JSON values against when deserializing.
     pub validate: Option<Value>,
+    /// An optional schema to validate JSON values against when serializing.
+    pub validate_on_serialize: Option<Value>,
     /// Whether to log generated items to stdout.
     pub debug: bool,
+    /// An optional file to write the generated items to.
+    pub debug_file: Option<String>,
+    /// Whether to generate round-trip property tests.
+    pub generate_tests: bool,
+    /// Whether to derive ordering traits on enums.
+    pub ordered_enum: bool,
+    /// An optional override of the path to the `schema_struct` crate.
+    pub internal_path: Option<Path>,
+    /// The serde `rename_all` rule to apply to generated structs, if any.
+    pub rename_all: Option<&'static str>,
+    /// Whether to represent arrays as boxed slices.
+    pub boxed_slices: bool,
+    /// Whether to use raw identifiers for fields named after Rust keywords.
+    pub raw_idents: bool,
+    /// Whether to generate `Deserialize` implementations for structs rather
+    /// than deriving them.
+    pub custom_serde: bool,
+    /// The serde traits implemented by generated types.
+    pub serde: SerdeTraits,
+    /// Existing Rust types to use in place of generated subschema types,
+    /// keyed by subschema name.
+    pub external_types: IndexMap<String, Path>,
+    /// Whether to append a table of the top-level fields to the doc
+    /// comment.
+    pub doc_table: bool,
+    /// Whether to reject schemas using unrecognized formats.
+    pub strict: bool,
+    /// The names of the generated JSON string methods.
+    pub method_names: MethodNames,
+    /// Additional traits to derive on generated structs and enums.
+    pub derives: Vec<Path>,
+    /// Whether to omit optional fields set to `None` when serializing.
+    pub skip_none: bool,
+    /// Whether to omit empty array and map fields when serializing.
+    pub omit_empty_collections: bool,
+    /// Whether to implement `Default` for structs whose fields are all
+    /// optional or have default values.
+    pub default_impl: bool,
+    /// Whether to generate a `new` constructor taking the required fields of
+    /// each struct.
+    pub constructor: bool,
+    /// Whether to generate a `diff` method on the top-level type.
+    pub diff: bool,
+    /// The primitive integer type to represent enums as, if any.
+    pub repr: Option<Ident>,
+    /// The inline capacity of arrays represented as `SmallVec`s, if any.
+    pub small_vec_capacity: Option<usize>,
+    /// The standard library type to represent maps as.
+    pub map_type: Ident,
+    /// The number of decimal places to debug format number fields with, if
+    /// any.
+    pub float_precision: Option<usize>,
+    /// Other generated types to implement `TryFrom` conversions from.
+    pub compat: Vec<Path>,
+    /// The original schema, used to resolve default values expressed as
+    /// refs.
+    pub source: Value,
+    /// Refs that lead back to the type containing them, as pairs of the
+    /// containing and referenced type. Only these refs are boxed.
+    pub cyclic_refs: HashSet<(String, String)>,
     /// The data structure's identifier name. If not specified, the schema
     /// title will be used.
     pub name: String,
@@ -470,38 +1002,217 @@ impl SchemaStruct {
             ident,
             def,
             validate,
+            validate_on_serialize,
             debug,
+            debug_file,
+            generate_tests,
+            required_by_default,
+            ordered_enum,
+            internal_path,
+            auto_rename,
+            boxed_slices,
+            compat,
+            defs,
+            flatten_refs,
+            raw_idents,
+            custom_serde,
+            prune_unused_defs,
+            serde,
+            external_types,
+            doc_table,
+            unit_enums,
+            strict,
+            parse_method,
+            emit_method,
+            derives,
+            skip_none,
+            default_impl,
+            diff,
+            repr,
+            vec_type,
+            map_type,
+            rename_all,
+            float_precision,
+            output,
+            dir: _,
+            omit_empty_collections,
+            constructor,
             schema,
         } = config;
 
+        if validate.unwrap_or(false) {
+            check_examples(&schema)?;
+        }
+
+        let serde = serde
+            .as_deref()
+            .map(SerdeTraits::from_str)
+            .transpose()?
+            .unwrap_or(SerdeTraits::Both);
+
+        if generate_tests.unwrap_or(false) {
+            if serde != SerdeTraits::Both {
+                return Err("round-trip tests require both `Serialize` and `Deserialize`".into());
+            }
+
+            // Arbitrary values cannot be expected to satisfy every constraint
+            // the schema places on them.
+            if validate.unwrap_or(false) || validate_on_serialize.unwrap_or(false) {
+                return Err(
+                    "round-trip tests cannot be combined with `validate` or `validate_on_serialize`"
+                        .into(),
+                );
+            }
+
+            if !cfg!(feature = "round-trip-tests") {
+                return Err("`generate_tests` requires the `round-trip-tests` feature".into());
+            }
+        }
+
+        if !serde.deserialize() && compat.as_ref().is_some_and(|compat| !compat.is_empty()) {
+            return Err("`compat` conversions require `Deserialize`".into());
+        }
+
+        let debug_file = match (debug_file, output) {
+            (Some(_), Some(_)) => {
+                return Err("`output` cannot be combined with `debug_file`".into());
+            }
+            (debug_file, output) => debug_file.or(output),
+        };
+
+        if let Some(repr) = &repr {
+            if repr_range(&repr.to_string()).is_none() {
+                return Err(format!("unsupported enum repr '{}'", repr).into());
+            }
+        }
+
+        let small_vec_capacity = vec_type
+            .as_deref()
+            .map(parse_small_vec_capacity)
+            .transpose()?;
+
+        if small_vec_capacity.is_some() {
+            if !cfg!(feature = "smallvec") {
+                return Err("`vec_type` requires the `smallvec` feature".into());
+            }
+
+            if boxed_slices.unwrap_or(false) {
+                return Err("`vec_type` cannot be combined with `boxed_slices`".into());
+            }
+
+            if generate_tests.unwrap_or(false) {
+                return Err("round-trip tests do not support `vec_type`".into());
+            }
+        }
+
+        let map_type = map_type
+            .as_deref()
+            .map(parse_map_type)
+            .transpose()?
+            .unwrap_or_else(|| format_ident!("HashMap"));
+
+        if !serde.serialize() && diff.unwrap_or(false) {
+            return Err("`diff` requires `Serialize`".into());
+        }
+
+        // Validation is always performed against the original schema, even
+        // when the generated types are stricter.
+        let types_schema = if required_by_default.unwrap_or(false) {
+            with_required_by_default(&schema)
+        } else {
+            schema.clone()
+        };
+        let types_schema = with_pointer_refs_as_subschemas(&types_schema)?;
+        let types_schema = match defs {
+            Some(defs) => with_only_defs(&types_schema, &defs)?,
+            None if prune_unused_defs.unwrap_or(false) => with_only_defs(&types_schema, &[])?,
+            None => types_schema,
+        };
+        let types_schema = if flatten_refs.unwrap_or(false) {
+            with_single_use_refs_inlined(&types_schema)
+        } else {
+            types_schema
+        };
+        let types_schema = if unit_enums.unwrap_or(false) {
+            with_unit_enums_as_const(&types_schema)
+        } else {
+            types_schema
+        };
+
+        let rename_all = match (rename_all, auto_rename.unwrap_or(false)) {
+            (Some(_), true) => {
+                return Err("`rename_all` cannot be combined with `auto_rename`".into());
+            }
+            (Some(rule), false) => Some(
+                RENAME_RULES
+                    .iter()
+                    .copied()
+                    .find(|known_rule| *known_rule == rule)
+                    .ok_or(format!("unsupported rename rule '{}'", rule))?,
+            ),
+            (None, true) => predominant_rename_rule(&schema),
+            (None, false) => None,
+        };
+
         let title = get_prop_str(&schema, "title")?.map(|s| s.to_owned());
         let description = get_prop_str(&schema, "description")?.map(|s| s.to_owned());
-        let subschema_defs = None
-            .or(get_prop_obj(&schema, "$defs")?)
-            .or(get_prop_obj(&schema, "definintions")?);
+        let mut subschema_defs = IndexMap::new();
+        for defs_key in ["$defs", "definitions"] {
+            for (subschema_name, subschema_value) in
+                get_prop_obj(&types_schema, defs_key)?.into_iter().flatten()
+            {
+                if subschema_defs
+                    .insert(subschema_name, subschema_value)
+                    .is_some()
+                {
+                    return Err(format!(
+                        "subschema '{}' is declared under both `$defs` and `definitions`",
+                        subschema_name
+                    )
+                    .into());
+                }
+            }
+        }
 
         let name = ident
             .map(|i| i.to_string())
             .or(title)
             .ok_or("no struct identifier specified in schema or macro invocation")?;
 
+        let external_types = external_types
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(ref_path, ty)| match RefType::from_path(&ref_path)? {
+                RefType::Subschema(subschema_name) => Ok((subschema_name, ty)),
+                RefType::Root => {
+                    Err("external types can only replace subschemas, not the root object".into())
+                }
+            })
+            .collect::<Result<IndexMap<_, _>, SchemaStructError>>()?;
+
+        if let Some(subschema_name) = external_types
+            .keys()
+            .find(|subschema_name| !subschema_defs.contains_key(*subschema_name))
+        {
+            return Err(format!("unknown subschema definition '{}'", subschema_name).into());
+        }
+
+        // Subschemas replaced by external types are never generated, so they
+        // are not parsed either.
         let subschemas = subschema_defs
-            .map(|subschema_defs| {
-                subschema_defs
-                    .iter()
-                    .map(|(subschema_name, subschema_value)| {
-                        let mut subschema_info = FieldInfo {
-                            name: subschema_name.clone(),
-                            description: None,
-                            required: true,
-                            subschema: true,
-                        };
-                        Subschema::from_schema(subschema_value, &mut subschema_info)
-                            .map(|subschema| (subschema_name.clone(), subschema))
-                    })
-                    .collect::<Result<IndexMap<_, _>, _>>()
+            .into_iter()
+            .filter(|(subschema_name, _)| !external_types.contains_key(*subschema_name))
+            .map(|(subschema_name, subschema_value)| {
+                let mut subschema_info = FieldInfo {
+                    name: subschema_name.clone(),
+                    description: None,
+                    required: true,
+                    subschema: true,
+                };
+                Subschema::from_schema(subschema_value, &mut subschema_info)
+                    .map(|subschema| (subschema_name.clone(), subschema))
             })
-            .unwrap_or(Ok(IndexMap::new()))?;
+            .collect::<Result<IndexMap<_, _>, _>>()?;
 
         let mut field_info = FieldInfo {
             name: name.clone(),
@@ -509,13 +1220,45 @@ impl SchemaStruct {
             required: true,
             subschema: false,
         };
-        let root = ObjectField::from_schema(&schema, &mut field_info)?;
+        let root = ObjectField::from_schema(&types_schema, &mut field_info)?;
 
         Ok(Self {
             vis: vis.unwrap_or(Visibility::Inherited),
             def: def.unwrap_or(true),
-            validate: validate.unwrap_or(false).then_some(schema),
+            validate_on_serialize: validate_on_serialize
+                .unwrap_or(false)
+                .then(|| schema.clone()),
+            validate: validate.unwrap_or(false).then(|| schema.clone()),
             debug: debug.unwrap_or(false),
+            debug_file,
+            generate_tests: generate_tests.unwrap_or(false),
+            ordered_enum: ordered_enum.unwrap_or(false),
+            internal_path,
+            rename_all,
+            boxed_slices: boxed_slices.unwrap_or(false),
+            raw_idents: raw_idents.unwrap_or(false),
+            custom_serde: custom_serde.unwrap_or(false),
+            serde,
+            external_types,
+            doc_table: doc_table.unwrap_or(false),
+            strict: strict.unwrap_or(false),
+            method_names: MethodNames {
+                parse: parse_method.unwrap_or_else(|| format_ident!("from_str")),
+                emit: emit_method.unwrap_or_else(|| format_ident!("to_str")),
+            },
+            derives: derives.unwrap_or_default(),
+            skip_none: skip_none.unwrap_or(false),
+            omit_empty_collections: omit_empty_collections.unwrap_or(false),
+            default_impl: default_impl.unwrap_or(false),
+            constructor: constructor.unwrap_or(false),
+            diff: diff.unwrap_or(false),
+            repr,
+            small_vec_capacity,
+            map_type,
+            float_precision,
+            compat: compat.unwrap_or_default(),
+            source: schema,
+            cyclic_refs: cyclic_refs(&types_schema),
             name,
             description,
             subschemas,
@@ -523,10 +1266,17 @@ impl SchemaStruct {
         })
     }
 
+    /// Gets the path of the type to represent maps as.
+    pub fn map_path(&self) -> TokenStream {
+        let map_type = &self.map_type;
+        quote!(::std::collections::#map_type)
+    }
+
     /// Generates Rust code from the data structure representation.
     pub fn to_struct(&self) -> Result<SchemaStructDef, SchemaStructError> {
-        let internal_path = match crate_name("schema-struct") {
-            Ok(FoundCrate::Name(name)) => {
+        let internal_path = match (&self.internal_path, crate_name("schema-struct")) {
+            (Some(path), _) => quote!(#path::__internal),
+            (None, Ok(FoundCrate::Name(name))) => {
                 let ident = format_ident!("{}", name);
                 quote!(::#ident::__internal)
             }
@@ -545,28 +1295,61 @@ impl SchemaStruct {
             name_prefix: String::new(),
             vis: self.vis.clone(),
             internal_path: internal_path.clone(),
+            container: "#".to_owned(),
         };
 
-        let (mut defs, mut defs_doc) = self.subschemas.iter().try_fold(
-            (Vec::new(), Vec::new()),
-            |(mut defs, mut defs_doc), (subschema_name, subschema)| {
-                let subschema_info = FieldInfo {
-                    name: subschema_name.clone(),
-                    description: None,
-                    required: true,
-                    subschema: true,
-                };
-                let subschema_def = subschema.to_struct(&subschema_info, &ctx)?;
-                defs.extend(subschema_def.defs);
-                defs_doc.extend(subschema_def.defs_doc);
-                Result::<_, SchemaStructError>::Ok((defs, defs_doc))
-            },
-        )?;
+        let (mut defs, mut defs_doc) = self
+            .subschemas
+            .iter()
+            .filter(|(subschema_name, _)| !self.external_types.contains_key(*subschema_name))
+            .try_fold(
+                (Vec::new(), Vec::new()),
+                |(mut defs, mut defs_doc), (subschema_name, subschema)| {
+                    let subschema_info = FieldInfo {
+                        name: subschema_name.clone(),
+                        description: None,
+                        required: true,
+                        subschema: true,
+                    };
+                    let subschema_ctx = FieldContext {
+                        container: subschema_name.clone(),
+                        ..ctx.clone()
+                    };
+                    let subschema_def = subschema.to_struct(&subschema_info, &subschema_ctx)?;
+                    defs.extend(subschema_def.defs);
+                    defs_doc.extend(subschema_def.defs_doc);
+                    Result::<_, SchemaStructError>::Ok((defs, defs_doc))
+                },
+            )?;
 
         let root_def = self.root.to_struct(&info, &ctx)?;
         defs.extend(root_def.defs);
         defs_doc.extend(root_def.defs_doc);
 
+        let doc_table = if self.doc_table {
+            let fields_ctx = FieldContext {
+                name_prefix: self.name.clone(),
+                ..ctx.clone()
+            };
+            let rows = self
+                .root
+                .fields
+                .values()
+                .map(|field| {
+                    let field_def = field.to_struct(&info, &fields_ctx)?;
+                    Ok((
+                        field.info.name.clone(),
+                        field_def.field_ty,
+                        field.info.required,
+                        field.info.description.clone(),
+                    ))
+                })
+                .collect::<Result<Vec<_>, SchemaStructError>>()?;
+            Some(fields_table(&rows))
+        } else {
+            None
+        };
+
         let ident = format_ident!("{}", renamed_struct(&self.name));
 
         Ok(SchemaStructDef {
@@ -575,8 +1358,17 @@ impl SchemaStruct {
             ident,
             defs,
             defs_doc: self.def.then_some(defs_doc),
+            doc_table,
+            schema_version: get_prop_str(&self.source, "$schema")?.map(ToOwned::to_owned),
             validate: self.validate.clone(),
+            validate_on_serialize: self.validate_on_serialize.clone(),
             debug: self.debug,
+            generate_tests: self.generate_tests,
+            debug_file: self.debug_file.clone(),
+            compat: self.compat.iter().map(ToTokens::to_token_stream).collect(),
+            diff: self.diff,
+            serde: self.serde,
+            method_names: self.method_names.clone(),
             internal_path,
         })
     }
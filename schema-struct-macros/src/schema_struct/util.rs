@@ -1,9 +1,13 @@
-use super::types::{SchemaStructError, ValueType};
+use super::types::{MethodNames, NumberBounds, SchemaStructError, SerdeTraits, ValueType};
 use convert_case::{Case, Casing};
-use proc_macro2::TokenStream;
-use quote::quote;
+use jsonschema::JSONSchema;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
 use regex::Regex;
-use serde_json::{Map, Value};
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use syn::Path;
 
 const RUST_KEYWORDS: &[&str] = &[
     "abstract",
@@ -75,7 +79,6 @@ pub fn get_prop_null(value: &Value, prop: &str) -> Result<Option<()>, String> {
 }
 
 /// Retrieves a boolean property from a JSON value.
-#[allow(dead_code)]
 pub fn get_prop_bool(value: &Value, prop: &str) -> Result<Option<bool>, String> {
     match value.get(prop) {
         Some(prop_value) => prop_value
@@ -99,7 +102,6 @@ pub fn get_prop_int(value: &Value, prop: &str) -> Result<Option<i64>, String> {
 }
 
 /// Retrieves a number property from a JSON value.
-#[allow(dead_code)]
 pub fn get_prop_number(value: &Value, prop: &str) -> Result<Option<f64>, String> {
     match value.get(prop) {
         Some(prop_value) => prop_value
@@ -110,6 +112,50 @@ pub fn get_prop_number(value: &Value, prop: &str) -> Result<Option<f64>, String>
     }
 }
 
+/// Retrieves a bound property from a JSON value as an exact number.
+fn get_prop_bound(value: &Value, prop: &str) -> Result<Option<Number>, String> {
+    match value.get(prop) {
+        Some(Value::Number(number)) => Ok(Some(number.clone())),
+        Some(_) => Err(format!("expected property `{}` to be a number", prop)),
+        None => Ok(None),
+    }
+}
+
+/// Retrieves an exclusive bound from a JSON value. Draft 4 declares
+/// `exclusiveMinimum` and `exclusiveMaximum` as booleans which make the
+/// corresponding inclusive bound exclusive, while later drafts declare them as
+/// numbers.
+fn get_prop_exclusive_bound(
+    value: &Value,
+    prop: &str,
+    inclusive: Option<&Number>,
+) -> Result<Option<Number>, String> {
+    match value.get(prop) {
+        Some(Value::Bool(true)) => Ok(inclusive.cloned()),
+        Some(Value::Bool(false)) | None => Ok(None),
+        Some(Value::Number(number)) => Ok(Some(number.clone())),
+        Some(_) => Err(format!(
+            "expected property `{}` to be a boolean or a number",
+            prop
+        )),
+    }
+}
+
+/// Retrieves the bounds declared on an integer or number schema.
+pub fn get_number_bounds(value: &Value) -> Result<NumberBounds, String> {
+    let minimum = get_prop_bound(value, "minimum")?;
+    let maximum = get_prop_bound(value, "maximum")?;
+    let exclusive_minimum = get_prop_exclusive_bound(value, "exclusiveMinimum", minimum.as_ref())?;
+    let exclusive_maximum = get_prop_exclusive_bound(value, "exclusiveMaximum", maximum.as_ref())?;
+
+    Ok(NumberBounds {
+        minimum,
+        exclusive_minimum,
+        maximum,
+        exclusive_maximum,
+    })
+}
+
 /// Retrieves a string property from a JSON value.
 pub fn get_prop_str<'a>(value: &'a Value, prop: &str) -> Result<Option<&'a str>, String> {
     match value.get(prop) {
@@ -121,6 +167,18 @@ pub fn get_prop_str<'a>(value: &'a Value, prop: &str) -> Result<Option<&'a str>,
     }
 }
 
+/// Retrieves a non-negative integer property, such as a length, from a JSON
+/// value.
+pub fn get_prop_length(value: &Value, prop: &str) -> Result<Option<u64>, String> {
+    match value.get(prop) {
+        Some(prop_value) => prop_value.as_u64().map(Some).ok_or(format!(
+            "expected property `{}` to be a non-negative integer",
+            prop
+        )),
+        None => Ok(None),
+    }
+}
+
 /// Retrieves an array property from a JSON value.
 pub fn get_prop_array<'a>(value: &'a Value, prop: &str) -> Result<Option<&'a Vec<Value>>, String> {
     match value.get(prop) {
@@ -146,9 +204,50 @@ pub fn get_prop_obj<'a>(
     }
 }
 
+/// Gets the name of a JSON value's type. A type array containing `null` and
+/// one other type resolves to the other type, while `["null"]` resolves to
+/// `null`.
+pub fn get_type_name(value: &Value) -> Result<Option<&str>, String> {
+    match value.get("type") {
+        None => Ok(None),
+        Some(Value::String(ty)) => Ok(Some(ty)),
+        Some(Value::Array(tys)) => {
+            let names = tys
+                .iter()
+                .map(|ty| {
+                    ty.as_str()
+                        .ok_or("value types in a type array must be strings".to_owned())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let non_null = names.iter().filter(|ty| **ty != "null").collect::<Vec<_>>();
+
+            match (&non_null[..], names.contains(&"null")) {
+                ([], true) => Ok(Some("null")),
+                ([ty], _) => Ok(Some(ty)),
+                _ => Err(format!(
+                    "type arrays may only contain `null` and at most one other type, found {}",
+                    Value::Array(tys.clone())
+                )),
+            }
+        }
+        Some(_) => Err("value type must be a string or an array of strings".to_owned()),
+    }
+}
+
+/// Checks whether a JSON value's type is an array containing `null` and one
+/// other type, making the value nullable.
+pub fn is_nullable_type_array(value: &Value) -> bool {
+    match value.get("type") {
+        Some(Value::Array(tys)) => {
+            tys.iter().any(|ty| ty == "null") && tys.iter().any(|ty| ty != "null")
+        }
+        _ => false,
+    }
+}
+
 /// Asserts that a JSON value's type matches as expected.
 pub fn assert_value_type(value: &Value, ty: &str) -> Result<(), String> {
-    let found_ty = get_prop_str(value, "type")?.ok_or("no type specified".to_owned())?;
+    let found_ty = get_type_name(value)?.ok_or("no type specified".to_owned())?;
 
     if found_ty == ty {
         Ok(())
@@ -162,34 +261,751 @@ pub fn assert_value_type(value: &Value, ty: &str) -> Result<(), String> {
 
 /// Parses a JSON value's type.
 pub fn parse_value_type(value: &Value) -> Result<ValueType, SchemaStructError> {
-    ValueType::from_str(match value.get("type") {
-        Some(ty) => {
-            match ty
-                .as_str()
-                .ok_or("value type must be a string".to_owned())?
-            {
-                "array" => {
-                    if value.get("prefixItems").is_some() {
-                        "tuple"
-                    } else {
-                        "array"
-                    }
+    // A constant pins the value regardless of its declared type.
+    if value.get("const").is_some() {
+        return Ok(ValueType::Const);
+    }
+
+    ValueType::from_str(match get_type_name(value)? {
+        Some(ty) => match ty {
+            "array" => {
+                if value.get("prefixItems").is_some() {
+                    "tuple"
+                } else {
+                    "array"
                 }
-                ty_str => ty_str,
             }
-        }
+            ty_str => ty_str,
+        },
         None => None
+            .or(value.get("oneOf").map(|_| "oneOf"))
+            .or(value.get("anyOf").map(|_| "anyOf"))
             .or(value.get("enum").map(|_| "enum"))
             .or(value.get("$ref").map(|_| "ref"))
+            .or(value.get("$recursiveRef").map(|_| "ref"))
+            .or(value.get("$dynamicRef").map(|_| "ref"))
             .ok_or("value type not specified".to_owned())?,
     })
 }
 
-/// Nicely formats a Rust token stream.
+/// Checks whether a JSON value's type allows `null`, either by being of type
+/// `null` itself or by including `null` in an array of types.
+pub fn allows_null(value: &Value) -> bool {
+    match value.get("type") {
+        Some(Value::String(ty)) => ty == "null",
+        Some(Value::Array(tys)) => tys.iter().any(|ty| ty == "null"),
+        _ => false,
+    }
+}
+
+/// Returns a copy of a schema in which every enum with a single string
+/// variant is replaced by an equivalent constant.
+pub fn with_unit_enums_as_const(value: &Value) -> Value {
+    let mut value = value.clone();
+    replace_unit_enums(&mut value);
+    value
+}
+
+/// Recursively replaces enums with a single string variant by constants.
+fn replace_unit_enums(value: &mut Value) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+
+    if let Some(Value::Array(variants)) = obj.get("enum") {
+        if let [variant @ Value::String(_)] = variants.as_slice() {
+            let variant = variant.clone();
+            obj.remove("enum");
+            obj.insert("const".to_owned(), variant);
+        }
+    }
+
+    for (key, inner) in obj.iter_mut() {
+        match key.as_str() {
+            "properties" | "$defs" | "definitions" => {
+                if let Some(inner) = inner.as_object_mut() {
+                    inner.values_mut().for_each(replace_unit_enums);
+                }
+            }
+            "prefixItems" | "oneOf" | "anyOf" => {
+                if let Some(inner) = inner.as_array_mut() {
+                    inner.iter_mut().for_each(replace_unit_enums);
+                }
+            }
+            "items" | "additionalProperties" => replace_unit_enums(inner),
+            _ => {}
+        }
+    }
+}
+
+/// Returns a copy of a schema in which every property of every object is
+/// marked as required, unless the property's type allows `null`.
+pub fn with_required_by_default(value: &Value) -> Value {
+    let mut value = value.clone();
+    mark_required_by_default(&mut value);
+    value
+}
+
+/// Recursively marks the properties of all object schemas as required,
+/// unless their types allow `null`.
+fn mark_required_by_default(value: &mut Value) {
+    let Some(obj) = value.as_object_mut() else {
+        return;
+    };
+
+    if let Some(Value::Object(properties)) = obj.get("properties") {
+        let mut required = match obj.get("required") {
+            Some(Value::Array(required)) => required.clone(),
+            _ => Vec::new(),
+        };
+
+        for (property_name, property_value) in properties {
+            let property_name = Value::String(property_name.clone());
+
+            if !allows_null(property_value) && !required.contains(&property_name) {
+                required.push(property_name);
+            }
+        }
+
+        obj.insert("required".to_owned(), Value::Array(required));
+    }
+
+    for (key, inner) in obj.iter_mut() {
+        match key.as_str() {
+            "properties" | "$defs" | "definitions" => {
+                if let Some(inner) = inner.as_object_mut() {
+                    inner.values_mut().for_each(mark_required_by_default);
+                }
+            }
+            "prefixItems" | "oneOf" | "anyOf" => {
+                if let Some(inner) = inner.as_array_mut() {
+                    inner.iter_mut().for_each(mark_required_by_default);
+                }
+            }
+            "items" | "additionalProperties" => mark_required_by_default(inner),
+            _ => {}
+        }
+    }
+}
+
+/// Recursively collects all ref paths that point somewhere other than the
+/// root object or a subschema.
+fn collect_pointer_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::String(path)) = obj.get("$ref") {
+                let segments = path.split('/').collect::<Vec<_>>();
+                let is_subschema = matches!(&segments[..], &["#", "$defs" | "definitions", _]);
+
+                if path.starts_with("#/") && !is_subschema && !refs.contains(path) {
+                    refs.push(path.clone());
+                }
+            }
+
+            obj.values()
+                .for_each(|inner| collect_pointer_refs(inner, refs));
+        }
+        Value::Array(arr) => arr
+            .iter()
+            .for_each(|inner| collect_pointer_refs(inner, refs)),
+        _ => {}
+    }
+}
+
+/// Recursively rewrites all refs with the given path to point to a new path.
+fn rewrite_refs(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::Object(obj) => {
+            if obj.get("$ref").and_then(Value::as_str) == Some(from) {
+                obj.insert("$ref".to_owned(), Value::String(to.to_owned()));
+            }
+
+            obj.values_mut()
+                .for_each(|inner| rewrite_refs(inner, from, to));
+        }
+        Value::Array(arr) => arr
+            .iter_mut()
+            .for_each(|inner| rewrite_refs(inner, from, to)),
+        _ => {}
+    }
+}
+
+/// Returns a copy of a schema loaded from a directory in which refs to other
+/// schema files in the directory (e.g. `other.json` or `./other.json`) point
+/// to empty placeholder subschemas instead, so that they can be replaced by
+/// the types generated for those files. Also returns the ref path of each
+/// placeholder along with the file name it stands for.
+pub fn with_file_refs_as_subschemas(
+    value: &Value,
+    file_names: &[String],
+) -> Result<(Value, Vec<(String, String)>), String> {
+    let mut value = value.clone();
+    let mut file_refs = Vec::new();
+
+    let defs_key = if value.get("$defs").is_none() && value.get("definitions").is_some() {
+        "definitions"
+    } else {
+        "$defs"
+    };
+
+    for file_name in file_names {
+        let subschema_path = format!("#/{}/{}", defs_key, file_name);
+        let before = value.clone();
+        rewrite_refs(&mut value, file_name, &subschema_path);
+        rewrite_refs(&mut value, &format!("./{}", file_name), &subschema_path);
+
+        if value == before {
+            continue;
+        }
+
+        let defs = value
+            .as_object_mut()
+            .ok_or("expected schema to be an object")?
+            .entry(defs_key)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or(format!("expected property `{}` to be an object", defs_key))?;
+
+        if defs.contains_key(file_name) {
+            return Err(format!(
+                "cannot create subschema '{}' for ref to file '{}', as a subschema with that name already exists",
+                file_name, file_name
+            ));
+        }
+
+        defs.insert(file_name.clone(), Value::Object(Map::new()));
+        file_refs.push((subschema_path, file_name.clone()));
+    }
+
+    Ok((value, file_refs))
+}
+
+/// Resolves a default value expressed as a ref, e.g.
+/// `{ "$ref": "#/$defs/defaultMoney" }`, to the value it points to within
+/// the schema. If the referenced location is a `const` schema, its constant
+/// value is used. Defaults that are not refs are returned unchanged.
+pub fn resolve_default<'a>(
+    value: Option<&'a Value>,
+    schema: &'a Value,
+) -> Result<Option<&'a Value>, String> {
+    let mut value = match value {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let mut visited = Vec::new();
+
+    while let Some(path) = default_ref_path(value) {
+        if visited.contains(&path) {
+            return Err(format!("default value ref '{}' is cyclic", path));
+        }
+
+        let pointer = path.strip_prefix('#').ok_or(format!(
+            "default value ref '{}' is not supported, only refs within the schema are allowed",
+            path
+        ))?;
+        let target = schema.pointer(pointer).ok_or(format!(
+            "default value ref '{}' does not point into the schema",
+            path
+        ))?;
+
+        visited.push(path);
+        value = target.get("const").unwrap_or(target);
+    }
+
+    Ok(Some(value))
+}
+
+/// Returns the path of a default value consisting solely of a `$ref`.
+fn default_ref_path(value: &Value) -> Option<&str> {
+    match value.as_object() {
+        Some(obj) if obj.len() == 1 => obj.get("$ref").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// Walks a JSON pointer ref path (e.g. `#/properties/address`) through a
+/// schema, returning the referenced location. Errors name the first segment
+/// that could not be resolved.
+fn pointer_target_mut<'a>(value: &'a mut Value, path: &str) -> Result<&'a mut Value, String> {
+    let mut target = value;
+
+    for segment in path.trim_start_matches("#/").split('/') {
+        let key = segment.replace("~1", "/").replace("~0", "~");
+        let next = match target {
+            Value::Object(obj) => obj.get_mut(&key),
+            Value::Array(arr) => key.parse::<usize>().ok().and_then(|i| arr.get_mut(i)),
+            _ => None,
+        };
+
+        target = next.ok_or(format!(
+            "ref path '{}' does not point into the schema, as segment '{}' could not be resolved",
+            path, segment
+        ))?;
+    }
+
+    Ok(target)
+}
+
+/// Returns a copy of a schema in which every location referenced by a JSON
+/// pointer ref (e.g. `#/properties/tags/items`) is moved into a subschema,
+/// leaving a ref to the new subschema in its place. This allows the
+/// referenced type to be generated once and shared.
+pub fn with_pointer_refs_as_subschemas(value: &Value) -> Result<Value, String> {
+    let mut value = value.clone();
+    let mut refs = Vec::new();
+    collect_pointer_refs(&value, &mut refs);
+
+    // Deeper locations are moved first, so that a location containing
+    // another referenced location carries the new ref along with it.
+    refs.sort_by_key(|path| std::cmp::Reverse(path.matches('/').count()));
+
+    let defs_key = if value.get("$defs").is_none() && value.get("definitions").is_some() {
+        "definitions"
+    } else {
+        "$defs"
+    };
+
+    for path in refs {
+        let subschema_name = path
+            .trim_start_matches("#/")
+            .replace('$', "")
+            .replace('/', "_")
+            .replace("~1", "_")
+            .replace("~0", "_");
+        let subschema_path = format!("#/{}/{}", defs_key, subschema_name);
+
+        let target = pointer_target_mut(&mut value, &path)?;
+        let subschema = std::mem::replace(target, Value::Null);
+        *target = serde_json::json!({ "$ref": subschema_path });
+
+        rewrite_refs(&mut value, &path, &subschema_path);
+
+        let defs = value
+            .as_object_mut()
+            .ok_or("expected schema to be an object")?
+            .entry(defs_key)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or(format!("expected property `{}` to be an object", defs_key))?;
+
+        if defs.contains_key(&subschema_name) {
+            return Err(format!(
+                "cannot create subschema '{}' for ref path '{}', as a subschema with that name already exists",
+                subschema_name, path
+            ));
+        }
+
+        defs.insert(subschema_name, subschema);
+    }
+
+    Ok(value)
+}
+
+/// Recursively collects the types referenced by a value, named `#` for the
+/// root object and by name for subschemas.
+fn collect_type_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(obj) => {
+            for key in ["$ref", "$recursiveRef", "$dynamicRef"] {
+                if let Some(Value::String(path)) = obj.get(key) {
+                    let target = match &path.split('/').collect::<Vec<_>>()[..] {
+                        ["#"] => Some("#"),
+                        ["#", "$defs" | "definitions", name] => Some(*name),
+                        _ => None,
+                    };
+
+                    if let Some(target) = target {
+                        if !refs.iter().any(|r| r == target) {
+                            refs.push(target.to_owned());
+                        }
+                    }
+                }
+            }
+
+            obj.values()
+                .for_each(|inner| collect_type_refs(inner, refs));
+        }
+        Value::Array(arr) => arr.iter().for_each(|inner| collect_type_refs(inner, refs)),
+        _ => {}
+    }
+}
+
+/// Finds the refs that lead back to the type containing them, and so must be
+/// boxed. Each ref is given as a pair of the containing type and the
+/// referenced type, named `#` for the root object and by name for
+/// subschemas.
+pub fn cyclic_refs(value: &Value) -> HashSet<(String, String)> {
+    let defs_keys = ["$defs", "definitions"];
+    let mut graph = HashMap::new();
+
+    let mut root_refs = Vec::new();
+    if let Some(obj) = value.as_object() {
+        obj.iter()
+            .filter(|(key, _)| !defs_keys.contains(&key.as_str()))
+            .for_each(|(_, inner)| collect_type_refs(inner, &mut root_refs));
+    }
+    graph.insert("#".to_owned(), root_refs);
+
+    for key in defs_keys {
+        if let Some(Value::Object(defs)) = value.get(key) {
+            for (name, def) in defs {
+                let mut refs = Vec::new();
+                collect_type_refs(def, &mut refs);
+                graph.insert(name.clone(), refs);
+            }
+        }
+    }
+
+    let reaches = |from: &str, to: &str| {
+        let mut reachable = vec![from.to_owned()];
+        let mut index = 0;
+
+        while index < reachable.len() {
+            if reachable[index] == to {
+                return true;
+            }
+
+            for next in graph.get(&reachable[index]).into_iter().flatten() {
+                if !reachable.contains(next) {
+                    reachable.push(next.clone());
+                }
+            }
+
+            index += 1;
+        }
+
+        false
+    };
+
+    graph
+        .iter()
+        .flat_map(|(container, refs)| {
+            refs.iter()
+                .filter(|target| reaches(target, container))
+                .map(|target| (container.clone(), target.clone()))
+        })
+        .collect()
+}
+
+/// Recursively collects the names of all subschemas referenced by a value.
+fn collect_subschema_refs(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::String(path)) = obj.get("$ref") {
+                if let &["#", "$defs" | "definitions", name] =
+                    &path.split('/').collect::<Vec<_>>()[..]
+                {
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_owned());
+                    }
+                }
+            }
+
+            obj.values()
+                .for_each(|inner| collect_subschema_refs(inner, names));
+        }
+        Value::Array(arr) => arr
+            .iter()
+            .for_each(|inner| collect_subschema_refs(inner, names)),
+        _ => {}
+    }
+}
+
+/// Returns a copy of a schema in which only the listed subschema
+/// definitions, along with any subschemas they or the root object reference,
+/// are kept. All other definitions are removed.
+pub fn with_only_defs(value: &Value, allowed: &[String]) -> Result<Value, String> {
+    let mut value = value.clone();
+    let defs_keys = ["$defs", "definitions"];
+    let get_def = |value: &Value, name: &str| {
+        defs_keys
+            .iter()
+            .find_map(|key| value.get(key).and_then(|defs| defs.get(name)))
+            .cloned()
+    };
+
+    let mut keep = Vec::new();
+
+    if let Some(obj) = value.as_object() {
+        obj.iter()
+            .filter(|(key, _)| !defs_keys.contains(&key.as_str()))
+            .for_each(|(_, inner)| collect_subschema_refs(inner, &mut keep));
+    }
+
+    for name in allowed {
+        if get_def(&value, name).is_none() {
+            return Err(format!("unknown subschema definition '{}'", name));
+        }
+
+        if !keep.contains(name) {
+            keep.push(name.clone());
+        }
+    }
+
+    // `keep` grows as referenced subschemas are discovered, so it is walked
+    // by index until no new names are added.
+    let mut index = 0;
+
+    while index < keep.len() {
+        if let Some(def) = get_def(&value, &keep[index]) {
+            collect_subschema_refs(&def, &mut keep);
+        }
+
+        index += 1;
+    }
+
+    for key in defs_keys {
+        if let Some(Value::Object(defs)) = value.get_mut(key) {
+            defs.retain(|name, _| keep.contains(name));
+        }
+    }
+
+    Ok(value)
+}
+
+/// Recursively counts the refs to each subschema within a value.
+fn count_subschema_refs(value: &Value, counts: &mut HashMap<String, usize>) {
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::String(path)) = obj.get("$ref") {
+                if let &["#", "$defs" | "definitions", name] =
+                    &path.split('/').collect::<Vec<_>>()[..]
+                {
+                    *counts.entry(name.to_owned()).or_default() += 1;
+                }
+            }
+
+            obj.values()
+                .for_each(|inner| count_subschema_refs(inner, counts));
+        }
+        Value::Array(arr) => arr
+            .iter()
+            .for_each(|inner| count_subschema_refs(inner, counts)),
+        _ => {}
+    }
+}
+
+/// Recursively replaces the single ref to a subschema with the subschema
+/// itself. Any properties alongside the ref are kept. Returns whether the ref
+/// was found.
+fn inline_subschema_ref(value: &mut Value, name: &str, subschema: &Value) -> bool {
+    match value {
+        Value::Object(obj) => {
+            let is_ref = obj
+                .get("$ref")
+                .and_then(Value::as_str)
+                .map(|path| {
+                    matches!(
+                        &path.split('/').collect::<Vec<_>>()[..],
+                        &["#", "$defs" | "definitions", ref_name] if ref_name == name
+                    )
+                })
+                .unwrap_or(false);
+
+            if is_ref {
+                obj.remove("$ref");
+                let siblings = std::mem::take(obj);
+                *value = subschema.clone();
+
+                if let Value::Object(inlined) = value {
+                    inlined.extend(siblings);
+                }
+
+                return true;
+            }
+
+            obj.values_mut()
+                .any(|inner| inline_subschema_ref(inner, name, subschema))
+        }
+        Value::Array(arr) => arr
+            .iter_mut()
+            .any(|inner| inline_subschema_ref(inner, name, subschema)),
+        _ => false,
+    }
+}
+
+/// Returns a copy of a schema in which every subschema that is referenced
+/// exactly once, and is not part of a reference cycle, is inlined at the
+/// location of its ref and removed from the subschema definitions.
+pub fn with_single_use_refs_inlined(value: &Value) -> Value {
+    let mut value = value.clone();
+    let defs_keys = ["$defs", "definitions"];
+    let get_def = |value: &Value, name: &str| {
+        defs_keys
+            .iter()
+            .find_map(|key| value.get(key).and_then(|defs| defs.get(name)))
+            .cloned()
+    };
+
+    let mut counts = HashMap::new();
+    count_subschema_refs(&value, &mut counts);
+
+    let mut single_use = counts
+        .into_iter()
+        .filter(|(_, count)| *count == 1)
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    single_use.sort();
+
+    for name in single_use {
+        let Some(subschema) = get_def(&value, &name) else {
+            continue;
+        };
+
+        // A subschema that can reach itself through refs must stay behind a
+        // `Box`, so it is not inlined.
+        let mut reachable = Vec::new();
+        collect_subschema_refs(&subschema, &mut reachable);
+        let mut index = 0;
+
+        while index < reachable.len() {
+            if let Some(def) = get_def(&value, &reachable[index]) {
+                collect_subschema_refs(&def, &mut reachable);
+            }
+
+            index += 1;
+        }
+
+        if reachable.contains(&name) {
+            continue;
+        }
+
+        for key in defs_keys {
+            if let Some(Value::Object(defs)) = value.get_mut(key) {
+                defs.shift_remove(&name);
+            }
+        }
+
+        inline_subschema_ref(&mut value, &name, &subschema);
+    }
+
+    value
+}
+
+/// Recursively collects the property names of all object schemas.
+fn collect_property_names<'a>(value: &'a Value, names: &mut Vec<&'a str>) {
+    let Some(obj) = value.as_object() else {
+        return;
+    };
+
+    for (key, inner) in obj {
+        match key.as_str() {
+            "properties" => {
+                if let Some(inner) = inner.as_object() {
+                    names.extend(inner.keys().map(String::as_str));
+                    inner
+                        .values()
+                        .for_each(|inner| collect_property_names(inner, names));
+                }
+            }
+            "$defs" | "definitions" => {
+                if let Some(inner) = inner.as_object() {
+                    inner
+                        .values()
+                        .for_each(|inner| collect_property_names(inner, names));
+                }
+            }
+            "prefixItems" | "oneOf" | "anyOf" => {
+                if let Some(inner) = inner.as_array() {
+                    inner
+                        .iter()
+                        .for_each(|inner| collect_property_names(inner, names));
+                }
+            }
+            "items" | "additionalProperties" => collect_property_names(inner, names),
+            _ => {}
+        }
+    }
+}
+
+/// Detects the predominant casing style of the property names in a schema,
+/// returning the matching serde `rename_all` rule. Returns `None` when names
+/// are predominantly snake_case, since generated field names already match.
+pub fn predominant_rename_rule(value: &Value) -> Option<&'static str> {
+    let mut names = Vec::new();
+    collect_property_names(value, &mut names);
+
+    let (mut snake, mut camel, mut kebab) = (0, 0, 0);
+
+    for name in names {
+        let has_upper = name.chars().any(|c| c.is_ascii_uppercase());
+        let has_underscore = name.contains('_');
+        let has_hyphen = name.contains('-');
+
+        if has_hyphen && !has_underscore && !has_upper {
+            kebab += 1;
+        } else if has_underscore && !has_hyphen && !has_upper {
+            snake += 1;
+        } else if has_upper
+            && name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            camel += 1;
+        }
+    }
+
+    if camel > snake && camel >= kebab {
+        Some("camelCase")
+    } else if kebab > snake && kebab > camel {
+        Some("kebab-case")
+    } else {
+        None
+    }
+}
+
+/// The serde `rename_all` rules that can be applied to generated structs.
+pub const RENAME_RULES: &[&str] = &[
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+/// Applies a serde `rename_all` rule to a snake_case field name, in the same
+/// way serde does.
+pub fn apply_rename_rule(field_name: &str, rule: &str) -> String {
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field_name.to_ascii_uppercase(),
+        "PascalCase" | "camelCase" => {
+            let mut renamed = String::new();
+            let mut capitalize = true;
+
+            for c in field_name.chars() {
+                if c == '_' {
+                    capitalize = true;
+                } else if capitalize {
+                    renamed.push(c.to_ascii_uppercase());
+                    capitalize = false;
+                } else {
+                    renamed.push(c);
+                }
+            }
+
+            if rule == "camelCase" {
+                let mut chars = renamed.chars();
+                if let Some(first) = chars.next() {
+                    renamed = first.to_ascii_lowercase().to_string() + chars.as_str();
+                }
+            }
+
+            renamed
+        }
+        "kebab-case" => field_name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field_name.to_ascii_uppercase().replace('_', "-"),
+        _ => field_name.to_owned(),
+    }
+}
+
+/// Nicely formats a Rust token stream. Each token stream may hold any number
+/// of items.
 pub fn pretty_print_token_stream(tokenstreams: &[TokenStream]) -> String {
     let items = tokenstreams
         .iter()
-        .map(|tokens| syn::parse2(tokens.clone()).unwrap())
+        .flat_map(|tokens| syn::parse2::<syn::File>(tokens.clone()).unwrap().items)
         .collect();
 
     let file = syn::File {
@@ -201,18 +1017,111 @@ pub fn pretty_print_token_stream(tokenstreams: &[TokenStream]) -> String {
     prettyplease::unparse(&file)
 }
 
-pub fn renamed_rust_keyword(name: &str) -> String {
-    if RUST_KEYWORDS.contains(&name) {
-        format!("{}_", name)
-    } else {
+/// Formats of `string` schemas that are recognized, whether or not they
+/// change the generated type.
+pub const STRING_FORMATS: &[&str] = &[
+    "date-time",
+    "date",
+    "time",
+    "duration",
+    "email",
+    "idn-email",
+    "hostname",
+    "idn-hostname",
+    "ipv4",
+    "ipv6",
+    "uri",
+    "uri-reference",
+    "iri",
+    "iri-reference",
+    "uuid",
+    "uri-template",
+    "json-pointer",
+    "relative-json-pointer",
+    "regex",
+];
+
+/// Formats of `integer` schemas that are recognized.
+pub const INTEGER_FORMATS: &[&str] = &["int32", "int64", "uint32", "uint64"];
+
+/// Formats of `number` schemas that are recognized.
+pub const NUMBER_FORMATS: &[&str] = &["float", "double"];
+
+/// Checks that a field's format is one of the recognized formats. Unknown
+/// formats are only rejected in strict mode.
+pub fn check_format(
+    format: Option<&str>,
+    known_formats: &[&str],
+    field_name: &str,
+    strict: bool,
+) -> Result<(), String> {
+    match format {
+        Some(format) if strict && !known_formats.contains(&format) => Err(format!(
+            "unknown format `{}` for field `{}`, expected one of {}",
+            format,
+            field_name,
+            known_formats
+                .iter()
+                .map(|format| format!("`{}`", format))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Nicely formats a Rust type.
+pub fn pretty_print_type(ty: &TokenStream) -> String {
+    let alias = pretty_print_token_stream(&[quote!(type T = #ty;)]);
+
+    alias
+        .trim()
+        .trim_start_matches("type T = ")
+        .trim_end_matches(';')
+        .to_owned()
+}
+
+/// Formats a list of fields as a Markdown table of their names, types,
+/// whether they are required and their descriptions.
+pub fn fields_table(rows: &[(String, TokenStream, bool, Option<String>)]) -> String {
+    let escape = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+    let mut table =
+        "| Field | Type | Required | Description |\n| --- | --- | --- | --- |".to_owned();
+
+    for (name, ty, required, description) in rows {
+        table.push_str(&format!(
+            "\n| `{}` | `{}` | {} | {} |",
+            escape(name),
+            escape(&pretty_print_type(ty)),
+            if *required { "yes" } else { "no" },
+            escape(description.as_deref().unwrap_or_default())
+        ));
+    }
+
+    table
+}
+
+/// Keywords that cannot be used as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super", "'static"];
+
+/// Renames a Rust keyword so that it can be used as an identifier, either by
+/// appending an underscore or, if `raw_idents` is set and the keyword allows
+/// it, by making it a raw identifier.
+pub fn renamed_rust_keyword(name: &str, raw_idents: bool) -> String {
+    if !RUST_KEYWORDS.contains(&name) {
         name.to_owned()
+    } else if raw_idents && !NON_RAW_KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        format!("{}_", name)
     }
 }
 
 /// Takes a JSON property name and returns a valid version of the property,
 /// along with the unchanged property name to be used in renaming during
-/// serialization.
-pub fn renamed_field(name: &str) -> (String, Option<String>) {
+/// serialization. Fields named after Rust keywords become raw identifiers if
+/// `raw_idents` is set.
+pub fn renamed_field(name: &str, raw_idents: bool) -> (String, Option<String>) {
     let re = Regex::new("^\\d+").unwrap();
     let renamed_without_leading_digits = re.replace(name, "").to_string();
 
@@ -220,10 +1129,10 @@ pub fn renamed_field(name: &str) -> (String, Option<String>) {
 
     let renamed_alphanumeric = renamed_snake_case
         .chars()
-        .filter_map(|c| (c.is_ascii_alphanumeric() || c == '_').then_some(c))
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
         .collect::<String>();
 
-    let renamed_keyword = renamed_rust_keyword(&renamed_alphanumeric);
+    let renamed_keyword = renamed_rust_keyword(&renamed_alphanumeric, raw_idents);
 
     let orig = if renamed_keyword == name {
         None
@@ -243,12 +1152,12 @@ pub fn renamed_struct(name: &str) -> String {
 
     let renamed_alphanumeric = renamed_pascal_case
         .chars()
-        .filter_map(|c| (c.is_ascii_alphanumeric()).then_some(c))
+        .filter(|c| c.is_ascii_alphanumeric())
         .collect::<String>();
 
     let renamed_pascal_case_again = renamed_alphanumeric.to_case(Case::Pascal);
 
-    renamed_rust_keyword(&renamed_pascal_case_again)
+    renamed_rust_keyword(&renamed_pascal_case_again, false)
 }
 
 /// Takes a JSON object name and returns a valid enum name for the object.
@@ -256,19 +1165,55 @@ pub fn renamed_enum(name: &str) -> String {
     renamed_struct(name)
 }
 
-/// Takes a JSON string from an enum array and returns a valid enum variant
-/// name, along with the unchanged property name to be used in renaming during
-/// serialization.
-pub fn renamed_enum_variant(name: &str) -> (String, Option<String>) {
-    let renamed = renamed_struct(name);
+/// Takes the JSON strings from an enum array and returns valid, unique enum
+/// variant names, along with the unchanged strings to be used in renaming
+/// during serialization. Strings that are left empty by renaming, or that
+/// collide with an earlier variant, fall back to `VariantN`, where `N` is the
+/// variant's position in the array.
+pub fn renamed_enum_variants(names: &[String]) -> Vec<(String, Option<String>)> {
+    let mut used = HashSet::new();
 
-    let orig = if renamed == name {
-        None
-    } else {
-        Some(name.to_owned())
-    };
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let mut renamed = renamed_struct(name);
 
-    (renamed, orig)
+            if renamed.is_empty() || used.contains(&renamed) {
+                renamed = format!("Variant{}", index);
+
+                while used.contains(&renamed) {
+                    renamed.push('_');
+                }
+            }
+
+            used.insert(renamed.clone());
+
+            let orig = if &renamed == name {
+                None
+            } else {
+                Some(name.to_owned())
+            };
+
+            (renamed, orig)
+        })
+        .collect()
+}
+
+/// Takes the JSON representations of the numbers in a numeric enum and
+/// returns valid enum variant names, such as `Variant1` for `1`, `Variant1_5`
+/// for `1.5` and `VariantNeg2` for `-2`.
+pub fn numeric_enum_variants(numbers: &[String]) -> Vec<String> {
+    numbers
+        .iter()
+        .map(|number| {
+            let renamed = number
+                .replace(['.', 'e', 'E'], "_")
+                .replace('+', "")
+                .replace('-', "Neg");
+            format!("Variant{}", renamed)
+        })
+        .collect()
 }
 
 /// Takes a JSON ref name and returns a valid type name for the ref.
@@ -278,7 +1223,7 @@ pub fn renamed_ref(name: &str, root_name: &str) -> String {
 
 /// Renames a function to fit with common conventions.
 pub fn renamed_function(name: &str) -> String {
-    renamed_field(name).0
+    renamed_field(name, false).0
 }
 
 /// Generates a name for a function providing a default value.
@@ -304,6 +1249,129 @@ pub fn maybe_optional_value(value: TokenStream, required: bool) -> TokenStream {
     }
 }
 
+/// Generates the inherent serialization and deserialization methods for a
+/// type, along with `FromStr` and `Display` implementations delegating to
+/// them. If a schema is provided, deserialized values are validated against
+/// it.
+pub fn impl_serde_methods(
+    ident: &Ident,
+    internal_path: &TokenStream,
+    serde: SerdeTraits,
+    method_names: &MethodNames,
+    validate: Option<&Value>,
+    validate_on_serialize: Option<&Value>,
+) -> TokenStream {
+    let MethodNames { parse, emit } = method_names;
+
+    let (deserialize, deserialize_from_value) = match validate {
+        None => (
+            quote!(#internal_path::deserialize(json)),
+            quote!(#internal_path::deserialize_from_value(value.to_owned())),
+        ),
+        Some(schema) => {
+            let schema_str = schema.to_string();
+
+            (
+                quote!(#internal_path::deserialize_validate(json, #schema_str)),
+                quote!(#internal_path::deserialize_from_value_validate(value.to_owned(), #schema_str)),
+            )
+        }
+    };
+
+    let (serialize, serialize_to_value) = match validate_on_serialize {
+        None => (
+            quote!(#internal_path::serialize(self)),
+            quote!(#internal_path::serialize_to_value(self)),
+        ),
+        Some(schema) => {
+            let schema_str = schema.to_string();
+
+            (
+                quote!(#internal_path::serialize_validate(self, #schema_str)),
+                quote!(#internal_path::serialize_to_value_validate(self, #schema_str)),
+            )
+        }
+    };
+
+    let deserialize_methods = serde.deserialize().then(|| {
+        quote! {
+            /// Deserializes a JSON string into this type.
+            pub fn #parse(json: &str) -> #internal_path::Result<Self> {
+                #deserialize
+            }
+
+            /// Deserializes an owned JSON string into this type.
+            pub fn from_string(json: String) -> #internal_path::Result<Self> {
+                Self::#parse(&json)
+            }
+        }
+    });
+    let serialize_methods = serde.serialize().then(|| {
+        quote! {
+            /// Serializes this type into a JSON string.
+            #[must_use = "serialization errors are lost if the result is ignored"]
+            pub fn #emit(&self) -> #internal_path::Result<String> {
+                #serialize
+            }
+        }
+    });
+    let deserialize_value_methods = serde.deserialize().then(|| {
+        quote! {
+            /// Deserializes a JSON value into this type.
+            pub fn from_value(value: &#internal_path::Value) -> #internal_path::Result<Self> {
+                #deserialize_from_value
+            }
+        }
+    });
+    let serialize_value_methods = serde.serialize().then(|| {
+        quote! {
+            /// Serializes this type into a JSON value.
+            #[must_use = "serialization errors are lost if the result is ignored"]
+            pub fn to_value(&self) -> #internal_path::Result<#internal_path::Value> {
+                #serialize_to_value
+            }
+        }
+    });
+
+    let from_str_impl = serde.deserialize().then(|| {
+        quote! {
+            impl ::core::str::FromStr for #ident {
+                type Err = #internal_path::JsonSchemaError;
+
+                fn from_str(json: &str) -> ::core::result::Result<Self, Self::Err> {
+                    Self::#parse(json)
+                }
+            }
+        }
+    });
+    let display_impl = serde.serialize().then(|| {
+        quote! {
+            impl ::core::fmt::Display for #ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    // Formatting cannot report serialization errors, so values that fail
+                    // to serialize fall back to their debug representation.
+                    match self.#emit() {
+                        Ok(json) => f.write_str(&json),
+                        Err(_) => ::core::write!(f, "{:?}", self),
+                    }
+                }
+            }
+        }
+    });
+
+    quote! {
+        impl #ident {
+            #deserialize_methods
+            #serialize_methods
+            #deserialize_value_methods
+            #serialize_value_methods
+        }
+
+        #from_str_impl
+        #display_impl
+    }
+}
+
 /// Creates a documentation attribute if the given doc string is not empty.
 pub fn doc_attribute(maybe_doc: Option<&str>) -> TokenStream {
     match maybe_doc {
@@ -336,6 +1404,118 @@ pub fn default_attribute(maybe_default: Option<&str>) -> TokenStream {
     }
 }
 
+/// Creates the serde attributes for a field serialized and deserialized
+/// using one of the given internal modules, depending on whether the field is
+/// optional. Optional fields without a default value are also given a serde
+/// default, as custom deserialization would otherwise make them required.
+pub fn serde_with_attribute(
+    internal_path: &TokenStream,
+    module: &str,
+    required: bool,
+    has_default: bool,
+) -> TokenStream {
+    let with = if required {
+        format!("{}::{}", internal_path, module)
+    } else {
+        format!("{}::{}_option", internal_path, module)
+    };
+
+    if required || has_default {
+        quote!(#[serde(with = #with)])
+    } else {
+        quote!(#[serde(default, with = #with)])
+    }
+}
+
+/// Gets the name of the function deserializing a string field while checking
+/// its length.
+pub fn length_fn_name(name_prefix: &str, name: &str) -> String {
+    renamed_function(&format!("{}_{}_length", name_prefix, name))
+}
+
+/// Creates an attribute deserializing a field with the given function.
+/// Optional fields without a default value are also given a serde default,
+/// as custom deserialization would otherwise make them required.
+pub fn deserialize_with_attribute(
+    function: &str,
+    required: bool,
+    has_default: bool,
+) -> TokenStream {
+    if required || has_default {
+        quote!(#[serde(deserialize_with = #function)])
+    } else {
+        quote!(#[serde(default, deserialize_with = #function)])
+    }
+}
+
+/// Creates an attribute deriving `proptest`'s `Arbitrary` trait in test builds
+/// if round-trip tests are being generated.
+pub fn arbitrary_attribute(generate_tests: bool) -> TokenStream {
+    if generate_tests {
+        quote!(#[cfg_attr(test, derive(::proptest_derive::Arbitrary))])
+    } else {
+        quote!()
+    }
+}
+
+/// Creates the list of additional traits to derive, following a type's fixed
+/// derives. Traits the type already derives or implements are skipped.
+pub fn extra_derives(derives: &[Path], fixed: &[&str]) -> TokenStream {
+    let extra = derives.iter().filter(|path| {
+        !path
+            .segments
+            .last()
+            .is_some_and(|segment| fixed.iter().any(|name| segment.ident == name))
+    });
+
+    quote!(#(, #extra)*)
+}
+
+/// Gets the range of values representable by a primitive integer type that
+/// enums can be represented as, or `None` if the type is not supported.
+pub fn repr_range(repr: &str) -> Option<(i128, i128)> {
+    match repr {
+        "u8" => Some((u8::MIN.into(), u8::MAX.into())),
+        "u16" => Some((u16::MIN.into(), u16::MAX.into())),
+        "u32" => Some((u32::MIN.into(), u32::MAX.into())),
+        "u64" => Some((u64::MIN.into(), u64::MAX.into())),
+        "i8" => Some((i8::MIN.into(), i8::MAX.into())),
+        "i16" => Some((i16::MIN.into(), i16::MAX.into())),
+        "i32" => Some((i32::MIN.into(), i32::MAX.into())),
+        "i64" => Some((i64::MIN.into(), i64::MAX.into())),
+        _ => None,
+    }
+}
+
+/// Parses the inline capacity from a `vec_type` option of the form
+/// `SmallVec<[_; N]>`.
+pub fn parse_small_vec_capacity(vec_type: &str) -> Result<usize, String> {
+    let re = Regex::new(r"^SmallVec<\[_;(\d+)\]>$").unwrap();
+    let compact = vec_type
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    re.captures(&compact)
+        .and_then(|captures| captures[1].parse().ok())
+        .ok_or(format!(
+            "unsupported `vec_type` '{}', expected `SmallVec<[_; N]>`",
+            vec_type
+        ))
+}
+
+/// Parses a `map_type` option, which names one of the standard library's map
+/// types.
+pub fn parse_map_type(map_type: &str) -> Result<Ident, String> {
+    match map_type.trim() {
+        map_type @ ("HashMap" | "BTreeMap") => Ok(format_ident!("{}", map_type)),
+        _ => Err(format!(
+            "unsupported `map_type` '{}', expected `HashMap` or `BTreeMap`",
+            map_type
+        )),
+    }
+}
+
 /// Inverts wrapped generic types.
 pub trait Invert<T> {
     /// Performs the type inversion.
@@ -359,3 +1539,188 @@ impl<T, E> Invert<Option<Result<T, E>>> for Result<Option<T>, E> {
         }
     }
 }
+
+/// Gets an integer JSON number as an `i128`, which holds every `i64` and
+/// `u64` value.
+fn number_as_i128(number: &Number) -> Option<i128> {
+    number
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| number.as_u64().map(i128::from))
+}
+
+/// Compares two JSON numbers exactly. Integers are never converted to `f64`,
+/// so large `i64` and `u64` values are not rounded before being compared.
+fn compare_numbers(a: &Number, b: &Number) -> Option<Ordering> {
+    match (number_as_i128(a), number_as_i128(b)) {
+        (Some(a), Some(b)) => Some(a.cmp(&b)),
+        (Some(a), None) => compare_int_float(a, b.as_f64()?),
+        (None, Some(b)) => compare_int_float(b, a.as_f64()?).map(Ordering::reverse),
+        (None, None) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+    }
+}
+
+/// Compares an integer to a float exactly, by comparing against the float's
+/// integral part and then its fractional part. The cast saturates, which
+/// still orders floats beyond the range of `i128` correctly.
+fn compare_int_float(int: i128, float: f64) -> Option<Ordering> {
+    let fract = float.fract();
+
+    if fract.is_nan() {
+        return None;
+    }
+
+    Some(
+        int.cmp(&(float.trunc() as i128))
+            .then(0.0.partial_cmp(&fract)?),
+    )
+}
+
+/// Checks that a numeric default value lies within the declared inclusive
+/// and exclusive bounds.
+pub fn check_default_bounds(default: &Value, bounds: &NumberBounds) -> Result<(), String> {
+    let value = match default {
+        Value::Number(value) => value,
+        _ => return Ok(()),
+    };
+    let compare = |bound: &&Number| compare_numbers(value, bound).unwrap_or(Ordering::Equal);
+
+    if let Some(minimum) = bounds
+        .minimum
+        .as_ref()
+        .filter(|minimum| compare(minimum).is_lt())
+    {
+        return Err(format!(
+            "default value {} is less than the minimum of {}",
+            default, minimum
+        ));
+    }
+
+    if let Some(minimum) = bounds
+        .exclusive_minimum
+        .as_ref()
+        .filter(|minimum| compare(minimum).is_le())
+    {
+        return Err(format!(
+            "default value {} is not greater than the exclusive minimum of {}",
+            default, minimum
+        ));
+    }
+
+    if let Some(maximum) = bounds
+        .maximum
+        .as_ref()
+        .filter(|maximum| compare(maximum).is_gt())
+    {
+        return Err(format!(
+            "default value {} is greater than the maximum of {}",
+            default, maximum
+        ));
+    }
+
+    if let Some(maximum) = bounds
+        .exclusive_maximum
+        .as_ref()
+        .filter(|maximum| compare(maximum).is_ge())
+    {
+        return Err(format!(
+            "default value {} is not less than the exclusive maximum of {}",
+            default, maximum
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that a numeric default value is a multiple of the declared
+/// `multipleOf` value.
+pub fn check_default_multiple(default: &Value, multiple_of: Option<f64>) -> Result<(), String> {
+    let value = default.as_f64().unwrap_or_default();
+
+    match multiple_of {
+        Some(multiple_of) if multiple_of <= 0.0 => Err(format!(
+            "expected `multipleOf` to be greater than 0, found {}",
+            multiple_of
+        )),
+        Some(multiple_of) => {
+            let quotient = value / multiple_of;
+
+            if (quotient - quotient.round()).abs() < 1e-9 {
+                Ok(())
+            } else {
+                Err(format!(
+                    "default value {} is not a multiple of {}",
+                    default, multiple_of
+                ))
+            }
+        }
+        None => Ok(()),
+    }
+}
+
+/// Checks that every value listed in the schema's `examples` validates
+/// against the schema itself.
+pub fn check_examples(schema: &Value) -> Result<(), String> {
+    let examples = match get_prop_array(schema, "examples")? {
+        Some(examples) => examples,
+        None => return Ok(()),
+    };
+    let compiled = JSONSchema::compile(schema)
+        .map_err(|e| format!("error compiling schema to check examples: {}", e))?;
+
+    for (index, example) in examples.iter().enumerate() {
+        if let Err(mut errors) = compiled.validate(example) {
+            let error = errors.next().map(|e| e.to_string()).unwrap_or_default();
+            return Err(format!(
+                "example {} does not validate against the schema: {}",
+                index, error
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a default value for a string of format `date-time`, `date` or
+/// `time` is valid for its format.
+#[cfg(feature = "chrono")]
+pub fn check_default_format(format: &str, default: &str) -> Result<(), String> {
+    let result = match format {
+        "date-time" => default.parse::<chrono::DateTime<chrono::Utc>>().map(|_| ()),
+        "date" => default.parse::<chrono::NaiveDate>().map(|_| ()),
+        "time" => default.parse::<chrono::NaiveTime>().map(|_| ()),
+        _ => Ok(()),
+    };
+
+    result.map_err(|e| {
+        format!(
+            "default value '{}' is not a valid {}: {}",
+            default, format, e
+        )
+    })
+}
+
+/// Checks that a default value for a string of format `date-time`, `date` or
+/// `time` is valid for its format. Dates and times are only supported with
+/// the `chrono` feature.
+#[cfg(not(feature = "chrono"))]
+pub fn check_default_format(_format: &str, _default: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// Checks that a default value for a string of format `uuid` is a valid
+/// UUID.
+#[cfg(feature = "uuid")]
+pub fn check_default_uuid(default: &str) -> Result<(), String> {
+    default
+        .parse::<uuid::Uuid>()
+        .map(|_| ())
+        .map_err(|e| format!("default value '{}' is not a valid uuid: {}", default, e))
+}
+
+/// Checks that a default value for a string of format `uuid` is a valid
+/// UUID. UUIDs are only supported with the `uuid` feature.
+#[cfg(not(feature = "uuid"))]
+pub fn check_default_uuid(_default: &str) -> Result<(), String> {
+    Ok(())
+}
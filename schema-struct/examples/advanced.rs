@@ -145,14 +145,10 @@ fn main() {
         schema.description,
         Some("A product from Acme's catalog".to_owned())
     );
-    assert_eq!(schema.type_, Some(Box::new(Draft04DefSimpleTypes::Object)));
+    assert_eq!(schema.type_, Some(Draft04DefSimpleTypes::Object));
     assert_eq!(
         schema.required,
-        Some(Box::new(vec![
-            "id".to_owned(),
-            "name".to_owned(),
-            "price".to_owned()
-        ]))
+        Some(vec!["id".to_owned(), "name".to_owned(), "price".to_owned()])
     );
 
     dbg!(schema);
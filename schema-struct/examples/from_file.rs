@@ -1,7 +1,7 @@
 use schema_struct::schema_struct;
 
 fn main() {
-    schema_struct!(file = "schema-struct/tests/schemas/product-file.json");
+    schema_struct!(file = "tests/schemas/product-file.json");
 
     let product_json = r#"
         {
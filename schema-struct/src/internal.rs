@@ -1,9 +1,14 @@
+#![allow(clippy::result_large_err)]
+
 use jsonschema::error::ValidationErrorKind;
 use jsonschema::paths::JSONPointer;
 use jsonschema::JSONSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// A schema validation error, modeled after `jsonschema::ValidationError`.
 #[derive(Debug)]
@@ -65,6 +70,38 @@ impl<'a> From<Box<dyn Iterator<Item = jsonschema::ValidationError<'a>> + Sync +
 /// A generic JSON schema error.
 pub type Result<T> = core::result::Result<T, JsonSchemaError>;
 
+/// Gets the compiled form of a JSON schema. Schemas are compiled once and
+/// shared process-wide, keyed by the schema string, so types validating
+/// against identical schemas share one compiled instance.
+pub fn compiled_schema(schema: &str) -> Result<Arc<JSONSchema>> {
+    static COMPILED: OnceLock<Mutex<HashMap<String, Arc<JSONSchema>>>> = OnceLock::new();
+
+    let registry = COMPILED.get_or_init(Default::default);
+
+    if let Some(compiled) = registry.lock().unwrap().get(schema) {
+        return Ok(Arc::clone(compiled));
+    }
+
+    // The schema is compiled without holding the lock. If another thread
+    // compiles the same schema concurrently, the first one stored wins.
+    let schema_value: Value = serde_json::from_str(schema)?;
+    let compiled = Arc::new(
+        JSONSchema::options()
+            .with_format("idn-email", formats::is_idn_email)
+            .with_format("idn-hostname", formats::is_idn_hostname)
+            .with_format("iri", formats::is_iri)
+            .compile(&schema_value)?,
+    );
+
+    Ok(Arc::clone(
+        registry
+            .lock()
+            .unwrap()
+            .entry(schema.to_owned())
+            .or_insert(compiled),
+    ))
+}
+
 /// Serializes a type to a JSON string.
 pub fn serialize<T>(value: &T) -> Result<String>
 where
@@ -73,6 +110,16 @@ where
     Ok(serde_json::to_string(&value)?)
 }
 
+/// Serializes a type to a JSON string after validating it against a JSON
+/// schema.
+pub fn serialize_validate<T>(value: &T, schema: &str) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    let json_value = serialize_to_value_validate(value, schema)?;
+    Ok(serde_json::to_string(&json_value)?)
+}
+
 /// Deserializes a JSON string into a type.
 pub fn deserialize<'a, T>(json: &'a str) -> Result<T>
 where
@@ -87,9 +134,8 @@ pub fn deserialize_validate<'a, T>(json: &'a str, schema: &str) -> Result<T>
 where
     T: Deserialize<'a>,
 {
-    let schema_value: Value = serde_json::from_str(schema)?;
     let json_value: Value = serde_json::from_str(json)?;
-    JSONSchema::compile(&schema_value)?.validate(&json_value)?;
+    compiled_schema(schema)?.validate(&json_value)?;
     deserialize(json)
 }
 
@@ -101,6 +147,16 @@ where
     Ok(serde_json::to_value(value)?)
 }
 
+/// Serializes a type to a JSON value and validates it against a JSON schema.
+pub fn serialize_to_value_validate<T>(value: &T, schema: &str) -> Result<Value>
+where
+    T: ?Sized + Serialize,
+{
+    let json_value = serialize_to_value(value)?;
+    compiled_schema(schema)?.validate(&json_value)?;
+    Ok(json_value)
+}
+
 /// Deserializes a JSON value into a type.
 pub fn deserialize_from_value<T>(value: Value) -> Result<T>
 where
@@ -115,7 +171,902 @@ pub fn deserialize_from_value_validate<T>(value: Value, schema: &str) -> Result<
 where
     T: DeserializeOwned,
 {
-    let schema_value: Value = serde_json::from_str(schema)?;
-    JSONSchema::compile(&schema_value)?.validate(&value)?;
+    compiled_schema(schema)?.validate(&value)?;
     deserialize_from_value(value)
 }
+
+/// Compares two JSON values, returning the parts of `new` that differ from
+/// `old`. Objects are compared key by key, with keys missing from `new`
+/// reported as `null`. Returns `None` if the values are equal.
+fn diff_values(old: &Value, new: &Value) -> Option<Value> {
+    match (old, new) {
+        (Value::Object(old_obj), Value::Object(new_obj)) => {
+            let mut diff = Map::new();
+
+            for (key, new_value) in new_obj {
+                let key_diff = match old_obj.get(key) {
+                    Some(old_value) => diff_values(old_value, new_value),
+                    None => Some(new_value.clone()),
+                };
+
+                if let Some(key_diff) = key_diff {
+                    diff.insert(key.clone(), key_diff);
+                }
+            }
+
+            for key in old_obj.keys() {
+                if !new_obj.contains_key(key) {
+                    diff.insert(key.clone(), Value::Null);
+                }
+            }
+
+            (!diff.is_empty()).then_some(Value::Object(diff))
+        }
+        (old, new) => (old != new).then(|| new.clone()),
+    }
+}
+
+/// Serializes two values of a type and compares them, returning a JSON
+/// object of the fields in `new` that differ from `old`. Nested objects
+/// produce nested diffs.
+pub fn diff<T>(old: &T, new: &T) -> Result<Value>
+where
+    T: ?Sized + Serialize,
+{
+    let old_value = serialize_to_value(old)?;
+    let new_value = serialize_to_value(new)?;
+
+    Ok(diff_values(&old_value, &new_value).unwrap_or_else(|| Value::Object(Map::new())))
+}
+
+/// Permissive, Unicode-aware checks for the internationalized string
+/// formats, used in place of the validator's own checks so that they apply
+/// consistently under every draft that validates formats.
+pub mod formats {
+    /// Checks whether a string is an internationalized hostname, i.e. one
+    /// that can be converted to an ASCII hostname under the strict UTS-46
+    /// rules. Labels may mix scripts and use combining marks, but may not be
+    /// empty, be longer than 63 bytes once encoded, or start or end with a
+    /// hyphen.
+    pub fn is_idn_hostname(value: &str) -> bool {
+        idna::domain_to_ascii_strict(value).is_ok()
+    }
+
+    /// Checks whether a string is an internationalized email address: a
+    /// local part of any characters other than whitespace, control
+    /// characters and `@`, followed by `@` and an internationalized
+    /// hostname.
+    pub fn is_idn_email(value: &str) -> bool {
+        match value.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !local.starts_with('.')
+                    && !local.ends_with('.')
+                    && !local.contains("..")
+                    && !local.chars().any(|c| c.is_whitespace() || c.is_control())
+                    && is_idn_hostname(domain)
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether a string is an IRI: a scheme, followed by `:` and a
+    /// part without whitespace or control characters.
+    pub fn is_iri(value: &str) -> bool {
+        match value.split_once(':') {
+            Some((scheme, rest)) => {
+                scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                    && !rest.chars().any(|c| c.is_whitespace() || c.is_control())
+            }
+            None => false,
+        }
+    }
+}
+
+/// Deserializes strings, checking their length in characters against
+/// `minLength` and `maxLength` bounds.
+pub mod length {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+
+    /// Checks that the length of a string is within the given bounds.
+    fn check<E>(value: &str, min: Option<usize>, max: Option<usize>) -> core::result::Result<(), E>
+    where
+        E: Error,
+    {
+        let length = value.chars().count();
+
+        if let Some(min) = min.filter(|min| length < *min) {
+            return Err(E::custom(format!(
+                "string of length {} is shorter than the minimum length {}",
+                length, min
+            )));
+        }
+
+        if let Some(max) = max.filter(|max| length > *max) {
+            return Err(E::custom(format!(
+                "string of length {} is longer than the maximum length {}",
+                length, max
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Deserializes a string, checking its length.
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+        min: Option<usize>,
+        max: Option<usize>,
+    ) -> core::result::Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        check(&value, min, max)?;
+        Ok(value)
+    }
+
+    /// Deserializes an optional string, checking its length if present.
+    pub fn deserialize_option<'de, D>(
+        deserializer: D,
+        min: Option<usize>,
+        max: Option<usize>,
+    ) -> core::result::Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Option::<String>::deserialize(deserializer)?;
+
+        if let Some(value) = &value {
+            check(value, min, max)?;
+        }
+
+        Ok(value)
+    }
+}
+
+/// Checks whether collections such as `Vec`s and `HashMap`s are empty, for
+/// omitting empty collections when serializing.
+pub mod empty {
+    /// Checks whether a collection is empty.
+    pub fn is_empty<T>(value: &T) -> bool
+    where
+        for<'a> &'a T: IntoIterator,
+    {
+        value.into_iter().next().is_none()
+    }
+
+    /// Checks whether an optional collection is present but empty.
+    pub fn is_some_empty<T>(value: &Option<T>) -> bool
+    where
+        for<'a> &'a T: IntoIterator,
+    {
+        value.as_ref().is_some_and(is_empty)
+    }
+
+    /// Checks whether an optional collection is absent or empty.
+    pub fn is_none_or_empty<T>(value: &Option<T>) -> bool
+    where
+        for<'a> &'a T: IntoIterator,
+    {
+        value.as_ref().is_none_or(is_empty)
+    }
+}
+
+/// Debug formats a number with a fixed number of decimal places. Numbers in
+/// options, boxes, arrays and maps are formatted in the same way.
+pub struct PreciseFloat<'a, T: ?Sized>(pub &'a T, pub usize);
+
+impl fmt::Debug for PreciseFloat<'_, f64> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.*}", self.1, self.0)
+    }
+}
+
+impl fmt::Debug for PreciseFloat<'_, f32> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.*}", self.1, self.0)
+    }
+}
+
+impl<T> fmt::Debug for PreciseFloat<'_, Option<T>>
+where
+    for<'a> PreciseFloat<'a, T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => f
+                .debug_tuple("Some")
+                .field(&PreciseFloat(value, self.1))
+                .finish(),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+impl<T> fmt::Debug for PreciseFloat<'_, Box<T>>
+where
+    T: ?Sized,
+    for<'a> PreciseFloat<'a, T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        PreciseFloat(&**self.0, self.1).fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for PreciseFloat<'_, [T]>
+where
+    for<'a> PreciseFloat<'a, T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|value| PreciseFloat(value, self.1)))
+            .finish()
+    }
+}
+
+impl<T, const N: usize> fmt::Debug for PreciseFloat<'_, [T; N]>
+where
+    for<'a> PreciseFloat<'a, T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        PreciseFloat(&self.0[..], self.1).fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for PreciseFloat<'_, Vec<T>>
+where
+    for<'a> PreciseFloat<'a, T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        PreciseFloat(self.0.as_slice(), self.1).fmt(f)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A> fmt::Debug for PreciseFloat<'_, smallvec::SmallVec<A>>
+where
+    A: smallvec::Array,
+    for<'a> PreciseFloat<'a, A::Item>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        PreciseFloat(self.0.as_slice(), self.1).fmt(f)
+    }
+}
+
+impl<K, V, S> fmt::Debug for PreciseFloat<'_, HashMap<K, V, S>>
+where
+    K: fmt::Debug,
+    for<'a> PreciseFloat<'a, V>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|(key, value)| (key, PreciseFloat(value, self.1))),
+            )
+            .finish()
+    }
+}
+
+impl<K, V> fmt::Debug for PreciseFloat<'_, BTreeMap<K, V>>
+where
+    K: fmt::Debug,
+    for<'a> PreciseFloat<'a, V>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|(key, value)| (key, PreciseFloat(value, self.1))),
+            )
+            .finish()
+    }
+}
+
+/// Serializes and deserializes a `bool` as the integer `0` or `1`.
+pub mod int_bool {
+    use serde::de::{Error, Unexpected};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes a `bool` as `0` or `1`.
+    pub fn serialize<S>(value: &bool, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(u8::from(*value))
+    }
+
+    /// Deserializes `0` or `1` into a `bool`.
+    pub fn deserialize<'de, D>(deserializer: D) -> core::result::Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u64::deserialize(deserializer)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(D::Error::invalid_value(
+                Unexpected::Unsigned(other),
+                &"0 or 1",
+            )),
+        }
+    }
+}
+
+/// Serializes and deserializes an `Option<bool>` as the integer `0` or `1`,
+/// or `null`.
+pub mod int_bool_option {
+    use serde::de::{Error, Unexpected};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes an `Option<bool>` as `0`, `1`, or `null`.
+    pub fn serialize<S>(
+        value: &Option<bool>,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(value) => super::int_bool::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes `0`, `1`, or `null` into an `Option<bool>`.
+    pub fn deserialize<'de, D>(deserializer: D) -> core::result::Result<Option<bool>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<u64>::deserialize(deserializer)? {
+            Some(0) => Ok(Some(false)),
+            Some(1) => Ok(Some(true)),
+            Some(other) => Err(D::Error::invalid_value(
+                Unexpected::Unsigned(other),
+                &"0 or 1",
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Serializes and deserializes a constant JSON value, given in its string
+/// representation.
+pub mod constant {
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    /// Serializes the constant.
+    pub fn serialize<S>(json: &str, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let constant = serde_json::from_str::<Value>(json).map_err(S::Error::custom)?;
+        constant.serialize(serializer)
+    }
+
+    /// Deserializes a value, failing if it is not equal to the constant.
+    pub fn deserialize<'de, D>(json: &str, deserializer: D) -> core::result::Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let constant = serde_json::from_str::<Value>(json).map_err(D::Error::custom)?;
+        let value = Value::deserialize(deserializer)?;
+
+        if value == constant {
+            Ok(())
+        } else {
+            Err(D::Error::custom(format!(
+                "expected constant {}, found {}",
+                constant, value
+            )))
+        }
+    }
+
+    /// Deserializes a value that must equal one of several constants,
+    /// returning the index of the matching constant.
+    pub fn deserialize_one_of<'de, D>(
+        jsons: &[&str],
+        deserializer: D,
+    ) -> core::result::Result<usize, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let constants = jsons
+            .iter()
+            .map(|json| serde_json::from_str::<Value>(json))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(D::Error::custom)?;
+        let value = Value::deserialize(deserializer)?;
+
+        constants
+            .iter()
+            .position(|constant| *constant == value)
+            .ok_or_else(|| {
+                D::Error::custom(format!(
+                    "expected one of {}, found {}",
+                    jsons.join(", "),
+                    value
+                ))
+            })
+    }
+}
+
+/// Deserializes a number from either a JSON number or a numeric string, and
+/// serializes it as a JSON number.
+pub mod coerce {
+    use serde::de::{DeserializeOwned, Error};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+    use std::str::FromStr;
+
+    /// Serializes a number as a JSON number.
+    pub fn serialize<S, T>(value: &T, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        value.serialize(serializer)
+    }
+
+    /// Deserializes a number from a JSON number or a numeric string.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> core::result::Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: DeserializeOwned + FromStr,
+    {
+        from_value(Value::deserialize(deserializer)?)
+    }
+
+    /// Converts a JSON number or numeric string into a number.
+    pub(super) fn from_value<T, E>(value: Value) -> core::result::Result<T, E>
+    where
+        T: DeserializeOwned + FromStr,
+        E: Error,
+    {
+        match value {
+            Value::String(s) => s
+                .trim()
+                .parse()
+                .map_err(|_| E::custom(format!("invalid numeric string '{}'", s))),
+            value => serde_json::from_value(value).map_err(E::custom),
+        }
+    }
+}
+
+/// Deserializes an optional number from either a JSON number, a numeric
+/// string, or `null`, and serializes it as a JSON number or `null`.
+pub mod coerce_option {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+    use std::str::FromStr;
+
+    /// Serializes an optional number as a JSON number or `null`.
+    pub fn serialize<S, T>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        value.serialize(serializer)
+    }
+
+    /// Deserializes an optional number from a JSON number, a numeric string,
+    /// or `null`.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> core::result::Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: DeserializeOwned + FromStr,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::Null => Ok(None),
+            value => super::coerce::from_value(value).map(Some),
+        }
+    }
+}
+
+/// Helpers used by generated `Deserialize` implementations that report the
+/// offending field and its expected schema type on failure.
+pub mod de {
+    use serde::de::{DeserializeOwned, Error};
+    use serde::{Deserialize, Deserializer};
+    use serde_json::{Map, Value};
+
+    /// Deserializes a JSON object.
+    pub fn object<'de, D>(
+        deserializer: D,
+        type_name: &str,
+    ) -> core::result::Result<Map<String, Value>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::Object(map) => Ok(map),
+            other => Err(D::Error::custom(format!(
+                "invalid value for `{}`, expected schema type `object`, found {}",
+                type_name, other
+            ))),
+        }
+    }
+
+    /// Deserializes the value of a field, if the field is present.
+    pub fn field<T, E>(
+        map: &Map<String, Value>,
+        key: &str,
+        expected: &str,
+    ) -> core::result::Result<Option<T>, E>
+    where
+        T: DeserializeOwned,
+        E: Error,
+    {
+        field_with(map, key, expected, serde_json::from_value)
+    }
+
+    /// Deserializes the value of a field using a custom deserialization
+    /// function, if the field is present.
+    pub fn field_with<T, E, F>(
+        map: &Map<String, Value>,
+        key: &str,
+        expected: &str,
+        deserialize: F,
+    ) -> core::result::Result<Option<T>, E>
+    where
+        E: Error,
+        F: FnOnce(Value) -> core::result::Result<T, serde_json::Error>,
+    {
+        map.get(key)
+            .map(|value| {
+                deserialize(value.clone()).map_err(|e| {
+                    E::custom(format!(
+                        "invalid value for field `{}`, expected schema type `{}`: {}",
+                        key, expected, e
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Deserializes a flattened field from the whole object.
+    pub fn flattened<T, E>(
+        map: &Map<String, Value>,
+        key: &str,
+        expected: &str,
+    ) -> core::result::Result<T, E>
+    where
+        T: DeserializeOwned,
+        E: Error,
+    {
+        serde_json::from_value(Value::Object(map.clone())).map_err(|e| {
+            E::custom(format!(
+                "invalid value for flattened field `{}`, expected schema type `{}`: {}",
+                key, expected, e
+            ))
+        })
+    }
+
+    /// Deserializes the properties of an object that are not declared by
+    /// its schema into a map.
+    pub fn additional<M, T, E>(
+        map: &Map<String, Value>,
+        known: &[&str],
+        expected: &str,
+    ) -> core::result::Result<M, E>
+    where
+        M: FromIterator<(String, T)>,
+        T: DeserializeOwned,
+        E: Error,
+    {
+        map.iter()
+            .filter(|(key, _)| !known.contains(&key.as_str()))
+            .map(|(key, value)| {
+                serde_json::from_value(value.clone())
+                    .map(|value| (key.clone(), value))
+                    .map_err(|e| {
+                        E::custom(format!(
+                            "invalid value for additional property `{}`, expected schema type `{}`: {}",
+                            key, expected, e
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Checks that an object has no properties other than the known ones.
+    pub fn deny_unknown_fields<E>(
+        map: &Map<String, Value>,
+        known: &'static [&'static str],
+    ) -> core::result::Result<(), E>
+    where
+        E: Error,
+    {
+        match map.keys().find(|key| !known.contains(&key.as_str())) {
+            Some(key) => Err(E::unknown_field(key, known)),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates an error for a missing required field.
+    pub fn missing_field<E>(key: &'static str) -> E
+    where
+        E: Error,
+    {
+        E::missing_field(key)
+    }
+}
+
+/// Helpers for the generated round-trip property tests.
+#[cfg(feature = "round-trip-tests")]
+pub mod round_trip {
+    use serde::ser::{self, Serialize};
+    use std::fmt;
+
+    /// Checks whether every float in a value is finite. Non-finite floats
+    /// serialize to `null`, so values holding them cannot be read back.
+    pub fn is_finite<T>(value: &T) -> bool
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(FiniteCheck).is_ok()
+    }
+
+    /// The error reported when a non-finite float is found.
+    #[derive(Debug)]
+    struct NonFinite;
+
+    impl fmt::Display for NonFinite {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("non-finite float")
+        }
+    }
+
+    impl std::error::Error for NonFinite {}
+
+    impl ser::Error for NonFinite {
+        fn custom<T>(_msg: T) -> Self
+        where
+            T: fmt::Display,
+        {
+            NonFinite
+        }
+    }
+
+    /// A serializer that discards its input, failing on non-finite floats.
+    struct FiniteCheck;
+
+    /// Implements serializer methods that accept any value.
+    macro_rules! accept {
+        ($($method:ident($ty:ty)),* $(,)?) => {
+            $(
+                fn $method(self, _value: $ty) -> Result<(), NonFinite> {
+                    Ok(())
+                }
+            )*
+        };
+    }
+
+    impl ser::Serializer for FiniteCheck {
+        type Ok = ();
+        type Error = NonFinite;
+        type SerializeSeq = Self;
+        type SerializeTuple = Self;
+        type SerializeTupleStruct = Self;
+        type SerializeTupleVariant = Self;
+        type SerializeMap = Self;
+        type SerializeStruct = Self;
+        type SerializeStructVariant = Self;
+
+        accept!(
+            serialize_bool(bool),
+            serialize_i8(i8),
+            serialize_i16(i16),
+            serialize_i32(i32),
+            serialize_i64(i64),
+            serialize_i128(i128),
+            serialize_u8(u8),
+            serialize_u16(u16),
+            serialize_u32(u32),
+            serialize_u64(u64),
+            serialize_u128(u128),
+            serialize_char(char),
+            serialize_str(&str),
+            serialize_bytes(&[u8]),
+            serialize_unit_struct(&'static str),
+        );
+
+        fn serialize_f32(self, value: f32) -> Result<(), NonFinite> {
+            value.is_finite().then_some(()).ok_or(NonFinite)
+        }
+
+        fn serialize_f64(self, value: f64) -> Result<(), NonFinite> {
+            value.is_finite().then_some(()).ok_or(NonFinite)
+        }
+
+        fn serialize_none(self) -> Result<(), NonFinite> {
+            Ok(())
+        }
+
+        fn serialize_some<T>(self, value: &T) -> Result<(), NonFinite>
+        where
+            T: Serialize + ?Sized,
+        {
+            value.serialize(self)
+        }
+
+        fn serialize_unit(self) -> Result<(), NonFinite> {
+            Ok(())
+        }
+
+        fn serialize_unit_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+        ) -> Result<(), NonFinite> {
+            Ok(())
+        }
+
+        fn serialize_newtype_struct<T>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<(), NonFinite>
+        where
+            T: Serialize + ?Sized,
+        {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T>(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            value: &T,
+        ) -> Result<(), NonFinite>
+        where
+            T: Serialize + ?Sized,
+        {
+            value.serialize(self)
+        }
+
+        fn serialize_seq(self, _len: Option<usize>) -> Result<Self, NonFinite> {
+            Ok(self)
+        }
+
+        fn serialize_tuple(self, _len: usize) -> Result<Self, NonFinite> {
+            Ok(self)
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self, NonFinite> {
+            Ok(self)
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self, NonFinite> {
+            Ok(self)
+        }
+
+        fn serialize_map(self, _len: Option<usize>) -> Result<Self, NonFinite> {
+            Ok(self)
+        }
+
+        fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, NonFinite> {
+            Ok(self)
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self, NonFinite> {
+            Ok(self)
+        }
+    }
+
+    /// Implements the compound serializer traits by checking each element.
+    macro_rules! check_elements {
+        ($($trait:ident::$method:ident),* $(,)?) => {
+            $(
+                impl ser::$trait for FiniteCheck {
+                    type Ok = ();
+                    type Error = NonFinite;
+
+                    fn $method<T>(&mut self, value: &T) -> Result<(), NonFinite>
+                    where
+                        T: Serialize + ?Sized,
+                    {
+                        value.serialize(FiniteCheck)
+                    }
+
+                    fn end(self) -> Result<(), NonFinite> {
+                        Ok(())
+                    }
+                }
+            )*
+        };
+    }
+
+    check_elements!(
+        SerializeSeq::serialize_element,
+        SerializeTuple::serialize_element,
+        SerializeTupleStruct::serialize_field,
+        SerializeTupleVariant::serialize_field,
+    );
+
+    impl ser::SerializeMap for FiniteCheck {
+        type Ok = ();
+        type Error = NonFinite;
+
+        fn serialize_key<T>(&mut self, key: &T) -> Result<(), NonFinite>
+        where
+            T: Serialize + ?Sized,
+        {
+            key.serialize(FiniteCheck)
+        }
+
+        fn serialize_value<T>(&mut self, value: &T) -> Result<(), NonFinite>
+        where
+            T: Serialize + ?Sized,
+        {
+            value.serialize(FiniteCheck)
+        }
+
+        fn end(self) -> Result<(), NonFinite> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeStruct for FiniteCheck {
+        type Ok = ();
+        type Error = NonFinite;
+
+        fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), NonFinite>
+        where
+            T: Serialize + ?Sized,
+        {
+            value.serialize(FiniteCheck)
+        }
+
+        fn end(self) -> Result<(), NonFinite> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeStructVariant for FiniteCheck {
+        type Ok = ();
+        type Error = NonFinite;
+
+        fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), NonFinite>
+        where
+            T: Serialize + ?Sized,
+        {
+            value.serialize(FiniteCheck)
+        }
+
+        fn end(self) -> Result<(), NonFinite> {
+            Ok(())
+        }
+    }
+}
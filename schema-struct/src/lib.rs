@@ -64,8 +64,17 @@ mod internal;
 #[doc(hidden)]
 pub mod __internal {
     pub use crate::internal::*;
-    pub use serde::{Deserialize, Serialize};
+    pub use serde::{Deserialize, Deserializer, Serialize, Serializer};
     pub use serde_json::Value;
+
+    #[cfg(feature = "chrono")]
+    pub use chrono;
+
+    #[cfg(feature = "smallvec")]
+    pub use smallvec;
+
+    #[cfg(feature = "uuid")]
+    pub use uuid;
 }
 
 pub use schema_struct_macros::schema_struct;
@@ -0,0 +1,96 @@
+impl ProductWithDebugFile {
+    /// Gets the JSON key that a field is serialized as, given the
+    /// name of the field in Rust.
+    pub fn wire_name(rust_field: &str) -> Option<&'static str> {
+        match rust_field {
+            "id" => Some("id"),
+            "name" => Some("name"),
+            "price" => Some("price"),
+            _ => None,
+        }
+    }
+}
+/** A product from Acme's catalog, but parsed from within a file
+
+# Full definition
+
+```
+/// A product from Acme's catalog, but parsed from within a file
+struct ProductWithDebugFile {
+    /// The unique identifier for a product
+    pub id: i64,
+    /// Name of the product
+    pub name: String,
+    pub price: f64,
+}
+
+```*/
+/// A product from Acme's catalog, but parsed from within a file
+#[derive(
+    ::schema_struct::__internal::Serialize,
+    ::schema_struct::__internal::Deserialize,
+    Debug,
+    Clone,
+    PartialEq
+)]
+struct ProductWithDebugFile {
+    /// The unique identifier for a product
+    pub id: i64,
+    /// Name of the product
+    pub name: String,
+    pub price: f64,
+}
+impl ProductWithDebugFile {
+    /// Deserializes a JSON string into this type.
+    pub fn from_str(json: &str) -> ::schema_struct::__internal::Result<Self> {
+        ::schema_struct::__internal::deserialize(json)
+    }
+    /// Deserializes an owned JSON string into this type.
+    pub fn from_string(json: String) -> ::schema_struct::__internal::Result<Self> {
+        Self::from_str(&json)
+    }
+    /// Serializes this type into a JSON string.
+    #[must_use = "serialization errors are lost if the result is ignored"]
+    pub fn to_str(&self) -> ::schema_struct::__internal::Result<String> {
+        ::schema_struct::__internal::serialize(self)
+    }
+    /// Deserializes a JSON value into this type.
+    pub fn from_value(
+        value: &::schema_struct::__internal::Value,
+    ) -> ::schema_struct::__internal::Result<Self> {
+        ::schema_struct::__internal::deserialize_from_value(value.to_owned())
+    }
+    /// Serializes this type into a JSON value.
+    #[must_use = "serialization errors are lost if the result is ignored"]
+    pub fn to_value(
+        &self,
+    ) -> ::schema_struct::__internal::Result<::schema_struct::__internal::Value> {
+        ::schema_struct::__internal::serialize_to_value(self)
+    }
+}
+impl ::core::str::FromStr for ProductWithDebugFile {
+    type Err = ::schema_struct::__internal::JsonSchemaError;
+    fn from_str(json: &str) -> ::core::result::Result<Self, Self::Err> {
+        Self::from_str(json)
+    }
+}
+impl ::core::fmt::Display for ProductWithDebugFile {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self.to_str() {
+            Ok(json) => f.write_str(&json),
+            Err(_) => ::core::write!(f, "{:?}", self),
+        }
+    }
+}
+impl ProductWithDebugFile {
+    /// The schema title, or the struct identifier if one was given.
+    pub const TITLE: &'static str = "ProductWithDebugFile";
+    /// The schema description, if any.
+    pub const DESCRIPTION: Option<&'static str> = Some(
+        "A product from Acme's catalog, but parsed from within a file",
+    );
+    /// Gets the `$schema` URI declared by the schema, if any.
+    pub fn schema_version() -> Option<&'static str> {
+        Some("http://json-schema.org/draft-04/schema#")
+    }
+}
@@ -2,6 +2,7 @@
 
 use schema_struct::schema_struct;
 use serde_json::json;
+use std::collections::{HashMap, HashSet};
 
 macro_rules! assert_values_eq {
     ( $left:expr, $right:expr ) => {
@@ -53,7 +54,7 @@ fn test_from_schema() {
 /// Test constructing a struct from a schema in a file.
 #[test]
 fn test_from_file() {
-    schema_struct!(file = "schema-struct/tests/schemas/product-file.json");
+    schema_struct!(file = "tests/schemas/product-file.json");
 
     let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
     let product = Product::from_str(product_json).unwrap();
@@ -64,6 +65,168 @@ fn test_from_file() {
     assert_eq!(product.price, 12.34);
 }
 
+/// Test the `FromStr` and `Display` implementations of generated types.
+#[test]
+fn test_from_str_display() {
+    schema_struct!(
+        ident = ProductWithTraits,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer"
+                },
+                "status": {
+                    "enum": ["available", "sold"]
+                }
+            },
+            "required": ["id", "status"]
+        }
+    );
+
+    fn round_trip<T>(json: &str) -> String
+    where
+        T: std::str::FromStr + std::fmt::Display,
+        T::Err: std::fmt::Debug,
+    {
+        json.parse::<T>().unwrap().to_string()
+    }
+
+    let product_json = "{\"id\":5,\"status\":\"sold\"}";
+    let product: ProductWithTraits = product_json.parse().unwrap();
+    assert_eq!(product.status, ProductWithTraitsStatus::Sold);
+    assert_eq!(format!("{}", product), product.to_str().unwrap());
+    assert_values_eq!(&round_trip::<ProductWithTraits>(product_json), product_json);
+
+    assert_eq!(product.status.to_string(), "\"sold\"");
+    assert_eq!(
+        "\"available\"".parse::<ProductWithTraitsStatus>().unwrap(),
+        ProductWithTraitsStatus::Available
+    );
+
+    let err: schema_struct::__internal::JsonSchemaError =
+        "{\"id\":5}".parse::<ProductWithTraits>().unwrap_err();
+    assert!(format!("{:?}", err).contains("status"), "{:?}", err);
+}
+
+/// Test constructing a struct from a schema file given relative to the
+/// working directory rather than the crate's manifest directory.
+#[test]
+fn test_from_file_working_directory() {
+    schema_struct!(
+        ident = ProductFromWorkingDirectory,
+        file = "schema-struct/tests/schemas/product-file.json"
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = ProductFromWorkingDirectory::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+}
+
+/// Test constructing a struct from a schema file with a byte order mark.
+#[test]
+fn test_from_file_with_bom() {
+    schema_struct!(
+        ident = ProductFromBomFile,
+        file = "tests/schemas/product-bom.json"
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = ProductFromBomFile::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.id, 5);
+}
+
+/// Test constructing a struct from a schema at a URL fetched with `ureq`.
+#[cfg(feature = "ureq")]
+#[test]
+fn test_from_url_ureq() {
+    schema_struct!(
+        ident = ProductFromUrlUreq,
+        url = "https://raw.githubusercontent.com/WKHAllen/schema-struct/main/schema-struct/tests/schemas/product-url.json"
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = ProductFromUrlUreq::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.id, 5);
+    assert_eq!(product.name, "product name".to_owned());
+    assert_eq!(product.price, 12.34);
+}
+
+/// Test extending a schema loaded from a file with inline overrides.
+#[test]
+fn test_extend() {
+    schema_struct!(
+        ident = ProductWithStringId,
+        extend = {
+            "properties": {
+                "id": {
+                    "type": "string"
+                },
+                "sku": {
+                    "type": "string"
+                }
+            }
+        },
+        file = "tests/schemas/product-file.json"
+    );
+
+    let product_json = "{\"id\":\"abc\",\"name\":\"product name\",\"price\":12.34,\"sku\":\"X1\"}";
+    let product = ProductWithStringId::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.id, "abc".to_owned());
+    assert_eq!(product.name, "product name".to_owned());
+    assert_eq!(product.price, 12.34);
+    assert_eq!(product.sku, Some("X1".to_owned()));
+}
+
+/// Test constructing a struct from a schema given as bytes.
+#[test]
+fn test_from_bytes() {
+    schema_struct!(
+        ident = ProductFromBytes,
+        bytes = include_bytes!("schemas/product-file.json")
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = ProductFromBytes::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.id, 5);
+    assert_eq!(product.name, "product name".to_owned());
+    assert_eq!(product.price, 12.34);
+
+    schema_struct!(
+        ident = ProductFromByteString,
+        bytes = b"{\"type\":\"object\",\"properties\":{\"id\":{\"type\":\"integer\"}}}"
+    );
+
+    let product = ProductFromByteString::from_str("{\"id\":7}").unwrap();
+    assert_eq!(product.id, Some(7));
+}
+
+/// Test deserializing from an owned string.
+#[test]
+fn test_from_string() {
+    schema_struct!(
+        ident = ProductFromString,
+        file = "tests/schemas/product-file.json"
+    );
+
+    let product_json = String::from("{\"id\":5,\"name\":\"product name\",\"price\":12.34}");
+    let product = ProductFromString::from_string(product_json.clone()).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), &product_json);
+
+    assert_eq!(product.id, 5);
+    assert_eq!(product.name, "product name".to_owned());
+    assert_eq!(product.price, 12.34);
+}
+
 /// Test constructing a struct from a schema at a URL.
 #[test]
 fn test_from_url() {
@@ -81,6 +244,33 @@ fn test_from_url() {
     assert_eq!(product.price, 12.34);
 }
 
+/// Test pinning the schema draft used for compilation and validation.
+#[test]
+fn test_draft() {
+    schema_struct!(
+        draft = "draft-07",
+        validate = true,
+        schema = {
+            "title": "SchemaWithPinnedDraft",
+            "description": "A schema relying on draft 6+ numeric `exclusiveMinimum`",
+            "type": "object",
+            "properties": {
+                "quantity": {
+                    "type": "number",
+                    "exclusiveMinimum": 0
+                }
+            },
+            "required": ["quantity"]
+        }
+    );
+
+    let json = "{\"quantity\":2.5}";
+    let value = SchemaWithPinnedDraft::from_str(json).unwrap();
+    assert_eq!(value.quantity, 2.5);
+
+    assert!(SchemaWithPinnedDraft::from_str("{\"quantity\":0.0}").is_err());
+}
+
 /// Test constructing a struct with optional fields.
 #[test]
 fn test_optional_field() {
@@ -114,1356 +304,4919 @@ fn test_optional_field() {
     assert_eq!(value_with_null_empty.name, None);
 }
 
-/// Test constructing a struct with null fields.
+/// Test omitting optional fields set to `None` when serializing.
 #[test]
-fn test_null() {
+fn test_skip_none() {
     schema_struct!(
+        skip_none = true,
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithNull",
-            "description": "A schema with a field of type `null`",
+            "title": "SchemaWithSkipNone",
+            "description": "A schema omitting unset optional fields",
             "type": "object",
             "properties": {
-                "null_field": {
-                    "type": "null"
+                "name": {
+                    "type": "string"
+                },
+                "count": {
+                    "type": "integer",
+                    "default": 3
+                },
+                "nickname": {
+                    "type": ["string", "null"]
+                },
+                "dimensions": {
+                    "type": "object",
+                    "properties": {
+                        "width": {
+                            "type": "integer"
+                        }
+                    }
                 }
             },
-            "required": ["null_field"]
+            "required": ["nickname"]
         }
     );
 
-    let json_with_null = "{\"null_field\":null}";
-    let value_with_null = SchemaWithNull::from_str(json_with_null).unwrap();
-    assert_values_eq!(&value_with_null.to_str().unwrap(), json_with_null);
+    let value = SchemaWithSkipNone {
+        name: None,
+        count: None,
+        nickname: None,
+        dimensions: Some(SchemaWithSkipNoneDimensions { width: None }),
+    };
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"nickname\":null,\"dimensions\":{}}"
+    );
+
+    let json = "{\"name\":\"a\",\"count\":1,\"nickname\":\"b\",\"dimensions\":{\"width\":2}}";
+    let value = SchemaWithSkipNone::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let value = SchemaWithSkipNone::from_str("{\"nickname\":null}").unwrap();
+    assert_eq!(value.name, None);
+    assert_eq!(value.count, Some(3));
+    assert_values_eq!(&value.to_str().unwrap(), "{\"count\":3,\"nickname\":null}");
 }
 
-/// Test constructing a struct with boolean fields.
+/// Test omitting empty arrays and maps when serializing.
 #[test]
-fn test_boolean() {
+fn test_omit_empty_collections() {
     schema_struct!(
+        omit_empty_collections = true,
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithBoolean",
-            "description": "A schema with a field of type `boolean`",
+            "title": "SchemaWithEmptyCollections",
             "type": "object",
             "properties": {
-                "boolean_field": {
-                    "type": "boolean"
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" }
+                },
+                "aliases": {
+                    "type": "array",
+                    "items": { "type": "string" }
+                },
+                "labels": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" }
                 }
             },
-            "required": ["boolean_field"]
+            "required": ["tags", "labels"]
         }
     );
 
-    let json_with_false = "{\"boolean_field\":false}";
-    let value_with_false = SchemaWithBoolean::from_str(json_with_false).unwrap();
-    assert_values_eq!(&value_with_false.to_str().unwrap(), json_with_false);
-    assert!(!value_with_false.boolean_field);
+    let value = SchemaWithEmptyCollections {
+        tags: vec![],
+        aliases: Some(vec![]),
+        labels: HashMap::new(),
+    };
+    assert_values_eq!(&value.to_str().unwrap(), "{}");
 
-    let json_with_true = "{\"boolean_field\":true}";
-    let value_with_true = SchemaWithBoolean::from_str(json_with_true).unwrap();
-    assert_values_eq!(&value_with_true.to_str().unwrap(), json_with_true);
-    assert!(value_with_true.boolean_field);
-}
+    let value = SchemaWithEmptyCollections {
+        tags: vec!["new".to_owned()],
+        aliases: None,
+        labels: HashMap::from([("color".to_owned(), "red".to_owned())]),
+    };
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"tags\":[\"new\"],\"aliases\":null,\"labels\":{\"color\":\"red\"}}"
+    );
 
-/// Test constructing a struct with integer fields.
-#[test]
-fn test_integer() {
     schema_struct!(
+        omit_empty_collections = true,
+        skip_none = true,
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithInteger",
-            "description": "A schema with a field of type `integer`",
+            "title": "SchemaWithSkippedCollections",
             "type": "object",
             "properties": {
-                "integer_field": {
-                    "type": "integer"
+                "aliases": {
+                    "type": "array",
+                    "items": { "type": "string" }
                 }
-            },
-            "required": ["integer_field"]
+            }
         }
     );
 
-    let json_with_pos_int = "{\"integer_field\":1729}";
-    let value_with_pos_int = SchemaWithInteger::from_str(json_with_pos_int).unwrap();
-    assert_values_eq!(&value_with_pos_int.to_str().unwrap(), json_with_pos_int);
-    assert_eq!(value_with_pos_int.integer_field, 1729);
-
-    let json_with_neg_int = "{\"integer_field\":-123}";
-    let value_with_neg_int = SchemaWithInteger::from_str(json_with_neg_int).unwrap();
-    assert_values_eq!(&value_with_neg_int.to_str().unwrap(), json_with_neg_int);
-    assert_eq!(value_with_neg_int.integer_field, -123);
-
-    let json_with_zero = "{\"integer_field\":0}";
-    let value_with_zero = SchemaWithInteger::from_str(json_with_zero).unwrap();
-    assert_values_eq!(&value_with_zero.to_str().unwrap(), json_with_zero);
-    assert_eq!(value_with_zero.integer_field, 0);
+    let value = SchemaWithSkippedCollections { aliases: None };
+    assert_values_eq!(&value.to_str().unwrap(), "{}");
+    let value = SchemaWithSkippedCollections {
+        aliases: Some(vec![]),
+    };
+    assert_values_eq!(&value.to_str().unwrap(), "{}");
+    let value = SchemaWithSkippedCollections {
+        aliases: Some(vec!["x".to_owned()]),
+    };
+    assert_values_eq!(&value.to_str().unwrap(), "{\"aliases\":[\"x\"]}");
 }
 
-/// Test constructing a struct with numeric fields.
+/// Test checking string lengths when deserializing.
 #[test]
-fn test_number() {
+fn test_string_length() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithNumber",
-            "description": "A schema with a field of type `number`",
+            "title": "SchemaWithStringLength",
+            "description": "A schema with bounded string lengths",
             "type": "object",
             "properties": {
-                "number_field": {
-                    "type": "number"
+                "code": {
+                    "type": "string",
+                    "minLength": 2,
+                    "maxLength": 4
+                },
+                "nickname": {
+                    "type": "string",
+                    "maxLength": 3
+                },
+                "label": {
+                    "type": "string",
+                    "minLength": 1,
+                    "default": "x"
                 }
             },
-            "required": ["number_field"]
+            "required": ["code"]
         }
     );
 
-    let json_with_pos_int = "{\"number_field\":1729.0}";
-    let value_with_pos_int = SchemaWithNumber::from_str(json_with_pos_int).unwrap();
-    assert_values_eq!(&value_with_pos_int.to_str().unwrap(), json_with_pos_int);
-    assert_eq!(value_with_pos_int.number_field, 1729.0);
-
-    let json_with_neg_int = "{\"number_field\":-123.0}";
-    let value_with_neg_int = SchemaWithNumber::from_str(json_with_neg_int).unwrap();
-    assert_values_eq!(&value_with_neg_int.to_str().unwrap(), json_with_neg_int);
-    assert_eq!(value_with_neg_int.number_field, -123.0);
-
-    let json_with_pos_float = "{\"number_field\":9.8765}";
-    let value_with_pos_float = SchemaWithNumber::from_str(json_with_pos_float).unwrap();
-    assert_values_eq!(&value_with_pos_float.to_str().unwrap(), json_with_pos_float);
-    assert_eq!(value_with_pos_float.number_field, 9.8765);
+    let value = SchemaWithStringLength::from_str("{\"code\":\"abc\"}").unwrap();
+    assert_eq!(value.code, "abc");
+    assert_eq!(value.nickname, None);
+    assert_eq!(value.label.as_deref(), Some("x"));
 
-    let json_with_neg_float = "{\"number_field\":-0.618}";
-    let value_with_neg_float = SchemaWithNumber::from_str(json_with_neg_float).unwrap();
-    assert_values_eq!(&value_with_neg_float.to_str().unwrap(), json_with_neg_float);
-    assert_eq!(value_with_neg_float.number_field, -0.618);
+    let value = SchemaWithStringLength::from_str(
+        "{\"code\":\"äöüß\",\"nickname\":\"abc\",\"label\":\"y\"}",
+    )
+    .unwrap();
+    assert_eq!(value.code, "äöüß");
+    assert_eq!(value.nickname.as_deref(), Some("abc"));
+    assert_eq!(value.label.as_deref(), Some("y"));
 
-    let json_with_zero = "{\"number_field\":0.0}";
-    let value_with_zero = SchemaWithNumber::from_str(json_with_zero).unwrap();
-    assert_values_eq!(&value_with_zero.to_str().unwrap(), json_with_zero);
-    assert_eq!(value_with_zero.number_field, 0.0);
+    let err = format!(
+        "{:?}",
+        SchemaWithStringLength::from_str("{\"code\":\"a\"}").unwrap_err()
+    );
+    assert!(
+        err.contains("string of length 1 is shorter than the minimum length 2"),
+        "{}",
+        err
+    );
+    let err = format!(
+        "{:?}",
+        SchemaWithStringLength::from_str("{\"code\":\"abcde\"}").unwrap_err()
+    );
+    assert!(
+        err.contains("string of length 5 is longer than the maximum length 4"),
+        "{}",
+        err
+    );
+    assert!(SchemaWithStringLength::from_str("{\"code\":\"ab\",\"nickname\":\"abcd\"}").is_err());
+    assert!(SchemaWithStringLength::from_str("{\"code\":\"ab\",\"label\":\"\"}").is_err());
 }
 
-/// Test constructing a struct with string fields.
+/// Test fixed-size arrays for arrays with equal item bounds.
 #[test]
-fn test_string() {
+fn test_fixed_size_array() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithString",
-            "description": "A schema with a field of type `string`",
+            "title": "SchemaWithFixedSizeArray",
+            "description": "A schema with fixed-size arrays",
             "type": "object",
             "properties": {
-                "string_field": {
-                    "type": "string"
+                "position": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "minItems": 3,
+                    "maxItems": 3
+                },
+                "matrix": {
+                    "type": "array",
+                    "items": {
+                        "type": "array",
+                        "items": { "type": "integer" },
+                        "minItems": 2,
+                        "maxItems": 2
+                    },
+                    "minItems": 2,
+                    "maxItems": 2,
+                    "default": [[1, 0], [0, 1]]
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "maxItems": 3
                 }
             },
-            "required": ["string_field"]
+            "required": ["position"]
         }
     );
 
-    let json_with_empty_str = "{\"string_field\":\"\"}";
-    let value_with_empty_str = SchemaWithString::from_str(json_with_empty_str).unwrap();
-    assert_values_eq!(&value_with_empty_str.to_str().unwrap(), json_with_empty_str);
-    assert_eq!(value_with_empty_str.string_field, "");
+    let value = SchemaWithFixedSizeArray::from_str("{\"position\":[1.0,2.5,-3.0]}").unwrap();
+    let position: [f64; 3] = value.position;
+    assert_eq!(position, [1.0, 2.5, -3.0]);
+    let matrix: Option<[[i64; 2]; 2]> = value.matrix;
+    assert_eq!(matrix, Some([[1, 0], [0, 1]]));
+    assert_eq!(value.tags, None);
 
-    let json_with_str = "{\"string_field\":\"a string value\"}";
-    let value_with_str = SchemaWithString::from_str(json_with_str).unwrap();
-    assert_values_eq!(&value_with_str.to_str().unwrap(), json_with_str);
-    assert_eq!(value_with_str.string_field, "a string value");
+    let value = SchemaWithFixedSizeArray::from_str(
+        "{\"position\":[0,0,0],\"matrix\":[[2,3],[4,5]],\"tags\":[\"a\",\"b\"]}",
+    )
+    .unwrap();
+    assert_eq!(value.matrix, Some([[2, 3], [4, 5]]));
+    let tags: &Option<Vec<String>> = &value.tags;
+    assert_eq!(tags, &Some(vec!["a".to_owned(), "b".to_owned()]));
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"position\":[0.0,0.0,0.0],\"matrix\":[[2,3],[4,5]],\"tags\":[\"a\",\"b\"]}"
+    );
+
+    assert!(SchemaWithFixedSizeArray::from_str("{\"position\":[1.0,2.0]}").is_err());
+    assert!(SchemaWithFixedSizeArray::from_str("{\"position\":[1.0,2.0,3.0,4.0]}").is_err());
 }
 
-/// Test constructing a struct with array fields.
+/// Test constructing a struct with null fields.
 #[test]
-fn test_array() {
+fn test_null() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithArray",
-            "description": "A schema with a field of type `array`",
+            "title": "SchemaWithNull",
+            "description": "A schema with a field of type `null`",
             "type": "object",
             "properties": {
-                "array_field": {
-                    "type": "array",
-                    "items": {
-                        "type": "integer"
-                    }
+                "null_field": {
+                    "type": "null"
                 }
             },
-            "required": ["array_field"]
+            "required": ["null_field"]
         }
     );
 
-    let json_with_empty_array = "{\"array_field\":[]}";
-    let value_with_empty_array = SchemaWithArray::from_str(json_with_empty_array).unwrap();
-    assert_values_eq!(
-        &value_with_empty_array.to_str().unwrap(),
-        json_with_empty_array
-    );
-    assert_eq!(value_with_empty_array.array_field, Vec::<i64>::new());
-
-    let json_with_array = "{\"array_field\":[1,3,7,9]}";
-    let value_with_array = SchemaWithArray::from_str(json_with_array).unwrap();
-    assert_values_eq!(&value_with_array.to_str().unwrap(), json_with_array);
-    assert_eq!(value_with_array.array_field, vec![1, 3, 7, 9]);
+    let json_with_null = "{\"null_field\":null}";
+    let value_with_null = SchemaWithNull::from_str(json_with_null).unwrap();
+    assert_values_eq!(&value_with_null.to_str().unwrap(), json_with_null);
 }
 
-/// Test constructing a struct with object fields.
+/// Test nullable fields expressed with type arrays.
 #[test]
-fn test_object() {
+fn test_nullable_type_array() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithObject",
-            "description": "A schema with a field of type `object`",
+            "title": "SchemaWithNullableTypes",
+            "description": "A schema with nullable fields",
             "type": "object",
             "properties": {
-                "object_field": {
-                    "type": "object",
-                    "properties": {
-                        "inner_field": {
-                            "type": "string"
-                        }
-                    },
-                    "required": ["inner_field"]
+                "name": {
+                    "type": ["string", "null"]
+                },
+                "tags": {
+                    "type": ["null", "array"],
+                    "items": {
+                        "type": "string"
+                    }
+                },
+                "nothing": {
+                    "type": ["null"]
                 }
             },
-            "required": ["object_field"]
+            "required": ["name", "tags", "nothing"]
         }
     );
 
-    let json_with_object = "{\"object_field\":{\"inner_field\":\"an inner object field\"}}";
-    let value_with_object = SchemaWithObject::from_str(json_with_object).unwrap();
-    assert_values_eq!(&value_with_object.to_str().unwrap(), json_with_object);
-    assert_eq!(
-        value_with_object.object_field.inner_field,
-        "an inner object field"
-    );
+    let json = "{\"name\":\"widget\",\"tags\":[\"a\"],\"nothing\":null}";
+    let value = SchemaWithNullableTypes::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.name, Some("widget".to_owned()));
+    assert_eq!(value.tags, Some(vec!["a".to_owned()]));
+
+    let json = "{\"name\":null,\"tags\":null,\"nothing\":null}";
+    let value = SchemaWithNullableTypes::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.name, None);
+    let () = value.nothing;
 }
 
-/// Test constructing a struct with enum fields.
+/// Test that required null fields must be present, while optional ones may
+/// be absent.
 #[test]
-fn test_enum() {
+fn test_required_null() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithEnum",
-            "description": "A schema with an enum field",
+            "title": "SchemaWithRequiredNull",
+            "description": "A schema with required and optional null fields",
             "type": "object",
             "properties": {
-                "enum_field": {
-                    "enum": ["first", "second", "third"]
+                "x": {
+                    "type": "null"
+                },
+                "y": {
+                    "type": "null"
                 }
             },
-            "required": ["enum_field"]
+            "required": ["x"]
         }
     );
 
-    let json_with_enum_first = "{\"enum_field\":\"first\"}";
-    let value_with_enum_first = SchemaWithEnum::from_str(json_with_enum_first).unwrap();
-    assert_values_eq!(
-        &value_with_enum_first.to_str().unwrap(),
-        json_with_enum_first
-    );
-    assert!(matches!(
-        value_with_enum_first.enum_field,
-        SchemaWithEnumEnumField::First
-    ));
-
-    let json_with_enum_second = "{\"enum_field\":\"second\"}";
-    let value_with_enum_second = SchemaWithEnum::from_str(json_with_enum_second).unwrap();
-    assert_values_eq!(
-        &value_with_enum_second.to_str().unwrap(),
-        json_with_enum_second
-    );
-    assert!(matches!(
-        value_with_enum_second.enum_field,
-        SchemaWithEnumEnumField::Second
-    ));
-
-    let json_with_enum_third = "{\"enum_field\":\"third\"}";
-    let value_with_enum_third = SchemaWithEnum::from_str(json_with_enum_third).unwrap();
-    assert_values_eq!(
-        &value_with_enum_third.to_str().unwrap(),
-        json_with_enum_third
-    );
-    assert!(matches!(
-        value_with_enum_third.enum_field,
-        SchemaWithEnumEnumField::Third
-    ));
+    assert!(SchemaWithRequiredNull::from_str("{}").is_err());
 
-    let json_with_enum_invalid_variant = "{\"enum_field\":\"fourth\"}";
-    assert!(SchemaWithEnum::from_str(json_with_enum_invalid_variant).is_err());
+    let value = SchemaWithRequiredNull::from_str("{\"x\":null}").unwrap();
+    let () = value.x;
+    assert_eq!(value.y, None);
+    assert_values_eq!(&value.to_str().unwrap(), "{\"x\":null,\"y\":null}");
 }
 
-/// Test constructing a struct with tuple fields.
+/// Test string fields of format `time` represented as `chrono::NaiveTime`.
+#[cfg(feature = "chrono")]
 #[test]
-fn test_tuple() {
+fn test_time_format() {
+    use chrono::{NaiveTime, Timelike};
+
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithTuple",
-            "description": "A schema with a tuple field",
+            "title": "SchemaWithTime",
+            "description": "A schema with a field of format `time`",
             "type": "object",
             "properties": {
-                "tuple_field": {
-                    "type": "array",
-                    "prefixItems": [
-                        {
-                            "type": "integer",
-                            "description": "The address number"
-                        },
-                        {
-                            "type": "string",
-                            "description": "The street name"
-                        },
-                        {
-                            "enum": ["Street", "Avenue", "Boulevard"],
-                            "description": "The street type"
-                        },
-                        {
-                            "enum": ["NW", "NE", "SW", "SE"],
-                            "description": "The city quadrant of the address"
-                        }
-                    ]
+                "opens_at": {
+                    "type": "string",
+                    "format": "time"
+                },
+                "closes_at": {
+                    "type": "string",
+                    "format": "time",
+                    "default": "17:30:00"
                 }
             },
-            "required": ["tuple_field"]
+            "required": ["opens_at"]
         }
     );
 
-    let json_with_tuple = "{\"tuple_field\":[1600,\"Pennsylvania\",\"Avenue\",\"NW\"]}";
-    let value_with_tuple = SchemaWithTuple::from_str(json_with_tuple).unwrap();
-    assert_values_eq!(&value_with_tuple.to_str().unwrap(), json_with_tuple);
-    assert_eq!(value_with_tuple.tuple_field.0, 1600);
-    assert_eq!(value_with_tuple.tuple_field.1, "Pennsylvania".to_owned());
-    assert!(matches!(
-        value_with_tuple.tuple_field.2,
-        SchemaWithTupleTupleField2::Avenue
-    ));
-    assert!(matches!(
-        value_with_tuple.tuple_field.3,
-        SchemaWithTupleTupleField3::Nw
-    ));
+    let json = "{\"opens_at\":\"13:45:00\",\"closes_at\":\"18:00:00\"}";
+    let value = SchemaWithTime::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let opens_at: NaiveTime = value.opens_at;
+    assert_eq!((opens_at.hour(), opens_at.minute()), (13, 45));
+
+    let value = SchemaWithTime::from_str("{\"opens_at\":\"09:00:00\"}").unwrap();
+    assert_eq!(value.closes_at, NaiveTime::from_hms_opt(17, 30, 0));
+
+    assert!(SchemaWithTime::from_str("{\"opens_at\":\"25:00:00\"}").is_err());
 }
 
-/// Test refs.
+/// Test string fields of format `date-time` and `date` represented as
+/// `chrono` types.
+#[cfg(feature = "chrono")]
 #[test]
-fn test_ref() {
+fn test_date_time_format() {
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
     schema_struct!(
-        vis = pub,
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithRef",
-            "description": "A schema with ref fields",
-            "$defs": {
-                "myInteger": {
-                    "description": "An alias for an integer value",
-                    "type": "integer"
-                },
-                "stringArray": {
-                    "description": "An array of strings",
-                    "type": "array",
-                    "items": {
-                        "type": "string"
-                    }
-                },
-                "objectWithStringArray": {
-                    "description": "An object containing a string array",
-                    "type": "object",
-                    "properties": {
-                        "inner_array": {
-                            "$ref": "#/$defs/stringArray"
-                        }
-                    },
-                    "required": ["inner_array"]
-                },
-                "arrayWithItemsRef": {
-                    "description": "An array with item type ref",
-                    "type": "array",
-                    "items": {
-                        "$ref": "#/$defs/myInteger"
-                    }
-                }
-            },
+            "title": "SchemaWithDateTime",
+            "description": "A schema with fields of format `date-time` and `date`",
             "type": "object",
             "properties": {
-                "my_integer_field": {
-                    "$ref": "#/$defs/myInteger"
-                },
-                "string_array_field": {
-                    "$ref": "#/$defs/stringArray"
-                },
-                "object_with_string_array_field": {
-                    "$ref": "#/definitions/objectWithStringArray"
+                "created_at": {
+                    "type": "string",
+                    "format": "date-time"
                 },
-                "array_with_items_ref_field": {
-                    "$ref": "#/$defs/arrayWithItemsRef"
+                "expires_at": {
+                    "type": "string",
+                    "format": "date-time",
+                    "default": "2030-01-01T00:00:00+02:00"
                 },
-                "self_referential_field": {
-                    "$ref": "#"
+                "birthday": {
+                    "type": "string",
+                    "format": "date",
+                    "default": "2000-02-29"
                 }
             },
-            "required": ["string_array_field"]
+            "required": ["created_at"]
         }
     );
 
-    let json_with_ref = "{\"array_with_items_ref_field\":[1,3,7,9],\"my_integer_field\":123,\"object_with_string_array_field\":{\"inner_array\":[\"four\"]},\"self_referential_field\":{\"array_with_items_ref_field\":null,\"my_integer_field\":null,\"object_with_string_array_field\":null,\"self_referential_field\":null,\"string_array_field\":[]},\"string_array_field\":[\"one\",\"two\",\"three\"]}";
-    let value_with_ref = SchemaWithRef::from_str(json_with_ref).unwrap();
-    assert_values_eq!(&value_with_ref.to_str().unwrap(), json_with_ref);
-    assert_eq!(value_with_ref.my_integer_field, Some(Box::new(123)));
-    assert_eq!(
-        value_with_ref.string_array_field,
-        Box::new(vec!["one".to_owned(), "two".to_owned(), "three".to_owned()])
-    );
-    assert_eq!(
-        value_with_ref.object_with_string_array_field,
-        Some(Box::new(SchemaWithRefDefObjectWithStringArray {
-            inner_array: Box::new(vec!["four".to_owned()])
-        }))
-    );
+    let json = "{\"created_at\":\"2024-05-01T13:45:00Z\",\"expires_at\":\"2025-01-01T00:00:00Z\",\"birthday\":\"1990-07-04\"}";
+    let value = SchemaWithDateTime::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let created_at: DateTime<Utc> = value.created_at;
     assert_eq!(
-        value_with_ref.array_with_items_ref_field,
-        Some(Box::new(vec![
-            Box::new(1),
-            Box::new(3),
-            Box::new(7),
-            Box::new(9)
-        ]))
+        created_at,
+        Utc.with_ymd_and_hms(2024, 5, 1, 13, 45, 0).unwrap()
     );
+    assert_eq!(value.birthday, NaiveDate::from_ymd_opt(1990, 7, 4));
+
+    let value =
+        SchemaWithDateTime::from_str("{\"created_at\":\"2024-05-01T15:45:00+02:00\"}").unwrap();
+    assert_eq!(value.created_at, created_at);
     assert_eq!(
-        value_with_ref.self_referential_field,
-        Some(Box::new(SchemaWithRef {
-            my_integer_field: None,
-            string_array_field: Box::<SchemaWithRefDefStringArray>::default(),
-            object_with_string_array_field: None,
-            array_with_items_ref_field: None,
-            self_referential_field: None
-        }))
+        value.expires_at,
+        Some(Utc.with_ymd_and_hms(2029, 12, 31, 22, 0, 0).unwrap())
     );
+    assert_eq!(value.birthday, NaiveDate::from_ymd_opt(2000, 2, 29));
+
+    assert!(SchemaWithDateTime::from_str("{\"created_at\":\"2024-05-01\"}").is_err());
 }
 
-/// Test constructing a struct containing arrays of objects.
+/// Test string fields of format `uuid` represented as `uuid::Uuid`.
+#[cfg(feature = "uuid")]
 #[test]
-fn test_array_of_objects() {
+fn test_uuid_format() {
+    use uuid::Uuid;
+
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithArrayOfObjects",
-            "description": "A schema with arrays of objects",
+            "title": "SchemaWithUuid",
+            "description": "A schema with fields of format `uuid`",
             "type": "object",
             "properties": {
-                "array_of_objects": {
-                    "type": "array",
-                    "items": {
-                        "type": "object",
-                        "properties": {
-                            "object_id": {
-                                "type": "integer"
-                            }
-                        },
-                        "required": ["object_id"]
-                    }
+                "id": {
+                    "type": "string",
+                    "format": "uuid"
+                },
+                "parent_id": {
+                    "type": "string",
+                    "format": "uuid",
+                    "default": "00000000-0000-0000-0000-000000000000"
                 }
             },
-            "required": ["array_of_objects"]
+            "required": ["id"]
         }
     );
 
-    let json_with_array_of_objects = "{\"array_of_objects\":[{\"object_id\":3},{\"object_id\":7}]}";
-    let value_with_array_of_objects =
-        SchemaWithArrayOfObjects::from_str(json_with_array_of_objects).unwrap();
-    assert_values_eq!(
-        &value_with_array_of_objects.to_str().unwrap(),
-        json_with_array_of_objects
-    );
-    assert_eq!(
-        value_with_array_of_objects.array_of_objects,
-        vec![
-            SchemaWithArrayOfObjectsItemsArrayOfObjects { object_id: 3 },
-            SchemaWithArrayOfObjectsItemsArrayOfObjects { object_id: 7 }
-        ]
-    );
+    let json = "{\"id\":\"67e55044-10b1-426f-9247-bb680e5fe0c8\",\"parent_id\":\"a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8\"}";
+    let value = SchemaWithUuid::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
 
-    let json_with_empty_array_of_objects = "{\"array_of_objects\":[]}";
-    let value_with_empty_array_of_objects =
-        SchemaWithArrayOfObjects::from_str(json_with_empty_array_of_objects).unwrap();
-    assert_values_eq!(
-        &value_with_empty_array_of_objects.to_str().unwrap(),
-        json_with_empty_array_of_objects
-    );
-    assert_eq!(value_with_empty_array_of_objects.array_of_objects, vec![]);
+    let id: Uuid = value.id;
+    assert_eq!(id, Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8));
+
+    let value =
+        SchemaWithUuid::from_str("{\"id\":\"67e55044-10b1-426f-9247-bb680e5fe0c8\"}").unwrap();
+    assert_eq!(value.parent_id, Some(Uuid::nil()));
+
+    assert!(SchemaWithUuid::from_str("{\"id\":\"not-a-uuid\"}").is_err());
 }
 
-/// Test constructing a struct containing nested arrays.
+/// Test constructing a struct with boolean fields.
 #[test]
-fn test_nested_arrays() {
+fn test_boolean() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithNestedArrays",
-            "description": "A schema with nested arrays",
+            "title": "SchemaWithBoolean",
+            "description": "A schema with a field of type `boolean`",
             "type": "object",
             "properties": {
-                "nested_arrays": {
-                    "description": "This should be defined as `Vec<Vec<Vec<...>>>`",
-                    "type": "array",
-                    "items": {
-                        "type": "array",
-                        "items": {
-                            "type": "array",
-                            "items": {
-                                "type": "integer"
-                            }
-                        }
-                    }
+                "boolean_field": {
+                    "type": "boolean"
                 }
             },
-            "required": ["nested_arrays"]
+            "required": ["boolean_field"]
         }
     );
 
-    let json_with_nested_arrays = "{\"nested_arrays\":[[[1,2],[3,4]],[[5,6],[7,8]]]}";
-    let value_with_nested_arrays =
-        SchemaWithNestedArrays::from_str(json_with_nested_arrays).unwrap();
-    assert_values_eq!(
-        &value_with_nested_arrays.to_str().unwrap(),
-        json_with_nested_arrays
-    );
-    assert_eq!(
-        value_with_nested_arrays.nested_arrays,
-        vec![vec![vec![1, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]]
-    );
-
-    let json_with_empty_nested_array1 = "{\"nested_arrays\":[]}";
-    let value_with_empty_nested_array1 =
-        SchemaWithNestedArrays::from_str(json_with_empty_nested_array1).unwrap();
-    assert_values_eq!(
-        &value_with_empty_nested_array1.to_str().unwrap(),
-        json_with_empty_nested_array1
-    );
-    assert_eq!(
-        value_with_empty_nested_array1.nested_arrays,
-        Vec::<Vec<Vec<i64>>>::new()
-    );
-
-    let json_with_empty_nested_array2 = "{\"nested_arrays\":[[]]}";
-    let value_with_empty_nested_array2 =
-        SchemaWithNestedArrays::from_str(json_with_empty_nested_array2).unwrap();
-    assert_values_eq!(
-        &value_with_empty_nested_array2.to_str().unwrap(),
-        json_with_empty_nested_array2
-    );
-    assert_eq!(
-        value_with_empty_nested_array2.nested_arrays,
-        vec![Vec::<Vec<i64>>::new()]
-    );
+    let json_with_false = "{\"boolean_field\":false}";
+    let value_with_false = SchemaWithBoolean::from_str(json_with_false).unwrap();
+    assert_values_eq!(&value_with_false.to_str().unwrap(), json_with_false);
+    assert!(!value_with_false.boolean_field);
 
-    let json_with_empty_nested_array3 = "{\"nested_arrays\":[[[]]]}";
-    let value_with_empty_nested_array3 =
-        SchemaWithNestedArrays::from_str(json_with_empty_nested_array3).unwrap();
-    assert_values_eq!(
-        &value_with_empty_nested_array3.to_str().unwrap(),
-        json_with_empty_nested_array3
-    );
-    assert_eq!(
-        value_with_empty_nested_array3.nested_arrays,
-        vec![vec![Vec::<i64>::new()]]
-    );
+    let json_with_true = "{\"boolean_field\":true}";
+    let value_with_true = SchemaWithBoolean::from_str(json_with_true).unwrap();
+    assert_values_eq!(&value_with_true.to_str().unwrap(), json_with_true);
+    assert!(value_with_true.boolean_field);
 }
 
-/// Test constructing a struct containing nested objects.
+/// Test constructing a struct with integer fields.
 #[test]
-fn test_nested_objects() {
+fn test_integer() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithNestedObjects",
-            "description": "A schema with nested objects",
+            "title": "SchemaWithInteger",
+            "description": "A schema with a field of type `integer`",
             "type": "object",
             "properties": {
-                "foo": {
-                    "type": "object",
-                    "properties": {
-                        "bar": {
-                            "type": "object",
-                            "properties": {
-                                "baz": {
-                                    "type": "object",
-                                    "properties": {
-                                        "message": {
-                                            "type": "string"
-                                        }
-                                    },
-                                    "required": ["message"]
-                                }
-                            },
-                            "required": ["baz"]
-                        }
-                    },
-                    "required": ["bar"]
+                "integer_field": {
+                    "type": "integer"
                 }
             },
-            "required": ["foo"]
+            "required": ["integer_field"]
         }
     );
 
-    let json_with_nested_objects =
-        "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"Hello, nested object!\"}}}}";
-    let value_with_nested_objects =
-        SchemaWithNestedObjects::from_str(json_with_nested_objects).unwrap();
-    assert_values_eq!(
-        &value_with_nested_objects.to_str().unwrap(),
-        json_with_nested_objects
-    );
-    assert_eq!(
-        value_with_nested_objects.foo.bar.baz.message,
-        "Hello, nested object!"
-    );
+    let json_with_pos_int = "{\"integer_field\":1729}";
+    let value_with_pos_int = SchemaWithInteger::from_str(json_with_pos_int).unwrap();
+    assert_values_eq!(&value_with_pos_int.to_str().unwrap(), json_with_pos_int);
+    assert_eq!(value_with_pos_int.integer_field, 1729);
+
+    let json_with_neg_int = "{\"integer_field\":-123}";
+    let value_with_neg_int = SchemaWithInteger::from_str(json_with_neg_int).unwrap();
+    assert_values_eq!(&value_with_neg_int.to_str().unwrap(), json_with_neg_int);
+    assert_eq!(value_with_neg_int.integer_field, -123);
+
+    let json_with_zero = "{\"integer_field\":0}";
+    let value_with_zero = SchemaWithInteger::from_str(json_with_zero).unwrap();
+    assert_values_eq!(&value_with_zero.to_str().unwrap(), json_with_zero);
+    assert_eq!(value_with_zero.integer_field, 0);
 }
 
-/// Test structs with default primitive fields.
+/// Test integer fields whose `format` selects the Rust integer type.
 #[test]
-fn test_default_primitives() {
+fn test_integer_format() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultPrimitives",
-            "description": "A product from Acme's catalog",
+            "title": "SchemaWithIntegerFormats",
+            "description": "A schema with integer fields of various formats",
             "type": "object",
             "properties": {
-                "null_prop": {
-                    "type": "null",
-                    "default": null
+                "small_field": {
+                    "type": "integer",
+                    "format": "int32",
+                    "default": 7
                 },
-                "boolean_prop": {
-                    "type": "boolean",
-                    "default": true
+                "large_field": {
+                    "type": "integer",
+                    "format": "int64"
                 },
-                "integer_prop": {
+                "count_field": {
                     "type": "integer",
-                    "default": 7
+                    "format": "uint32"
                 },
-                "number_prop": {
-                    "type": "number",
-                    "default": 3.45
+                "id_field": {
+                    "type": "integer",
+                    "format": "uint64",
+                    "default": 18446744073709551615
                 },
-                "string_prop": {
-                    "type": "string",
-                    "default": "Hello, world!"
+                "other_field": {
+                    "type": "integer",
+                    "format": "int8"
                 }
             },
-            "required": ["null_prop", "boolean_prop", "integer_prop", "number_prop", "string_prop"]
+            "required": ["large_field", "count_field"]
         }
     );
 
-    let product_json = "{\"null_prop\":null,\"boolean_prop\":true,\"integer_prop\":7,\"number_prop\":3.45,\"string_prop\":\"Hello, world!\"}";
-    let product = SchemaWithDefaultPrimitives::from_str("{}").unwrap();
-    assert_values_eq!(&product.to_str().unwrap(), product_json);
+    let json = "{\"large_field\":-9000000000,\"count_field\":4000000000,\"other_field\":-1}";
+    let value = SchemaWithIntegerFormats::from_str(json).unwrap();
+    let small_field: Option<i32> = value.small_field;
+    let large_field: i64 = value.large_field;
+    let count_field: u32 = value.count_field;
+    let id_field: Option<u64> = value.id_field;
+    let other_field: Option<i64> = value.other_field;
+    assert_eq!(small_field, Some(7));
+    assert_eq!(large_field, -9_000_000_000);
+    assert_eq!(count_field, 4_000_000_000);
+    assert_eq!(id_field, Some(u64::MAX));
+    assert_eq!(other_field, Some(-1));
 
-    assert!(product.boolean_prop);
-    assert_eq!(product.integer_prop, 7);
-    assert_eq!(product.number_prop, 3.45);
-    assert_eq!(product.string_prop, "Hello, world!".to_owned());
+    assert!(SchemaWithIntegerFormats::from_str("{\"large_field\":0,\"count_field\":-1}").is_err());
 }
 
-/// Test structs with default array fields.
+/// Test integer fields with a non-negative minimum represented as unsigned
+/// integers.
 #[test]
-fn test_default_array() {
+fn test_integer_unsigned_minimum() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultArray",
-            "description": "A schema with a field of type `array`",
+            "title": "SchemaWithUnsignedIntegers",
+            "description": "A schema with integer fields bounded below",
             "type": "object",
             "properties": {
-                "array_field": {
-                    "type": "array",
-                    "items": {
-                        "type": "integer"
-                    },
-                    "default": [7, 8, 9]
+                "count": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "default": 3
+                },
+                "small_count": {
+                    "type": "integer",
+                    "format": "int32",
+                    "minimum": 1
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": -10
                 }
             },
-            "required": ["array_field"]
+            "required": ["small_count", "offset"]
         }
     );
 
-    let json = "{\"array_field\":[7,8,9]}";
-    let value = SchemaWithDefaultArray::from_str("{}").unwrap();
-    assert_values_eq!(&value.to_str().unwrap(), json);
-    assert_eq!(value.array_field, vec![7, 8, 9]);
+    let json = "{\"small_count\":5,\"offset\":-2}";
+    let value = SchemaWithUnsignedIntegers::from_str(json).unwrap();
+    let count: Option<u64> = value.count;
+    let small_count: u32 = value.small_count;
+    let offset: i64 = value.offset;
+    assert_eq!(count, Some(3));
+    assert_eq!(small_count, 5);
+    assert_eq!(offset, -2);
+
+    assert!(SchemaWithUnsignedIntegers::from_str("{\"small_count\":-5,\"offset\":0}").is_err());
 }
 
-/// Test structs with default object fields.
+/// Test constructing a struct with numeric fields.
 #[test]
-fn test_default_object() {
+fn test_number() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultObject",
-            "description": "A schema with a field of type `object`",
+            "title": "SchemaWithNumber",
+            "description": "A schema with a field of type `number`",
             "type": "object",
             "properties": {
-                "object_field": {
-                    "type": "object",
-                    "properties": {
-                        "inner_field": {
-                            "type": "string"
-                        }
-                    },
-                    "required": ["inner_field"],
-                    "default": {
-                        "inner_field": "an inner object field"
-                    }
+                "number_field": {
+                    "type": "number"
                 }
             },
-            "required": ["object_field"]
+            "required": ["number_field"]
         }
     );
 
-    let json_with_object = "{\"object_field\":{\"inner_field\":\"an inner object field\"}}";
-    let value_with_object = SchemaWithDefaultObject::from_str("{}").unwrap();
-    assert_values_eq!(&value_with_object.to_str().unwrap(), json_with_object);
-    assert_eq!(
-        value_with_object.object_field.inner_field,
-        "an inner object field"
-    );
+    let json_with_pos_int = "{\"number_field\":1729.0}";
+    let value_with_pos_int = SchemaWithNumber::from_str(json_with_pos_int).unwrap();
+    assert_values_eq!(&value_with_pos_int.to_str().unwrap(), json_with_pos_int);
+    assert_eq!(value_with_pos_int.number_field, 1729.0);
+
+    let json_with_neg_int = "{\"number_field\":-123.0}";
+    let value_with_neg_int = SchemaWithNumber::from_str(json_with_neg_int).unwrap();
+    assert_values_eq!(&value_with_neg_int.to_str().unwrap(), json_with_neg_int);
+    assert_eq!(value_with_neg_int.number_field, -123.0);
+
+    let json_with_pos_float = "{\"number_field\":9.8765}";
+    let value_with_pos_float = SchemaWithNumber::from_str(json_with_pos_float).unwrap();
+    assert_values_eq!(&value_with_pos_float.to_str().unwrap(), json_with_pos_float);
+    assert_eq!(value_with_pos_float.number_field, 9.8765);
+
+    let json_with_neg_float = "{\"number_field\":-0.618}";
+    let value_with_neg_float = SchemaWithNumber::from_str(json_with_neg_float).unwrap();
+    assert_values_eq!(&value_with_neg_float.to_str().unwrap(), json_with_neg_float);
+    assert_eq!(value_with_neg_float.number_field, -0.618);
+
+    let json_with_zero = "{\"number_field\":0.0}";
+    let value_with_zero = SchemaWithNumber::from_str(json_with_zero).unwrap();
+    assert_values_eq!(&value_with_zero.to_str().unwrap(), json_with_zero);
+    assert_eq!(value_with_zero.number_field, 0.0);
 }
 
-/// Test structs with default enum fields.
+/// Test number fields whose `format` selects the Rust float type.
 #[test]
-fn test_default_enum() {
+fn test_number_format() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultEnum",
-            "description": "A schema with an enum field",
+            "title": "SchemaWithNumberFormats",
+            "description": "A schema with number fields of various formats",
             "type": "object",
             "properties": {
-                "enum_field": {
-                    "enum": ["first", "second", "third"],
-                    "default": "first"
+                "float_field": {
+                    "type": "number",
+                    "format": "float",
+                    "default": 3.45
+                },
+                "double_field": {
+                    "type": "number",
+                    "format": "double"
                 }
             },
-            "required": ["enum_field"]
+            "required": ["double_field"]
         }
     );
 
-    let json = "{\"enum_field\":\"first\"}";
-    let value = SchemaWithDefaultEnum::from_str("{}").unwrap();
+    let json = "{\"double_field\":0.1}";
+    let value = SchemaWithNumberFormats::from_str(json).unwrap();
+    let float_field: Option<f32> = value.float_field;
+    let double_field: f64 = value.double_field;
+    assert_eq!(float_field, Some(3.45f32));
+    assert_eq!(double_field, 0.1);
+
+    let json = "{\"float_field\":1.5,\"double_field\":-2.25}";
+    let value = SchemaWithNumberFormats::from_str(json).unwrap();
     assert_values_eq!(&value.to_str().unwrap(), json);
-    assert!(matches!(
-        value.enum_field,
-        SchemaWithDefaultEnumEnumField::First
-    ));
+    assert_eq!(value.float_field, Some(1.5));
 }
 
-/// Test structs with default tuple fields.
+/// Test that strict mode accepts recognized formats.
 #[test]
-fn test_default_tuple() {
+fn test_strict_formats() {
     schema_struct!(
+        strict = true,
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultTuple",
-            "description": "A schema with a tuple field",
+            "title": "SchemaWithStrictFormats",
+            "description": "A schema using only recognized formats",
             "type": "object",
             "properties": {
-                "tuple_field": {
-                    "type": "array",
-                    "prefixItems": [
-                        {
-                            "type": "integer",
-                            "description": "The address number"
-                        },
-                        {
-                            "type": "string",
-                            "description": "The street name"
-                        },
-                        {
-                            "enum": ["Street", "Avenue", "Boulevard"],
-                            "description": "The street type"
-                        },
-                        {
-                            "enum": ["NW", "NE", "SW", "SE"],
-                            "description": "The city quadrant of the address"
-                        }
-                    ],
-                    "default": [1600, "Pennsylvania", "Avenue", "NW"]
+                "email": {
+                    "type": "string",
+                    "format": "email"
+                },
+                "count": {
+                    "type": "integer",
+                    "format": "int32"
+                },
+                "ratio": {
+                    "type": "number",
+                    "format": "double"
                 }
             },
-            "required": ["tuple_field"]
+            "required": ["email", "count", "ratio"]
         }
     );
 
-    let json = "{\"tuple_field\":[1600,\"Pennsylvania\",\"Avenue\",\"NW\"]}";
-    let value = SchemaWithDefaultTuple::from_str("{}").unwrap();
+    let json = "{\"email\":\"user@example.com\",\"count\":3,\"ratio\":0.5}";
+    let value = SchemaWithStrictFormats::from_str(json).unwrap();
     assert_values_eq!(&value.to_str().unwrap(), json);
-    assert_eq!(value.tuple_field.0, 1600);
-    assert_eq!(value.tuple_field.1, "Pennsylvania".to_owned());
-    assert!(matches!(
-        value.tuple_field.2,
-        SchemaWithDefaultTupleTupleField2::Avenue
-    ));
-    assert!(matches!(
-        value.tuple_field.3,
-        SchemaWithDefaultTupleTupleField3::Nw
-    ));
+    assert_eq!(value.email, "user@example.com");
 }
 
-/// Test structs with default refs.
+/// Test constructing a struct with string fields.
 #[test]
-fn test_default_ref() {
+fn test_string() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultRef",
-            "description": "A schema with ref fields",
-            "$defs": {
-                "myDef": {
-                    "type": "object",
-                    "properties": {
-                        "inner": {
-                            "type": "integer",
-                            "default": 1
-                        }
-                    },
-                    "required": ["inner"],
-                    "default": {
-                        "inner": 2
-                    }
-                }
-            },
+            "title": "SchemaWithString",
+            "description": "A schema with a field of type `string`",
             "type": "object",
             "properties": {
-                "my_def_field": {
-                    "$ref": "#/definitions/myDef"
-                },
-                "self_ref_field": {
-                    "type": "object",
-                    "properties": {
-                        "self_ref_field_inner": {
-                            "$ref": "#"
-                        }
-                    },
-                    "default": {}
+                "string_field": {
+                    "type": "string"
                 }
             },
-            "required": ["my_def_field", "self_ref_field"],
-            "default": {
-                "my_def_field": {
-                    "inner": 3
-                },
-                "self_ref_field": {}
-            }
+            "required": ["string_field"]
         }
     );
 
-    let json1 =
-        "{\"my_def_field\":{\"inner\":2},\"self_ref_field\":{\"self_ref_field_inner\":null}}";
-    let value1 = SchemaWithDefaultRef::from_str("{}").unwrap();
-    assert_values_eq!(&value1.to_str().unwrap(), json1);
-    assert_eq!(
-        value1.my_def_field,
-        Box::new(SchemaWithDefaultRefDefMyDef { inner: 2 })
-    );
+    let json_with_empty_str = "{\"string_field\":\"\"}";
+    let value_with_empty_str = SchemaWithString::from_str(json_with_empty_str).unwrap();
+    assert_values_eq!(&value_with_empty_str.to_str().unwrap(), json_with_empty_str);
+    assert_eq!(value_with_empty_str.string_field, "");
 
-    let json2 =
-        "{\"my_def_field\":{\"inner\":1},\"self_ref_field\":{\"self_ref_field_inner\":null}}";
-    let value2 = SchemaWithDefaultRef::from_str("{\"my_def_field\":{}}").unwrap();
-    assert_values_eq!(&value2.to_str().unwrap(), json2);
-    assert_eq!(
-        value2.my_def_field,
-        Box::new(SchemaWithDefaultRefDefMyDef { inner: 1 })
+    let json_with_str = "{\"string_field\":\"a string value\"}";
+    let value_with_str = SchemaWithString::from_str(json_with_str).unwrap();
+    assert_values_eq!(&value_with_str.to_str().unwrap(), json_with_str);
+    assert_eq!(value_with_str.string_field, "a string value");
+}
+
+/// Test constructing a struct with array fields.
+#[test]
+fn test_array() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithArray",
+            "description": "A schema with a field of type `array`",
+            "type": "object",
+            "properties": {
+                "array_field": {
+                    "type": "array",
+                    "items": {
+                        "type": "integer"
+                    }
+                }
+            },
+            "required": ["array_field"]
+        }
     );
 
-    let json3 = "{\"my_def_field\":{\"inner\":2},\"self_ref_field\":{\"self_ref_field_inner\":{\"my_def_field\":{\"inner\":3},\"self_ref_field\":{\"self_ref_field_inner\":null}}}}";
-    let value3 = SchemaWithDefaultRef::from_str("{\"self_ref_field\":{}}").unwrap();
-    assert_values_eq!(&value3.to_str().unwrap(), json3);
-    assert_eq!(
-        value3.self_ref_field.self_ref_field_inner,
-        Some(Box::new(SchemaWithDefaultRef {
-            my_def_field: Box::new(SchemaWithDefaultRefDefMyDef { inner: 3 }),
-            self_ref_field: SchemaWithDefaultRefSelfRefField {
-                self_ref_field_inner: None
-            }
-        }))
+    let json_with_empty_array = "{\"array_field\":[]}";
+    let value_with_empty_array = SchemaWithArray::from_str(json_with_empty_array).unwrap();
+    assert_values_eq!(
+        &value_with_empty_array.to_str().unwrap(),
+        json_with_empty_array
     );
+    assert_eq!(value_with_empty_array.array_field, Vec::<i64>::new());
+
+    let json_with_array = "{\"array_field\":[1,3,7,9]}";
+    let value_with_array = SchemaWithArray::from_str(json_with_array).unwrap();
+    assert_values_eq!(&value_with_array.to_str().unwrap(), json_with_array);
+    assert_eq!(value_with_array.array_field, vec![1, 3, 7, 9]);
 }
 
-/// Test structs with default arrays of objects.
+/// Test representing arrays as boxed slices.
 #[test]
-fn test_default_array_of_objects() {
+fn test_boxed_slices() {
     schema_struct!(
+        boxed_slices = true,
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultArrayOfObjects",
-            "description": "A schema with arrays of objects",
+            "title": "SchemaWithBoxedSlice",
+            "description": "A schema with array fields represented as boxed slices",
             "type": "object",
             "properties": {
-                "array_of_objects": {
+                "array_field": {
                     "type": "array",
                     "items": {
-                        "type": "object",
-                        "properties": {
-                            "object_id": {
-                                "type": "integer",
-                                "default": 123
-                            }
-                        },
-                        "required": ["object_id"],
-                        "default": {
-                            "object_id": 456
-                        }
+                        "type": "integer"
+                    }
+                },
+                "default_array_field": {
+                    "type": "array",
+                    "items": {
+                        "type": "integer"
                     },
-                    "default": [
-                        {
-                            "object_id": 789
-                        }
-                    ]
+                    "default": [2, 4]
                 }
             },
-            "required": ["array_of_objects"]
+            "required": ["array_field", "default_array_field"]
         }
     );
 
-    let json1 = "{\"array_of_objects\":[{\"object_id\":789}]}";
-    let value1 = SchemaWithDefaultArrayOfObjects::from_str("{}").unwrap();
-    assert_values_eq!(&value1.to_str().unwrap(), json1);
-    assert_eq!(
-        value1.array_of_objects,
-        vec![SchemaWithDefaultArrayOfObjectsItemsArrayOfObjects { object_id: 789 }]
-    );
+    let json = "{\"array_field\":[1,3,7,9],\"default_array_field\":[2,4]}";
+    let value = SchemaWithBoxedSlice::from_str("{\"array_field\":[1,3,7,9]}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
 
-    let json2 = "{\"array_of_objects\":[{\"object_id\":123}]}";
-    let value2 = SchemaWithDefaultArrayOfObjects::from_str("{\"array_of_objects\":[{}]}").unwrap();
-    assert_values_eq!(&value2.to_str().unwrap(), json2);
-    assert_eq!(
-        value2.array_of_objects,
-        vec![SchemaWithDefaultArrayOfObjectsItemsArrayOfObjects { object_id: 123 }]
-    );
+    assert_eq!(value.array_field, vec![1, 3, 7, 9].into_boxed_slice());
+    assert_eq!(&value.default_array_field[..], &[2, 4]);
 }
 
-/// Test structs with default nested arrays.
+/// Test representing arrays as `SmallVec`s.
+#[cfg(feature = "smallvec")]
 #[test]
-fn test_default_nested_arrays() {
+fn test_small_vec() {
+    use smallvec::SmallVec;
+
     schema_struct!(
+        vec_type = "SmallVec<[_; 4]>",
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultNestedArrays",
-            "description": "A schema with nested arrays",
+            "title": "SchemaWithSmallVec",
+            "description": "A schema with array fields represented as small vectors",
             "type": "object",
             "properties": {
-                "nested_arrays": {
-                    "description": "This should be defined as `Vec<Vec<Vec<...>>>`",
+                "array_field": {
                     "type": "array",
                     "items": {
-                        "type": "array",
-                        "items": {
-                            "type": "array",
-                            "items": {
-                                "type": "integer",
-                                "default": 123
-                            },
-                            "default": [234]
-                        },
-                        "default": [[345]]
+                        "type": "integer"
+                    }
+                },
+                "default_array_field": {
+                    "type": "array",
+                    "items": {
+                        "type": "integer"
                     },
-                    "default": [[[456]]]
+                    "default": [2, 4]
                 }
             },
-            "required": ["nested_arrays"]
+            "required": ["array_field", "default_array_field"]
         }
     );
 
-    let json = "{\"nested_arrays\":[[[456]]]}";
-    let value = SchemaWithDefaultNestedArrays::from_str("{}").unwrap();
+    let json = "{\"array_field\":[1,3,7,9],\"default_array_field\":[2,4]}";
+    let value = SchemaWithSmallVec::from_str("{\"array_field\":[1,3,7,9]}").unwrap();
     assert_values_eq!(&value.to_str().unwrap(), json);
-    assert_eq!(value.nested_arrays, vec![vec![vec![456]]]);
+
+    let array_field: SmallVec<[i64; 4]> = value.array_field;
+    assert_eq!(&array_field[..], &[1, 3, 7, 9]);
+    assert!(!array_field.spilled());
+    assert_eq!(&value.default_array_field[..], &[2, 4]);
+
+    let json = "{\"array_field\":[1,2,3,4,5],\"default_array_field\":[]}";
+    let value = SchemaWithSmallVec::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert!(value.array_field.spilled());
 }
 
-/// Test structs with default nested objects.
+/// Test constructing a struct with object fields.
 #[test]
-fn test_default_nested_objects() {
+fn test_object() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithDefaultNestedObjects",
-            "description": "A schema with nested objects",
+            "title": "SchemaWithObject",
+            "description": "A schema with a field of type `object`",
             "type": "object",
             "properties": {
-                "foo": {
+                "object_field": {
                     "type": "object",
                     "properties": {
-                        "bar": {
-                            "type": "object",
-                            "properties": {
-                                "baz": {
-                                    "type": "object",
-                                    "properties": {
-                                        "message": {
-                                            "type": "string",
-                                            "default": "one"
-                                        }
-                                    },
-                                    "required": ["message"],
-                                    "default": {
-                                        "message": "two"
-                                    }
-                                }
-                            },
-                            "required": ["baz"],
-                            "default": {
-                                "baz": {
-                                    "message": "three"
-                                }
-                            }
+                        "inner_field": {
+                            "type": "string"
                         }
                     },
-                    "required": ["bar"],
-                    "default": {
-                        "bar": {
-                            "baz": {
-                                "message": "four"
-                            }
-                        }
-                    }
+                    "required": ["inner_field"]
                 }
             },
-            "required": ["foo"]
+            "required": ["object_field"]
         }
     );
 
-    let json1 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"one\"}}}}";
-    let value1 =
-        SchemaWithDefaultNestedObjects::from_str("{\"foo\":{\"bar\":{\"baz\":{}}}}").unwrap();
-    assert_values_eq!(&value1.to_str().unwrap(), json1);
-    assert_eq!(value1.foo.bar.baz.message, "one");
-
-    let json2 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"two\"}}}}";
-    let value2 = SchemaWithDefaultNestedObjects::from_str("{\"foo\":{\"bar\":{}}}").unwrap();
-    assert_values_eq!(&value2.to_str().unwrap(), json2);
-    assert_eq!(value2.foo.bar.baz.message, "two");
-
-    let json3 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"three\"}}}}";
-    let value3 = SchemaWithDefaultNestedObjects::from_str("{\"foo\":{}}").unwrap();
-    assert_values_eq!(&value3.to_str().unwrap(), json3);
-    assert_eq!(value3.foo.bar.baz.message, "three");
-
-    let json4 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"four\"}}}}";
-    let value4 = SchemaWithDefaultNestedObjects::from_str("{}").unwrap();
-    assert_values_eq!(&value4.to_str().unwrap(), json4);
-    assert_eq!(value4.foo.bar.baz.message, "four");
+    let json_with_object = "{\"object_field\":{\"inner_field\":\"an inner object field\"}}";
+    let value_with_object = SchemaWithObject::from_str(json_with_object).unwrap();
+    assert_values_eq!(&value_with_object.to_str().unwrap(), json_with_object);
+    assert_eq!(
+        value_with_object.object_field.inner_field,
+        "an inner object field"
+    );
 }
 
-/// Test structs with default values propagated through objects.
+/// Test flattening nested object fields into their parent.
 #[test]
-fn test_default_propagation() {
+fn test_flatten() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithPropagatedDefaults",
+            "title": "SchemaWithFlattenedObject",
+            "description": "A schema with a flattened object field",
             "type": "object",
             "properties": {
-                "foo": {
-                    "type": "object",
-                    "properties": {
-                        "bar": {
-                            "type": "object",
-                            "properties": {
-                                "baz": {
-                                    "type": "object",
-                                    "properties": {
-                                        "message": {
-                                            "type": "string",
-                                            "default": "Hello, default propagation!"
-                                        }
-                                    },
-                                    "default": {}
-                                }
-                            },
-                            "default": {}
-                        }
-                    },
-                    "default": {}
-                },
-                "tuple_field": {
-                    "type": "array",
-                    "prefixItems": [
-                        {
-                            "type": "integer",
-                            "description": "The address number",
-                            "default": 1600
-                        },
-                        {
-                            "type": "string",
-                            "description": "The street name",
-                            "default": "Pennsylvania"
-                        },
-                        {
-                            "enum": ["Street", "Avenue", "Boulevard"],
-                            "description": "The street type",
-                            "default": "Avenue"
-                        },
-                        {
-                            "enum": ["NW", "NE", "SW", "SE"],
-                            "description": "The city quadrant of the address",
-                            "default": "NW"
-                        }
-                    ],
-                    "default": [2300]
+                "name": {
+                    "type": "string"
                 },
-                "foo_with_null_default": {
+                "address": {
                     "type": "object",
+                    "x-flatten": true,
                     "properties": {
-                        "bar": {
-                            "type": "object",
-                            "properties": {
-                                "baz": {
-                                    "type": "object",
-                                    "properties": {
-                                        "message": {
-                                            "type": "string",
-                                            "default": "This should not propagate"
-                                        }
-                                    },
-                                    "default": {}
-                                }
-                            },
-                            "default": {
-                                "baz": null
-                            }
+                        "street": {
+                            "type": "string"
+                        },
+                        "city": {
+                            "type": "string"
                         }
                     },
-                    "default": {}
+                    "required": ["street", "city"]
                 }
-            }
+            },
+            "required": ["name", "address"]
         }
     );
 
-    let json1 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"Hello, default propagation!\"}}},\"tuple_field\":[2300,\"Pennsylvania\",\"Avenue\",\"NW\"],\"foo_with_null_default\":{\"bar\":{\"baz\":null}}}";
-    let value1 = SchemaWithPropagatedDefaults::from_str("{}").unwrap();
-    assert_values_eq!(&value1.to_str().unwrap(), json1);
+    let json = "{\"name\":\"Jane\",\"street\":\"Main\",\"city\":\"Springfield\"}";
+    let value = SchemaWithFlattenedObject::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
     assert_eq!(
-        value1,
-        SchemaWithPropagatedDefaults {
-            foo: Some(SchemaWithPropagatedDefaultsFoo {
-                bar: Some(SchemaWithPropagatedDefaultsFooBar {
-                    baz: Some(SchemaWithPropagatedDefaultsFooBarBaz {
-                        message: Some("Hello, default propagation!".to_owned())
-                    })
-                })
-            }),
-            tuple_field: Some((
-                2300,
-                "Pennsylvania".to_owned(),
-                SchemaWithPropagatedDefaultsTupleField2::Avenue,
-                SchemaWithPropagatedDefaultsTupleField3::Nw
-            )),
-            foo_with_null_default: Some(SchemaWithPropagatedDefaultsFooWithNullDefault {
-                bar: Some(SchemaWithPropagatedDefaultsFooWithNullDefaultBar { baz: None })
-            })
+        value.address,
+        SchemaWithFlattenedObjectAddress {
+            street: "Main".to_owned(),
+            city: "Springfield".to_owned(),
         }
     );
+    assert_eq!(SchemaWithFlattenedObject::wire_name("address"), None);
 }
 
-/// Test structs with optional default fields.
+/// Test integer fields represented as booleans.
 #[test]
-fn test_default_optional() {
+fn test_int_bool() {
     schema_struct!(
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "SchemaWithOptionalDefaults",
-            "description": "A product from Acme's catalog",
+            "title": "SchemaWithIntBool",
+            "description": "A schema with booleans encoded as integers",
             "type": "object",
-            "$defs": {
-                "myDef": {
-                    "type": "integer",
-                    "default": 9
-                }
-            },
             "properties": {
-                "null_prop": {
-                    "type": "null",
-                    "default": null
-                },
-                "boolean_prop": {
-                    "type": "boolean",
-                    "default": true
-                },
-                "integer_prop": {
+                "active": {
                     "type": "integer",
-                    "default": 7
-                },
-                "number_prop": {
-                    "type": "number",
-                    "default": 3.45
-                },
-                "string_prop": {
-                    "type": "string",
-                    "default": "Hello, world!"
-                },
-                "array_prop": {
-                    "type": "array",
-                    "items": {
-                        "type": "integer",
-                        "default": 0
-                    },
-                    "default": [1, 2, 3]
-                },
-                "object_prop": {
-                    "type": "object",
-                    "properties": {
-                        "message": {
-                            "type": "string",
-                            "default": "Hello, string!"
-                        }
-                    },
-                    "default": {
-                        "message": "Hello, object!"
-                    }
-                },
-                "enum_prop": {
-                    "enum": ["a", "b", "c"],
-                    "default": "c"
-                },
-                "tuple_prop": {
-                    "type": "array",
-                    "prefixItems": [
-                        {
-                            "type": "integer",
-                            "default": 1970
-                        },
-                        {
-                            "type": "string",
-                            "default": "January"
-                        },
-                        {
-                            "type": "integer",
-                            "default": 1
-                        }
-                    ],
-                    "default": [2020, "June", 27]
+                    "enum": [0, 1],
+                    "x-bool": true
                 },
-                "ref_prop": {
-                    "$ref": "#/definitions/myDef"
+                "archived": {
+                    "type": "integer",
+                    "x-bool": true
                 },
-                "optional_prop_without_default": {
-                    "type": "integer"
+                "legacy": {
+                    "type": "integer",
+                    "x-bool": true,
+                    "default": 1
                 }
-            }
+            },
+            "required": ["active"]
         }
     );
 
-    let product_json = "{\"null_prop\":null,\"boolean_prop\":true,\"integer_prop\":7,\"number_prop\":3.45,\"string_prop\":\"Hello, world!\",\"array_prop\":[1,2,3],\"object_prop\":{\"message\":\"Hello, object!\"},\"enum_prop\":\"c\",\"tuple_prop\":[2020,\"June\",27],\"ref_prop\":9,\"optional_prop_without_default\":null}";
-    let product = SchemaWithOptionalDefaults::from_str("{}").unwrap();
-    assert_values_eq!(&product.to_str().unwrap(), product_json);
+    let value = SchemaWithIntBool::from_str("{\"active\":0}").unwrap();
+    assert!(!value.active);
+    assert_eq!(value.archived, None);
+    assert_eq!(value.legacy, Some(true));
 
-    assert_eq!(product.boolean_prop, Some(true));
-    assert_eq!(product.integer_prop, Some(7));
-    assert_eq!(product.number_prop, Some(3.45));
-    assert_eq!(product.string_prop, Some("Hello, world!".to_owned()));
-    assert_eq!(product.array_prop, Some(vec![1, 2, 3]));
-    assert_eq!(
-        product.object_prop,
-        Some(SchemaWithOptionalDefaultsObjectProp {
-            message: Some("Hello, object!".to_owned())
-        })
+    let value = SchemaWithIntBool {
+        active: true,
+        archived: Some(false),
+        legacy: None,
+    };
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"active\":1,\"archived\":0,\"legacy\":null}"
     );
-    assert_eq!(
-        product.enum_prop,
-        Some(SchemaWithOptionalDefaultsEnumProp::C)
+
+    assert!(SchemaWithIntBool::from_str("{\"active\":2}").is_err());
+    assert!(SchemaWithIntBool::from_str("{\"active\":true}").is_err());
+}
+
+/// Test numeric fields that also accept numeric strings.
+#[test]
+fn test_coerce() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithCoerce",
+            "description": "A schema with numbers that may be sent as strings",
+            "type": "object",
+            "properties": {
+                "price": {
+                    "type": "number",
+                    "x-coerce": true
+                },
+                "quantity": {
+                    "type": "integer",
+                    "x-coerce": true
+                }
+            },
+            "required": ["price"]
+        }
+    );
+
+    let from_number = SchemaWithCoerce::from_str("{\"price\":12.34,\"quantity\":3}").unwrap();
+    let from_string =
+        SchemaWithCoerce::from_str("{\"price\":\"12.34\",\"quantity\":\"3\"}").unwrap();
+    assert_eq!(from_number, from_string);
+    assert_eq!(from_string.price, 12.34);
+    assert_eq!(from_string.quantity, Some(3));
+    assert_values_eq!(
+        &from_string.to_str().unwrap(),
+        "{\"price\":12.34,\"quantity\":3}"
+    );
+
+    let without_quantity = SchemaWithCoerce::from_str("{\"price\":\"1\"}").unwrap();
+    assert_eq!(without_quantity.quantity, None);
+
+    assert!(SchemaWithCoerce::from_str("{\"price\":\"abc\"}").is_err());
+}
+
+/// Test constructing a struct with enum fields.
+#[test]
+fn test_enum() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithEnum",
+            "description": "A schema with an enum field",
+            "type": "object",
+            "properties": {
+                "enum_field": {
+                    "enum": ["first", "second", "third"]
+                }
+            },
+            "required": ["enum_field"]
+        }
+    );
+
+    let json_with_enum_first = "{\"enum_field\":\"first\"}";
+    let value_with_enum_first = SchemaWithEnum::from_str(json_with_enum_first).unwrap();
+    assert_values_eq!(
+        &value_with_enum_first.to_str().unwrap(),
+        json_with_enum_first
+    );
+    assert!(matches!(
+        value_with_enum_first.enum_field,
+        SchemaWithEnumEnumField::First
+    ));
+
+    let json_with_enum_second = "{\"enum_field\":\"second\"}";
+    let value_with_enum_second = SchemaWithEnum::from_str(json_with_enum_second).unwrap();
+    assert_values_eq!(
+        &value_with_enum_second.to_str().unwrap(),
+        json_with_enum_second
+    );
+    assert!(matches!(
+        value_with_enum_second.enum_field,
+        SchemaWithEnumEnumField::Second
+    ));
+
+    let json_with_enum_third = "{\"enum_field\":\"third\"}";
+    let value_with_enum_third = SchemaWithEnum::from_str(json_with_enum_third).unwrap();
+    assert_values_eq!(
+        &value_with_enum_third.to_str().unwrap(),
+        json_with_enum_third
+    );
+    assert!(matches!(
+        value_with_enum_third.enum_field,
+        SchemaWithEnumEnumField::Third
+    ));
+
+    let json_with_enum_invalid_variant = "{\"enum_field\":\"fourth\"}";
+    assert!(SchemaWithEnum::from_str(json_with_enum_invalid_variant).is_err());
+}
+
+/// Test enum variants containing whitespace and punctuation.
+#[test]
+fn test_enum_punctuation() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithPunctuatedEnum",
+            "description": "A schema with enum variants that need renaming",
+            "type": "object",
+            "properties": {
+                "status": {
+                    "enum": ["in progress", "N/A", "n/a", "???"],
+                    "default": "???"
+                }
+            },
+            "required": ["status"]
+        }
+    );
+
+    let variants = [
+        ("in progress", SchemaWithPunctuatedEnumStatus::InProgress),
+        ("N/A", SchemaWithPunctuatedEnumStatus::Na),
+        ("n/a", SchemaWithPunctuatedEnumStatus::Variant2),
+        ("???", SchemaWithPunctuatedEnumStatus::Variant3),
+    ];
+
+    for (wire_name, variant) in variants {
+        let json = format!("{{\"status\":\"{}\"}}", wire_name);
+        let value = SchemaWithPunctuatedEnum::from_str(&json).unwrap();
+        assert_values_eq!(&value.to_str().unwrap(), &json);
+        assert_eq!(value.status, variant);
+    }
+
+    let value = SchemaWithPunctuatedEnum::from_str("{}").unwrap();
+    assert_eq!(value.status, SchemaWithPunctuatedEnumStatus::Variant3);
+}
+
+/// Test enums whose variants are integers or numbers.
+#[test]
+fn test_numeric_enum() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithNumericEnums",
+            "description": "A schema with numeric enums",
+            "type": "object",
+            "properties": {
+                "level": {
+                    "enum": [1, 2, 3],
+                    "default": 2
+                },
+                "scale": {
+                    "enum": [1.5, 2.5, -0.5]
+                }
+            },
+            "required": ["scale"]
+        }
+    );
+
+    let json = "{\"level\":3,\"scale\":-0.5}";
+    let value = SchemaWithNumericEnums::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.level, Some(SchemaWithNumericEnumsLevel::Variant3));
+    assert_eq!(value.scale, SchemaWithNumericEnumsScale::VariantNeg0_5);
+
+    let value = SchemaWithNumericEnums::from_str("{\"scale\":1.5}").unwrap();
+    assert_eq!(value.level, Some(SchemaWithNumericEnumsLevel::Variant2));
+    assert_eq!(value.scale, SchemaWithNumericEnumsScale::Variant1_5);
+
+    assert!(SchemaWithNumericEnums::from_str("{\"level\":4,\"scale\":1.5}").is_err());
+    assert!(SchemaWithNumericEnums::from_str("{\"level\":\"1\",\"scale\":1.5}").is_err());
+}
+
+/// Test representing enums as primitive integers with explicit
+/// discriminants.
+#[test]
+fn test_enum_repr() {
+    schema_struct!(
+        repr = u8,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithEnumRepr",
+            "description": "A schema with enums represented as bytes",
+            "type": "object",
+            "properties": {
+                "level": {
+                    "enum": [1, 2, 4]
+                },
+                "color": {
+                    "enum": ["red", "green", "blue"]
+                }
+            },
+            "required": ["level", "color"]
+        }
+    );
+
+    assert_eq!(std::mem::size_of::<SchemaWithEnumReprLevel>(), 1);
+    assert_eq!(std::mem::size_of::<SchemaWithEnumReprColor>(), 1);
+    assert_eq!(SchemaWithEnumReprLevel::Variant1 as u8, 1);
+    assert_eq!(SchemaWithEnumReprLevel::Variant2 as u8, 2);
+    assert_eq!(SchemaWithEnumReprLevel::Variant4 as u8, 4);
+    assert_eq!(SchemaWithEnumReprColor::Red as u8, 0);
+    assert_eq!(SchemaWithEnumReprColor::Green as u8, 1);
+    assert_eq!(SchemaWithEnumReprColor::Blue as u8, 2);
+
+    let json = "{\"level\":4,\"color\":\"green\"}";
+    let value = SchemaWithEnumRepr::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.level, SchemaWithEnumReprLevel::Variant4);
+}
+
+/// Test objects with typed additional properties represented as maps.
+#[test]
+fn test_additional_properties() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "Inventory",
+            "description": "Stock levels per warehouse",
+            "type": "object",
+            "$defs": {
+                "tally": {
+                    "type": "object",
+                    "additionalProperties": { "type": "integer" }
+                }
+            },
+            "properties": {
+                "tallies": { "$ref": "#/$defs/tally" },
+                "counts": {
+                    "type": "object",
+                    "additionalProperties": { "type": "integer" },
+                    "default": { "main": 1 }
+                },
+                "locations": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "aisle": { "type": "integer" }
+                        },
+                        "required": ["aisle"]
+                    }
+                },
+                "labels": {
+                    "type": "object",
+                    "properties": {
+                        "primary": { "type": "string" }
+                    },
+                    "required": ["primary"],
+                    "additionalProperties": { "type": "string" }
+                },
+                "untyped": {
+                    "type": "object",
+                    "additionalProperties": true
+                }
+            },
+            "required": ["counts", "locations", "labels"]
+        }
+    );
+
+    let json = "{\"tallies\":null,\"counts\":{\"north\":3},\"locations\":{\"bolts\":{\"aisle\":4}},\"labels\":{\"primary\":\"a\",\"secondary\":\"b\"},\"untyped\":{}}";
+    let value = Inventory::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let counts: HashMap<String, i64> = value.counts;
+    assert_eq!(counts, HashMap::from([("north".to_owned(), 3)]));
+    assert_eq!(
+        value.locations,
+        HashMap::from([("bolts".to_owned(), InventoryLocationsValue { aisle: 4 })])
+    );
+    assert_eq!(value.labels.primary, "a");
+    assert_eq!(
+        value.labels.additional_properties,
+        HashMap::from([("secondary".to_owned(), "b".to_owned())])
+    );
+
+    let json = "{\"locations\":{},\"labels\":{\"primary\":\"a\"}}";
+    let value = Inventory::from_str(json).unwrap();
+    assert_eq!(value.counts, HashMap::from([("main".to_owned(), 1)]));
+    assert!(value.labels.additional_properties.is_empty());
+    assert_eq!(value.tallies, None);
+
+    let tally: InventoryDefTally = HashMap::from([("south".to_owned(), 2)]);
+    let json = "{\"tallies\":{\"south\":2},\"locations\":{},\"labels\":{\"primary\":\"a\"}}";
+    let value = Inventory::from_str(json).unwrap();
+    assert_eq!(value.tallies, Some(tally));
+
+    assert!(Inventory::from_str(
+        "{\"counts\":{\"north\":\"x\"},\"locations\":{},\"labels\":{\"primary\":\"a\"}}"
+    )
+    .is_err());
+
+    schema_struct!(
+        custom_serde = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "CustomLabels",
+            "type": "object",
+            "properties": {
+                "primary": { "type": "string" }
+            },
+            "required": ["primary"],
+            "additionalProperties": { "type": "integer" }
+        }
+    );
+
+    let json = "{\"primary\":\"a\",\"width\":3}";
+    let value = CustomLabels::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.additional_properties,
+        HashMap::from([("width".to_owned(), 3)])
+    );
+
+    let err = format!(
+        "{:?}",
+        CustomLabels::from_str("{\"primary\":\"a\",\"width\":\"x\"}").unwrap_err()
+    );
+    assert!(err.contains("additional property `width`"), "{}", err);
+
+    // Properties of flattened objects are not additional properties.
+    macro_rules! flattened_labels {
+        ($custom_serde:literal, $ident:ident) => {
+            schema_struct!(
+                custom_serde = $custom_serde,
+                ident = $ident,
+                schema = {
+                    "$schema": "http://json-schema.org/draft-04/schema#",
+                    "$defs": {
+                        "audit": {
+                            "type": "object",
+                            "properties": {
+                                "author": { "type": "string" }
+                            }
+                        }
+                    },
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "integer" },
+                        "address": {
+                            "type": "object",
+                            "x-flatten": true,
+                            "properties": {
+                                "street": { "type": "string" }
+                            }
+                        },
+                        "audit": {
+                            "$ref": "#/$defs/audit",
+                            "x-flatten": true
+                        }
+                    },
+                    "required": ["id", "address", "audit"],
+                    "additionalProperties": { "type": "string" }
+                }
+            );
+        };
+    }
+    flattened_labels!(false, DerivedFlattenedLabels);
+    flattened_labels!(true, CustomFlattenedLabels);
+
+    let json = "{\"id\":1,\"street\":\"Main\",\"author\":\"ann\",\"extra\":\"x\"}";
+    let derived = DerivedFlattenedLabels::from_str(json).unwrap();
+    let custom = CustomFlattenedLabels::from_str(json).unwrap();
+    let extra = HashMap::from([("extra".to_owned(), "x".to_owned())]);
+    assert_eq!(derived.additional_properties, extra);
+    assert_eq!(custom.additional_properties, extra);
+    assert_eq!(custom.address.street, Some("Main".to_owned()));
+    assert_eq!(custom.audit.author, Some("ann".to_owned()));
+    assert_values_eq!(&custom.to_str().unwrap(), json);
+}
+
+/// Test building map-backed types from iterators.
+#[test]
+fn test_additional_properties_collect() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "Scoreboard",
+            "type": "object",
+            "$defs": {
+                "scores": {
+                    "type": "object",
+                    "additionalProperties": { "type": "integer" }
+                }
+            },
+            "properties": {
+                "scores": { "$ref": "#/$defs/scores" }
+            },
+            "required": ["scores"]
+        }
+    );
+
+    let mut scores = [("ann", 3), ("bob", 5)]
+        .into_iter()
+        .map(|(name, score)| (name.to_owned(), score))
+        .collect::<ScoreboardDefScores>();
+    scores.extend([("cat".to_owned(), 8)]);
+
+    let value = Scoreboard { scores };
+    let parsed = Scoreboard::from_str(&value.to_str().unwrap()).unwrap();
+    assert_eq!(parsed, value);
+    assert_eq!(parsed.scores.get("cat"), Some(&8));
+}
+
+/// Test representing open objects as `BTreeMap`s, which serialize their keys
+/// in sorted order.
+#[test]
+fn test_map_type() {
+    macro_rules! sorted_inventory {
+        ($custom_serde:literal, $ident:ident) => {
+            schema_struct!(
+                custom_serde = $custom_serde,
+                map_type = "BTreeMap",
+                ident = $ident,
+                schema = {
+                    "$schema": "http://json-schema.org/draft-04/schema#",
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "integer" },
+                        "counts": {
+                            "type": "object",
+                            "additionalProperties": { "type": "integer" },
+                            "default": { "b": 2, "a": 1 }
+                        }
+                    },
+                    "required": ["id"],
+                    "additionalProperties": { "type": "string" }
+                }
+            );
+        };
+    }
+    sorted_inventory!(false, SortedInventory);
+    sorted_inventory!(true, CustomSortedInventory);
+
+    let json =
+        "{\"id\":1,\"counts\":{\"pear\":3,\"apple\":5,\"fig\":1},\"zone\":\"z\",\"aisle\":\"a\"}";
+    let sorted =
+        "{\"id\":1,\"counts\":{\"apple\":5,\"fig\":1,\"pear\":3},\"aisle\":\"a\",\"zone\":\"z\"}";
+
+    let value = SortedInventory::from_str(json).unwrap();
+    assert_eq!(value.to_str().unwrap(), sorted);
+    let counts: std::collections::BTreeMap<String, i64> = value.counts.unwrap();
+    assert_eq!(counts.keys().collect::<Vec<_>>(), ["apple", "fig", "pear"]);
+
+    let value = CustomSortedInventory::from_str(json).unwrap();
+    assert_eq!(value.to_str().unwrap(), sorted);
+    assert_eq!(
+        value.additional_properties,
+        std::collections::BTreeMap::from([
+            ("aisle".to_owned(), "a".to_owned()),
+            ("zone".to_owned(), "z".to_owned()),
+        ])
+    );
+
+    let value = SortedInventory::from_str("{\"id\":2}").unwrap();
+    assert_eq!(
+        value.to_str().unwrap(),
+        "{\"id\":2,\"counts\":{\"a\":1,\"b\":2}}"
+    );
+}
+
+/// Test rejecting unknown properties of objects disallowing additional
+/// properties.
+#[test]
+fn test_deny_unknown_fields() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "StrictPoint",
+            "type": "object",
+            "properties": {
+                "x": { "type": "integer" },
+                "y": { "type": "integer" },
+                "meta": {
+                    "type": "object",
+                    "properties": {
+                        "label": { "type": "string" }
+                    },
+                    "additionalProperties": true
+                }
+            },
+            "required": ["x", "y"],
+            "additionalProperties": false
+        }
+    );
+
+    let json = "{\"x\":1,\"y\":2,\"meta\":{\"label\":\"a\"}}";
+    let value = StrictPoint::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    assert!(StrictPoint::from_str("{\"x\":1,\"y\":2,\"z\":3}").is_err());
+    assert!(StrictPoint::from_str("{\"x\":1,\"y\":2,\"meta\":{\"color\":\"red\"}}").is_ok());
+
+    schema_struct!(
+        custom_serde = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "CustomStrictPoint",
+            "type": "object",
+            "properties": {
+                "x": { "type": "integer" }
+            },
+            "required": ["x"],
+            "additionalProperties": false
+        }
+    );
+
+    assert!(CustomStrictPoint::from_str("{\"x\":1}").is_ok());
+    let err = format!(
+        "{:?}",
+        CustomStrictPoint::from_str("{\"x\":1,\"z\":3}").unwrap_err()
+    );
+    assert!(err.contains("unknown field `z`"), "{}", err);
+}
+
+/// Test `oneOf` fields represented as enums.
+#[test]
+fn test_one_of() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "Notification",
+            "description": "A notification with a variable payload",
+            "type": "object",
+            "$defs": {
+                "point": {
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "number" },
+                        "y": { "type": "number" }
+                    },
+                    "required": ["x", "y"]
+                }
+            },
+            "properties": {
+                "event": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "kind": { "const": "message" },
+                                "text": { "type": "string" }
+                            },
+                            "required": ["kind", "text"]
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "kind": { "const": "ping" }
+                            },
+                            "required": ["kind"]
+                        }
+                    ]
+                },
+                "location": {
+                    "oneOf": [
+                        { "$ref": "#/$defs/point" },
+                        { "type": "string" }
+                    ]
+                }
+            },
+            "required": ["event", "location"]
+        }
+    );
+
+    let json =
+        "{\"event\":{\"kind\":\"message\",\"text\":\"hello\"},\"location\":{\"x\":1.0,\"y\":2.0}}";
+    let value = Notification::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.event,
+        NotificationEvent::Message(NotificationEventMessage {
+            text: "hello".to_owned()
+        })
+    );
+    assert_eq!(
+        value.location,
+        NotificationLocation::Point(NotificationDefPoint { x: 1.0, y: 2.0 })
+    );
+
+    let json = "{\"event\":{\"kind\":\"ping\"},\"location\":\"home\"}";
+    let value = Notification::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.event,
+        NotificationEvent::Ping(NotificationEventPing {})
+    );
+    assert_eq!(
+        value.location,
+        NotificationLocation::String("home".to_owned())
+    );
+
+    assert!(
+        Notification::from_str("{\"event\":{\"kind\":\"other\"},\"location\":\"home\"}").is_err()
+    );
+
+    let kinds = [
+        "{\"kind\":\"message\",\"text\":\"hi\"}",
+        "{\"kind\":\"ping\"}",
+    ]
+    .map(|event| {
+        let json = format!("{{\"event\":{},\"location\":\"home\"}}", event);
+        match Notification::from_str(&json).unwrap().event.kind() {
+            NotificationEventKind::Message => "message",
+            NotificationEventKind::Ping => "ping",
+        }
+    });
+    assert_eq!(kinds, ["message", "ping"]);
+    assert!(Notification::from_str("{\"event\":{\"kind\":\"ping\"},\"location\":5}").is_err());
+}
+
+/// Test `oneOf` fields whose alternatives are refs to subschemas sharing a
+/// `const` property, which are represented as untagged enums.
+#[test]
+fn test_one_of_refs() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Drawing",
+            "type": "object",
+            "$defs": {
+                "circle": {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "const": "circle" },
+                        "radius": { "type": "number" }
+                    },
+                    "required": ["kind", "radius"]
+                },
+                "square": {
+                    "type": "object",
+                    "properties": {
+                        "kind": { "const": "square" },
+                        "side": { "type": "number" }
+                    },
+                    "required": ["kind", "side"]
+                }
+            },
+            "properties": {
+                "shape": {
+                    "oneOf": [
+                        { "$ref": "#/$defs/circle" },
+                        { "$ref": "#/$defs/square" }
+                    ]
+                }
+            },
+            "required": ["shape"]
+        }
+    );
+
+    let json = "{\"shape\":{\"kind\":\"square\",\"side\":2.0}}";
+    let value = Drawing::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert!(matches!(
+        value.shape,
+        DrawingShape::Square(DrawingDefSquare { side, .. }) if side == 2.0
+    ));
+
+    let json = "{\"shape\":{\"kind\":\"circle\",\"radius\":1.5}}";
+    let value = Drawing::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert!(matches!(
+        value.shape,
+        DrawingShape::Circle(DrawingDefCircle { radius, .. }) if radius == 1.5
+    ));
+
+    assert!(Drawing::from_str("{\"shape\":{\"kind\":\"circle\",\"side\":2.0}}").is_err());
+}
+
+/// Test `anyOf` fields represented as untagged enums.
+#[test]
+fn test_any_of() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "Setting",
+            "description": "A setting with a loosely typed value",
+            "type": "object",
+            "properties": {
+                "value": {
+                    "anyOf": [
+                        { "type": "integer" },
+                        { "type": "number" },
+                        { "type": "string" },
+                        {
+                            "title": "Range",
+                            "type": "object",
+                            "properties": {
+                                "low": { "type": "integer" },
+                                "high": { "type": "integer" }
+                            },
+                            "required": ["low", "high"]
+                        }
+                    ]
+                }
+            },
+            "required": ["value"]
+        }
+    );
+
+    let json = "{\"value\":5}";
+    let value = Setting::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.value, SettingValue::Integer(5));
+
+    let json = "{\"value\":2.5}";
+    let value = Setting::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.value, SettingValue::Number(2.5));
+
+    let json = "{\"value\":\"high\"}";
+    let value = Setting::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.value, SettingValue::String("high".to_owned()));
+
+    let json = "{\"value\":{\"low\":1,\"high\":3}}";
+    let value = Setting::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.value,
+        SettingValue::Range(SettingValueRange { low: 1, high: 3 })
+    );
+
+    assert!(Setting::from_str("{\"value\":true}").is_err());
+}
+
+/// Test constant fields represented as marker types.
+#[test]
+fn test_const() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "UserMessage",
+            "description": "A message tagged with constant fields",
+            "type": "object",
+            "properties": {
+                "kind": {
+                    "const": "user"
+                },
+                "version": {
+                    "type": "integer",
+                    "const": 2,
+                    "default": 2
+                },
+                "body": {
+                    "type": "string"
+                }
+            },
+            "required": ["kind", "body"]
+        }
+    );
+
+    let json = "{\"kind\":\"user\",\"body\":\"hello\"}";
+    let value = UserMessage::from_str(json).unwrap();
+    assert_eq!(value.kind, UserMessageKind);
+    assert_eq!(value.version, Some(UserMessageVersion));
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"kind\":\"user\",\"version\":2,\"body\":\"hello\"}"
+    );
+
+    assert_eq!(UserMessageKind.to_str().unwrap(), "\"user\"");
+    assert!(UserMessage::from_str("{\"kind\":\"admin\",\"body\":\"hello\"}").is_err());
+    assert!(UserMessage::from_str("{\"kind\":\"user\",\"version\":3,\"body\":\"\"}").is_err());
+}
+
+/// Test representing enums with a single variant as constants.
+#[test]
+fn test_unit_enums() {
+    schema_struct!(
+        unit_enums = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithUnitEnum",
+            "description": "A schema with single and multiple variant enums",
+            "type": "object",
+            "properties": {
+                "mode": {
+                    "enum": ["only"]
+                },
+                "color": {
+                    "enum": ["red", "green"]
+                }
+            },
+            "required": ["mode", "color"]
+        }
+    );
+
+    let json = "{\"mode\":\"only\",\"color\":\"green\"}";
+    let value = SchemaWithUnitEnum::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.mode, SchemaWithUnitEnumMode);
+    assert_eq!(value.color, SchemaWithUnitEnumColor::Green);
+
+    assert!(SchemaWithUnitEnum::from_str("{\"mode\":\"other\",\"color\":\"red\"}").is_err());
+}
+
+/// Test constructing a struct with tuple fields.
+#[test]
+fn test_tuple() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithTuple",
+            "description": "A schema with a tuple field",
+            "type": "object",
+            "properties": {
+                "tuple_field": {
+                    "type": "array",
+                    "prefixItems": [
+                        {
+                            "type": "integer",
+                            "description": "The address number"
+                        },
+                        {
+                            "type": "string",
+                            "description": "The street name"
+                        },
+                        {
+                            "enum": ["Street", "Avenue", "Boulevard"],
+                            "description": "The street type"
+                        },
+                        {
+                            "enum": ["NW", "NE", "SW", "SE"],
+                            "description": "The city quadrant of the address"
+                        }
+                    ]
+                }
+            },
+            "required": ["tuple_field"]
+        }
+    );
+
+    let json_with_tuple = "{\"tuple_field\":[1600,\"Pennsylvania\",\"Avenue\",\"NW\"]}";
+    let value_with_tuple = SchemaWithTuple::from_str(json_with_tuple).unwrap();
+    assert_values_eq!(&value_with_tuple.to_str().unwrap(), json_with_tuple);
+    assert_eq!(value_with_tuple.tuple_field.0, 1600);
+    assert_eq!(value_with_tuple.tuple_field.1, "Pennsylvania".to_owned());
+    assert!(matches!(
+        value_with_tuple.tuple_field.2,
+        SchemaWithTupleTupleField2::Avenue
+    ));
+    assert!(matches!(
+        value_with_tuple.tuple_field.3,
+        SchemaWithTupleTupleField3::Nw
+    ));
+}
+
+/// Test the `$schema` URI exposed by generated types.
+#[test]
+fn test_schema_version() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "ProductSchema",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" }
+            }
+        }
+    );
+
+    schema_struct!(
+        schema = {
+            "title": "UnversionedSchema",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" }
+            }
+        }
+    );
+
+    assert_eq!(
+        ProductSchema::schema_version(),
+        Some("http://json-schema.org/draft-04/schema#")
+    );
+    assert_eq!(UnversionedSchema::schema_version(), None);
+}
+
+/// Test exposing the schema title and description as associated constants.
+#[test]
+fn test_schema_title_and_description() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "DescribedSchema",
+            "description": "A product from Acme's catalog",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" }
+            }
+        }
+    );
+
+    schema_struct!(
+        ident = RenamedSchema,
+        schema = {
+            "title": "UndescribedSchema",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" }
+            }
+        }
+    );
+
+    assert_eq!(DescribedSchema::TITLE, "DescribedSchema");
+    assert_eq!(
+        DescribedSchema::DESCRIPTION,
+        Some("A product from Acme's catalog")
+    );
+    assert_eq!(RenamedSchema::TITLE, "RenamedSchema");
+    assert_eq!(RenamedSchema::DESCRIPTION, None);
+}
+
+/// Test diffing two values of a type.
+#[test]
+fn test_diff() {
+    schema_struct!(
+        diff = true,
+        ident = ProductWithDiff,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "ProductSchema",
+            "description": "A product from Acme's catalog",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer"
+                },
+                "name": {
+                    "type": "string"
+                },
+                "price": {
+                    "type": "number"
+                },
+                "dimensions": {
+                    "type": "object",
+                    "properties": {
+                        "width": {
+                            "type": "number"
+                        },
+                        "height": {
+                            "type": "number"
+                        }
+                    },
+                    "required": ["width", "height"]
+                }
+            },
+            "required": ["id", "name", "price"]
+        }
+    );
+
+    let old = ProductWithDiff::from_str(
+        "{\"id\":5,\"name\":\"product name\",\"price\":12.34,\"dimensions\":{\"width\":1.0,\"height\":2.0}}",
+    )
+    .unwrap();
+    let new = ProductWithDiff {
+        price: 10.0,
+        dimensions: Some(ProductWithDiffDimensions {
+            width: 1.0,
+            height: 3.0,
+        }),
+        ..old.clone()
+    };
+
+    assert_eq!(
+        old.diff(&new).unwrap(),
+        json!({ "price": 10.0, "dimensions": { "height": 3.0 } })
+    );
+    assert_eq!(old.diff(&old).unwrap(), json!({}));
+
+    let without_dimensions = ProductWithDiff {
+        dimensions: None,
+        ..old.clone()
+    };
+    assert_eq!(
+        old.diff(&without_dimensions).unwrap(),
+        json!({ "dimensions": null })
+    );
+}
+
+/// Test subschemas declared under the draft-04 `definitions` keyword.
+#[test]
+fn test_ref_definitions() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefinitions",
+            "description": "A schema declaring subschemas under `definitions`",
+            "definitions": {
+                "quantity": {
+                    "type": "integer"
+                }
+            },
+            "type": "object",
+            "properties": {
+                "quantity": {
+                    "$ref": "#/definitions/quantity"
+                }
+            },
+            "required": ["quantity"]
+        }
+    );
+
+    let quantity: SchemaWithDefinitionsDefQuantity = 3;
+    let json = "{\"quantity\":3}";
+    let value = SchemaWithDefinitions::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.quantity, quantity);
+}
+
+/// Test subschemas declared under both `$defs` and `definitions`.
+#[test]
+fn test_ref_defs_and_definitions() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "SchemaWithDefsAndDefinitions",
+            "$defs": {
+                "name": {
+                    "type": "string"
+                }
+            },
+            "definitions": {
+                "quantity": {
+                    "type": "integer"
+                }
+            },
+            "type": "object",
+            "properties": {
+                "name": {
+                    "$ref": "#/$defs/name"
+                },
+                "quantity": {
+                    "$ref": "#/definitions/quantity"
+                }
+            },
+            "required": ["name", "quantity"]
+        }
+    );
+
+    let name: SchemaWithDefsAndDefinitionsDefName = "bolt".to_owned();
+    let quantity: SchemaWithDefsAndDefinitionsDefQuantity = 3;
+    let json = "{\"name\":\"bolt\",\"quantity\":3}";
+    let value = SchemaWithDefsAndDefinitions::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.name, name);
+    assert_eq!(value.quantity, quantity);
+}
+
+/// Test refs.
+#[test]
+fn test_ref() {
+    schema_struct!(
+        vis = pub,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithRef",
+            "description": "A schema with ref fields",
+            "$defs": {
+                "myInteger": {
+                    "description": "An alias for an integer value",
+                    "type": "integer"
+                },
+                "stringArray": {
+                    "description": "An array of strings",
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    }
+                },
+                "objectWithStringArray": {
+                    "description": "An object containing a string array",
+                    "type": "object",
+                    "properties": {
+                        "inner_array": {
+                            "$ref": "#/$defs/stringArray"
+                        }
+                    },
+                    "required": ["inner_array"]
+                },
+                "arrayWithItemsRef": {
+                    "description": "An array with item type ref",
+                    "type": "array",
+                    "items": {
+                        "$ref": "#/$defs/myInteger"
+                    }
+                }
+            },
+            "type": "object",
+            "properties": {
+                "my_integer_field": {
+                    "$ref": "#/$defs/myInteger"
+                },
+                "string_array_field": {
+                    "$ref": "#/$defs/stringArray"
+                },
+                "object_with_string_array_field": {
+                    "$ref": "#/definitions/objectWithStringArray"
+                },
+                "array_with_items_ref_field": {
+                    "$ref": "#/$defs/arrayWithItemsRef"
+                },
+                "self_referential_field": {
+                    "$ref": "#"
+                }
+            },
+            "required": ["string_array_field"]
+        }
+    );
+
+    let json_with_ref = "{\"array_with_items_ref_field\":[1,3,7,9],\"my_integer_field\":123,\"object_with_string_array_field\":{\"inner_array\":[\"four\"]},\"self_referential_field\":{\"array_with_items_ref_field\":null,\"my_integer_field\":null,\"object_with_string_array_field\":null,\"self_referential_field\":null,\"string_array_field\":[]},\"string_array_field\":[\"one\",\"two\",\"three\"]}";
+    let value_with_ref = SchemaWithRef::from_str(json_with_ref).unwrap();
+    assert_values_eq!(&value_with_ref.to_str().unwrap(), json_with_ref);
+    assert_eq!(value_with_ref.my_integer_field, Some(123));
+    assert_eq!(
+        value_with_ref.string_array_field,
+        vec!["one".to_owned(), "two".to_owned(), "three".to_owned()]
+    );
+    assert_eq!(
+        value_with_ref.object_with_string_array_field,
+        Some(SchemaWithRefDefObjectWithStringArray {
+            inner_array: vec!["four".to_owned()]
+        })
+    );
+    assert_eq!(
+        value_with_ref.array_with_items_ref_field,
+        Some(vec![1, 3, 7, 9])
+    );
+    assert_eq!(
+        value_with_ref.self_referential_field,
+        Some(Box::new(SchemaWithRef {
+            my_integer_field: None,
+            string_array_field: SchemaWithRefDefStringArray::default(),
+            object_with_string_array_field: None,
+            array_with_items_ref_field: None,
+            self_referential_field: None
+        }))
+    );
+}
+
+/// Test refs between subschemas declared in reverse dependency order.
+#[test]
+fn test_ref_declaration_order() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithReversedDefs",
+            "description": "A schema whose subschemas reference subschemas declared after them",
+            "$defs": {
+                "order": {
+                    "type": "object",
+                    "properties": {
+                        "total": {
+                            "$ref": "#/$defs/money"
+                        },
+                        "line_items": {
+                            "$ref": "#/$defs/lineItems"
+                        }
+                    },
+                    "required": ["total", "line_items"]
+                },
+                "lineItems": {
+                    "type": "array",
+                    "items": {
+                        "$ref": "#/$defs/money"
+                    }
+                },
+                "money": {
+                    "type": "object",
+                    "properties": {
+                        "cents": {
+                            "type": "integer",
+                            "default": 0
+                        }
+                    },
+                    "required": ["cents"],
+                    "default": {}
+                }
+            },
+            "type": "object",
+            "properties": {
+                "order": {
+                    "$ref": "#/$defs/order"
+                }
+            },
+            "required": ["order"]
+        }
+    );
+
+    let json =
+        "{\"order\":{\"total\":{\"cents\":300},\"line_items\":[{\"cents\":100},{\"cents\":200}]}}";
+    let value = SchemaWithReversedDefs::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.order,
+        SchemaWithReversedDefsDefOrder {
+            total: SchemaWithReversedDefsDefMoney { cents: 300 },
+            line_items: vec![
+                SchemaWithReversedDefsDefMoney { cents: 100 },
+                SchemaWithReversedDefsDefMoney { cents: 200 }
+            ]
+        }
+    );
+
+    let json_with_defaults = "{\"order\":{\"line_items\":[]}}";
+    let value_with_defaults = SchemaWithReversedDefs::from_str(json_with_defaults).unwrap();
+    assert_eq!(
+        value_with_defaults.order.total,
+        SchemaWithReversedDefsDefMoney { cents: 0 }
+    );
+}
+
+/// Test boxing only the refs that cycle back to their containing type.
+#[test]
+fn test_ref_cycles() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithRefCycles",
+            "description": "A schema whose subschemas reference each other",
+            "$defs": {
+                "name": {
+                    "type": "string"
+                },
+                "treeNode": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "$ref": "#/$defs/name"
+                        },
+                        "children": {
+                            "$ref": "#/$defs/nodeList"
+                        }
+                    },
+                    "required": ["name", "children"]
+                },
+                "nodeList": {
+                    "type": "array",
+                    "items": {
+                        "$ref": "#/$defs/treeNode"
+                    }
+                }
+            },
+            "type": "object",
+            "properties": {
+                "root": {
+                    "$ref": "#/$defs/treeNode"
+                }
+            },
+            "required": ["root"]
+        }
+    );
+
+    let json = "{\"root\":{\"name\":\"a\",\"children\":[{\"name\":\"b\",\"children\":[]}]}}";
+    let value = SchemaWithRefCycles::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let root: SchemaWithRefCyclesDefTreeNode = value.root;
+    let name: SchemaWithRefCyclesDefName = root.name;
+    assert_eq!(name, "a");
+    let children: Box<SchemaWithRefCyclesDefNodeList> = root.children;
+    assert_eq!(
+        *children,
+        vec![Box::new(SchemaWithRefCyclesDefTreeNode {
+            name: "b".to_owned(),
+            children: Box::default()
+        })]
+    );
+}
+
+/// Test recursive and dynamic refs to the root object.
+#[test]
+fn test_recursive_ref() {
+    schema_struct!(
+        schema = {
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "title": "TreeNode",
+            "description": "A tree node using a recursive ref",
+            "type": "object",
+            "properties": {
+                "value": {
+                    "type": "integer"
+                },
+                "children": {
+                    "type": "array",
+                    "items": {
+                        "$recursiveRef": "#"
+                    }
+                },
+                "parent": {
+                    "$dynamicRef": "#"
+                }
+            },
+            "required": ["value", "children"]
+        }
+    );
+
+    let json = "{\"value\":1,\"children\":[{\"value\":2,\"children\":[],\"parent\":null},{\"value\":3,\"children\":[{\"value\":4,\"children\":[],\"parent\":null}],\"parent\":null}],\"parent\":null}";
+    let value = TreeNode::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.children.len(), 2);
+    assert_eq!(value.children[1].children[0].value, 4);
+}
+
+/// Test refs to subschemas that are enums or tuples.
+#[test]
+fn test_ref_enum_and_tuple() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithEnumAndTupleDefs",
+            "description": "A schema with enum and tuple subschemas",
+            "type": "object",
+            "properties": {
+                "task_priority": {
+                    "$ref": "#/$defs/task_priority"
+                },
+                "point": {
+                    "$ref": "#/$defs/point"
+                }
+            },
+            "required": ["task_priority", "point"],
+            "$defs": {
+                "task_priority": {
+                    "enum": ["low", "high"],
+                    "default": "low"
+                },
+                "point": {
+                    "type": "array",
+                    "prefixItems": [
+                        {
+                            "type": "integer"
+                        },
+                        {
+                            "type": "integer"
+                        }
+                    ]
+                }
+            }
+        }
+    );
+
+    let json = "{\"task_priority\":\"high\",\"point\":[1,2]}";
+    let value = SchemaWithEnumAndTupleDefs::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.task_priority,
+        SchemaWithEnumAndTupleDefsDefTaskPriority::High
+    );
+    assert_eq!(value.point, (1, 2));
+
+    let point: SchemaWithEnumAndTupleDefsDefPoint = (3, 4);
+    assert_eq!(point, (3, 4));
+    assert_eq!(
+        SchemaWithEnumAndTupleDefsDefTaskPriority::default(),
+        SchemaWithEnumAndTupleDefsDefTaskPriority::Low
+    );
+}
+
+/// Test skipping subschemas that are not referenced by the root object.
+#[test]
+fn test_prune_unused_defs() {
+    // Defined here to ensure that the macro does not also generate it.
+    #[allow(dead_code)]
+    struct SchemaWithUnusedDefsDefUnused;
+
+    schema_struct!(
+        prune_unused_defs = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithUnusedDefs",
+            "description": "A schema with an unreferenced subschema",
+            "type": "object",
+            "$defs": {
+                "used": {
+                    "type": "array",
+                    "items": {
+                        "$ref": "#/$defs/nested"
+                    }
+                },
+                "nested": {
+                    "type": "integer"
+                },
+                "unused": {
+                    "type": "string"
+                }
+            },
+            "properties": {
+                "values": {
+                    "$ref": "#/$defs/used"
+                }
+            },
+            "required": ["values"]
+        }
+    );
+
+    let json = "{\"values\":[1,2]}";
+    let value = SchemaWithUnusedDefs::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let nested: &SchemaWithUnusedDefsDefNested = &value.values[0];
+    assert_eq!(*nested, 1);
+}
+
+/// Test refs resolving to types generated by other macro invocations.
+#[test]
+fn test_external_types() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "Money",
+            "description": "An amount of money",
+            "type": "object",
+            "properties": {
+                "amount": {
+                    "type": "integer"
+                },
+                "currency": {
+                    "type": "string"
+                }
+            },
+            "required": ["amount", "currency"]
+        }
+    );
+
+    // Defined here to ensure that the macro does not also generate it.
+    #[allow(dead_code)]
+    struct InvoiceDefMoney;
+
+    schema_struct!(
+        external_types = { "#/$defs/money": Money },
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "Invoice",
+            "description": "An invoice referencing a shared money type",
+            "type": "object",
+            "$defs": {
+                "money": {
+                    "type": "object",
+                    "properties": {
+                        "amount": {
+                            "type": "integer"
+                        },
+                        "currency": {
+                            "type": "string"
+                        }
+                    }
+                }
+            },
+            "properties": {
+                "total": {
+                    "$ref": "#/$defs/money"
+                },
+                "discount": {
+                    "$ref": "#/$defs/money"
+                }
+            },
+            "required": ["total"]
+        }
+    );
+
+    let json = "{\"total\":{\"amount\":10,\"currency\":\"EUR\"},\"discount\":null}";
+    let value = Invoice::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let total: Money = value.total;
+    assert_eq!(total.amount, 10);
+    assert_eq!(value.discount, None::<Money>);
+}
+
+/// Test inlining subschemas that are referenced only once.
+#[test]
+fn test_flatten_refs() {
+    schema_struct!(
+        flatten_refs = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithFlattenRefs",
+            "description": "A schema with single-use and shared refs",
+            "type": "object",
+            "$defs": {
+                "money": {
+                    "type": "object",
+                    "properties": {
+                        "amount": {
+                            "type": "integer"
+                        }
+                    },
+                    "required": ["amount"]
+                },
+                "label": {
+                    "type": "string"
+                }
+            },
+            "properties": {
+                "price": {
+                    "$ref": "#/$defs/money",
+                    "description": "The price"
+                },
+                "title": {
+                    "$ref": "#/$defs/label"
+                },
+                "subtitle": {
+                    "$ref": "#/$defs/label"
+                },
+                "next": {
+                    "$ref": "#"
+                }
+            },
+            "required": ["price", "title", "subtitle"]
+        }
+    );
+
+    let json = "{\"price\":{\"amount\":5},\"title\":\"a\",\"subtitle\":\"b\",\"next\":null}";
+    let value = SchemaWithFlattenRefs::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let price: SchemaWithFlattenRefsPrice = value.price;
+    assert_eq!(price.amount, 5);
+    let title: SchemaWithFlattenRefsDefLabel = value.title;
+    assert_eq!(title, "a");
+}
+
+/// Test refs that reuse the item schema of an array.
+#[test]
+fn test_ref_array_items() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithItemsRef",
+            "description": "A schema reusing an array's item schema",
+            "type": "object",
+            "properties": {
+                "tags": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "label": {
+                                "type": "string"
+                            }
+                        },
+                        "required": ["label"]
+                    }
+                },
+                "primary_tag": {
+                    "$ref": "#/properties/tags/items"
+                }
+            },
+            "required": ["tags", "primary_tag"]
+        }
+    );
+
+    let json = "{\"tags\":[{\"label\":\"a\"},{\"label\":\"b\"}],\"primary_tag\":{\"label\":\"a\"}}";
+    let value = SchemaWithItemsRef::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let primary_tag: SchemaWithItemsRefDefPropertiesTagsItems = value.primary_tag;
+    assert_eq!(primary_tag.label, "a");
+    assert_eq!(value.tags[0], primary_tag);
+}
+
+/// Test generating only an allowlisted subset of subschema definitions.
+#[test]
+fn test_defs_allowlist() {
+    // Defined here to ensure that the macro does not also generate it.
+    #[allow(dead_code)]
+    struct SchemaWithDefsAllowlistDefUnused;
+
+    schema_struct!(
+        defs = ["Address"],
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefsAllowlist",
+            "description": "A schema generating only some of its subschemas",
+            "type": "object",
+            "$defs": {
+                "Money": {
+                    "type": "object",
+                    "properties": {
+                        "amount": {
+                            "type": "integer"
+                        },
+                        "currency": {
+                            "$ref": "#/$defs/Currency"
+                        }
+                    },
+                    "required": ["amount", "currency"]
+                },
+                "Currency": {
+                    "type": "string"
+                },
+                "Address": {
+                    "type": "object",
+                    "properties": {
+                        "city": {
+                            "type": "string"
+                        }
+                    },
+                    "required": ["city"]
+                },
+                "Unused": {
+                    "type": "integer"
+                }
+            },
+            "properties": {
+                "price": {
+                    "$ref": "#/$defs/Money"
+                }
+            },
+            "required": ["price"]
+        }
+    );
+
+    let json = "{\"price\":{\"amount\":5,\"currency\":\"USD\"}}";
+    let value = SchemaWithDefsAllowlist::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let currency: &SchemaWithDefsAllowlistDefCurrency = &value.price.currency;
+    assert_eq!(currency, "USD");
+
+    let address = SchemaWithDefsAllowlistDefAddress::from_str("{\"city\":\"Paris\"}").unwrap();
+    assert_eq!(address.city, "Paris");
+}
+
+/// Test constructing a struct containing arrays of objects.
+#[test]
+fn test_array_of_objects() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithArrayOfObjects",
+            "description": "A schema with arrays of objects",
+            "type": "object",
+            "properties": {
+                "array_of_objects": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "object_id": {
+                                "type": "integer"
+                            }
+                        },
+                        "required": ["object_id"]
+                    }
+                }
+            },
+            "required": ["array_of_objects"]
+        }
+    );
+
+    let json_with_array_of_objects = "{\"array_of_objects\":[{\"object_id\":3},{\"object_id\":7}]}";
+    let value_with_array_of_objects =
+        SchemaWithArrayOfObjects::from_str(json_with_array_of_objects).unwrap();
+    assert_values_eq!(
+        &value_with_array_of_objects.to_str().unwrap(),
+        json_with_array_of_objects
+    );
+    assert_eq!(
+        value_with_array_of_objects.array_of_objects,
+        vec![
+            SchemaWithArrayOfObjectsItemsArrayOfObjects { object_id: 3 },
+            SchemaWithArrayOfObjectsItemsArrayOfObjects { object_id: 7 }
+        ]
+    );
+
+    let json_with_empty_array_of_objects = "{\"array_of_objects\":[]}";
+    let value_with_empty_array_of_objects =
+        SchemaWithArrayOfObjects::from_str(json_with_empty_array_of_objects).unwrap();
+    assert_values_eq!(
+        &value_with_empty_array_of_objects.to_str().unwrap(),
+        json_with_empty_array_of_objects
+    );
+    assert_eq!(value_with_empty_array_of_objects.array_of_objects, vec![]);
+}
+
+/// Test constructing a struct containing nested arrays.
+#[test]
+fn test_nested_arrays() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithNestedArrays",
+            "description": "A schema with nested arrays",
+            "type": "object",
+            "properties": {
+                "nested_arrays": {
+                    "description": "This should be defined as `Vec<Vec<Vec<...>>>`",
+                    "type": "array",
+                    "items": {
+                        "type": "array",
+                        "items": {
+                            "type": "array",
+                            "items": {
+                                "type": "integer"
+                            }
+                        }
+                    }
+                }
+            },
+            "required": ["nested_arrays"]
+        }
+    );
+
+    let json_with_nested_arrays = "{\"nested_arrays\":[[[1,2],[3,4]],[[5,6],[7,8]]]}";
+    let value_with_nested_arrays =
+        SchemaWithNestedArrays::from_str(json_with_nested_arrays).unwrap();
+    assert_values_eq!(
+        &value_with_nested_arrays.to_str().unwrap(),
+        json_with_nested_arrays
+    );
+    assert_eq!(
+        value_with_nested_arrays.nested_arrays,
+        vec![vec![vec![1, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]]
+    );
+
+    let json_with_empty_nested_array1 = "{\"nested_arrays\":[]}";
+    let value_with_empty_nested_array1 =
+        SchemaWithNestedArrays::from_str(json_with_empty_nested_array1).unwrap();
+    assert_values_eq!(
+        &value_with_empty_nested_array1.to_str().unwrap(),
+        json_with_empty_nested_array1
+    );
+    assert_eq!(
+        value_with_empty_nested_array1.nested_arrays,
+        Vec::<Vec<Vec<i64>>>::new()
+    );
+
+    let json_with_empty_nested_array2 = "{\"nested_arrays\":[[]]}";
+    let value_with_empty_nested_array2 =
+        SchemaWithNestedArrays::from_str(json_with_empty_nested_array2).unwrap();
+    assert_values_eq!(
+        &value_with_empty_nested_array2.to_str().unwrap(),
+        json_with_empty_nested_array2
+    );
+    assert_eq!(
+        value_with_empty_nested_array2.nested_arrays,
+        vec![Vec::<Vec<i64>>::new()]
+    );
+
+    let json_with_empty_nested_array3 = "{\"nested_arrays\":[[[]]]}";
+    let value_with_empty_nested_array3 =
+        SchemaWithNestedArrays::from_str(json_with_empty_nested_array3).unwrap();
+    assert_values_eq!(
+        &value_with_empty_nested_array3.to_str().unwrap(),
+        json_with_empty_nested_array3
+    );
+    assert_eq!(
+        value_with_empty_nested_array3.nested_arrays,
+        vec![vec![Vec::<i64>::new()]]
+    );
+}
+
+/// Test constructing a struct containing nested objects.
+#[test]
+fn test_nested_objects() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithNestedObjects",
+            "description": "A schema with nested objects",
+            "type": "object",
+            "properties": {
+                "foo": {
+                    "type": "object",
+                    "properties": {
+                        "bar": {
+                            "type": "object",
+                            "properties": {
+                                "baz": {
+                                    "type": "object",
+                                    "properties": {
+                                        "message": {
+                                            "type": "string"
+                                        }
+                                    },
+                                    "required": ["message"]
+                                }
+                            },
+                            "required": ["baz"]
+                        }
+                    },
+                    "required": ["bar"]
+                }
+            },
+            "required": ["foo"]
+        }
+    );
+
+    let json_with_nested_objects =
+        "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"Hello, nested object!\"}}}}";
+    let value_with_nested_objects =
+        SchemaWithNestedObjects::from_str(json_with_nested_objects).unwrap();
+    assert_values_eq!(
+        &value_with_nested_objects.to_str().unwrap(),
+        json_with_nested_objects
+    );
+    assert_eq!(
+        value_with_nested_objects.foo.bar.baz.message,
+        "Hello, nested object!"
+    );
+}
+
+/// Test structs with default primitive fields.
+#[test]
+fn test_default_primitives() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultPrimitives",
+            "description": "A product from Acme's catalog",
+            "type": "object",
+            "properties": {
+                "null_prop": {
+                    "type": "null",
+                    "default": null
+                },
+                "boolean_prop": {
+                    "type": "boolean",
+                    "default": true
+                },
+                "integer_prop": {
+                    "type": "integer",
+                    "default": 7
+                },
+                "number_prop": {
+                    "type": "number",
+                    "default": 3.45
+                },
+                "string_prop": {
+                    "type": "string",
+                    "default": "Hello, world!"
+                }
+            },
+            "required": ["null_prop", "boolean_prop", "integer_prop", "number_prop", "string_prop"]
+        }
+    );
+
+    let product_json = "{\"null_prop\":null,\"boolean_prop\":true,\"integer_prop\":7,\"number_prop\":3.45,\"string_prop\":\"Hello, world!\"}";
+    let product = SchemaWithDefaultPrimitives::from_str("{}").unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert!(product.boolean_prop);
+    assert_eq!(product.integer_prop, 7);
+    assert_eq!(product.number_prop, 3.45);
+    assert_eq!(product.string_prop, "Hello, world!".to_owned());
+}
+
+/// Test defaults that lie within exclusive and large integer bounds.
+#[test]
+fn test_default_exclusive_bounds() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "SchemaWithDefaultExclusiveBounds",
+            "type": "object",
+            "properties": {
+                "price": {
+                    "type": "number",
+                    "exclusiveMinimum": 0,
+                    "exclusiveMaximum": 1,
+                    "default": 0.5
+                },
+                "count": {
+                    "type": "integer",
+                    "format": "uint64",
+                    "maximum": 18446744073709551615,
+                    "default": 18446744073709551615
+                }
+            },
+            "required": ["price", "count"]
+        }
+    );
+
+    let value = SchemaWithDefaultExclusiveBounds::from_str("{}").unwrap();
+    assert_eq!(value.price, 0.5);
+    assert_eq!(value.count, u64::MAX);
+}
+
+/// Test structs with default array fields.
+#[test]
+fn test_default_array() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultArray",
+            "description": "A schema with a field of type `array`",
+            "type": "object",
+            "properties": {
+                "array_field": {
+                    "type": "array",
+                    "items": {
+                        "type": "integer"
+                    },
+                    "default": [7, 8, 9]
+                }
+            },
+            "required": ["array_field"]
+        }
+    );
+
+    let json = "{\"array_field\":[7,8,9]}";
+    let value = SchemaWithDefaultArray::from_str("{}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.array_field, vec![7, 8, 9]);
+}
+
+/// Test structs with default object fields.
+#[test]
+fn test_default_object() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultObject",
+            "description": "A schema with a field of type `object`",
+            "type": "object",
+            "properties": {
+                "object_field": {
+                    "type": "object",
+                    "properties": {
+                        "inner_field": {
+                            "type": "string"
+                        }
+                    },
+                    "required": ["inner_field"],
+                    "default": {
+                        "inner_field": "an inner object field"
+                    }
+                }
+            },
+            "required": ["object_field"]
+        }
+    );
+
+    let json_with_object = "{\"object_field\":{\"inner_field\":\"an inner object field\"}}";
+    let value_with_object = SchemaWithDefaultObject::from_str("{}").unwrap();
+    assert_values_eq!(&value_with_object.to_str().unwrap(), json_with_object);
+    assert_eq!(
+        value_with_object.object_field.inner_field,
+        "an inner object field"
+    );
+}
+
+/// Test structs with default enum fields.
+#[test]
+fn test_default_enum() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultEnum",
+            "description": "A schema with an enum field",
+            "type": "object",
+            "properties": {
+                "enum_field": {
+                    "enum": ["first", "second", "third"],
+                    "default": "first"
+                }
+            },
+            "required": ["enum_field"]
+        }
+    );
+
+    let json = "{\"enum_field\":\"first\"}";
+    let value = SchemaWithDefaultEnum::from_str("{}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert!(matches!(
+        value.enum_field,
+        SchemaWithDefaultEnumEnumField::First
+    ));
+    assert!(matches!(
+        SchemaWithDefaultEnumEnumField::default(),
+        SchemaWithDefaultEnumEnumField::First
+    ));
+}
+
+/// Test structs with default tuple fields.
+#[test]
+fn test_default_tuple() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultTuple",
+            "description": "A schema with a tuple field",
+            "type": "object",
+            "properties": {
+                "tuple_field": {
+                    "type": "array",
+                    "prefixItems": [
+                        {
+                            "type": "integer",
+                            "description": "The address number"
+                        },
+                        {
+                            "type": "string",
+                            "description": "The street name"
+                        },
+                        {
+                            "enum": ["Street", "Avenue", "Boulevard"],
+                            "description": "The street type"
+                        },
+                        {
+                            "enum": ["NW", "NE", "SW", "SE"],
+                            "description": "The city quadrant of the address"
+                        }
+                    ],
+                    "default": [1600, "Pennsylvania", "Avenue", "NW"]
+                }
+            },
+            "required": ["tuple_field"]
+        }
+    );
+
+    let json = "{\"tuple_field\":[1600,\"Pennsylvania\",\"Avenue\",\"NW\"]}";
+    let value = SchemaWithDefaultTuple::from_str("{}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.tuple_field.0, 1600);
+    assert_eq!(value.tuple_field.1, "Pennsylvania".to_owned());
+    assert!(matches!(
+        value.tuple_field.2,
+        SchemaWithDefaultTupleTupleField2::Avenue
+    ));
+    assert!(matches!(
+        value.tuple_field.3,
+        SchemaWithDefaultTupleTupleField3::Nw
+    ));
+}
+
+/// Test refs pointing into the properties of subschemas and the root object.
+#[test]
+fn test_ref_nested_pointer() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithNestedPointerRefs",
+            "description": "A schema with refs into nested properties",
+            "$defs": {
+                "outer": {
+                    "type": "object",
+                    "properties": {
+                        "inner": {
+                            "type": "object",
+                            "properties": {
+                                "value": { "type": "integer" }
+                            },
+                            "required": ["value"]
+                        }
+                    },
+                    "required": ["inner"]
+                }
+            },
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "object",
+                    "properties": {
+                        "street": { "type": "string" }
+                    },
+                    "required": ["street"]
+                },
+                "billing_address": { "$ref": "#/properties/address" },
+                "inner": { "$ref": "#/$defs/outer/properties/inner" }
+            },
+            "required": ["address", "billing_address", "inner"]
+        }
+    );
+
+    let json = "{\"address\":{\"street\":\"Main\"},\"billing_address\":{\"street\":\"High\"},\"inner\":{\"value\":3}}";
+    let value = SchemaWithNestedPointerRefs::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.address.street, "Main");
+    assert_eq!(value.billing_address.street, "High");
+    assert_eq!(
+        value.inner,
+        SchemaWithNestedPointerRefsDefDefsOuterPropertiesInner { value: 3 }
+    );
+}
+
+/// Test structs with default refs.
+#[test]
+fn test_default_ref() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultRef",
+            "description": "A schema with ref fields",
+            "$defs": {
+                "myDef": {
+                    "type": "object",
+                    "properties": {
+                        "inner": {
+                            "type": "integer",
+                            "default": 1
+                        }
+                    },
+                    "required": ["inner"],
+                    "default": {
+                        "inner": 2
+                    }
+                }
+            },
+            "type": "object",
+            "properties": {
+                "my_def_field": {
+                    "$ref": "#/definitions/myDef"
+                },
+                "self_ref_field": {
+                    "type": "object",
+                    "properties": {
+                        "self_ref_field_inner": {
+                            "$ref": "#"
+                        }
+                    },
+                    "default": {}
+                }
+            },
+            "required": ["my_def_field", "self_ref_field"],
+            "default": {
+                "my_def_field": {
+                    "inner": 3
+                },
+                "self_ref_field": {}
+            }
+        }
+    );
+
+    let json1 =
+        "{\"my_def_field\":{\"inner\":2},\"self_ref_field\":{\"self_ref_field_inner\":null}}";
+    let value1 = SchemaWithDefaultRef::from_str("{}").unwrap();
+    assert_values_eq!(&value1.to_str().unwrap(), json1);
+    assert_eq!(
+        value1.my_def_field,
+        SchemaWithDefaultRefDefMyDef { inner: 2 }
+    );
+
+    let json2 =
+        "{\"my_def_field\":{\"inner\":1},\"self_ref_field\":{\"self_ref_field_inner\":null}}";
+    let value2 = SchemaWithDefaultRef::from_str("{\"my_def_field\":{}}").unwrap();
+    assert_values_eq!(&value2.to_str().unwrap(), json2);
+    assert_eq!(
+        value2.my_def_field,
+        SchemaWithDefaultRefDefMyDef { inner: 1 }
+    );
+
+    let json3 = "{\"my_def_field\":{\"inner\":2},\"self_ref_field\":{\"self_ref_field_inner\":{\"my_def_field\":{\"inner\":3},\"self_ref_field\":{\"self_ref_field_inner\":null}}}}";
+    let value3 = SchemaWithDefaultRef::from_str("{\"self_ref_field\":{}}").unwrap();
+    assert_values_eq!(&value3.to_str().unwrap(), json3);
+    assert_eq!(
+        value3.self_ref_field.self_ref_field_inner,
+        Some(Box::new(SchemaWithDefaultRef {
+            my_def_field: SchemaWithDefaultRefDefMyDef { inner: 3 },
+            self_ref_field: SchemaWithDefaultRefSelfRefField {
+                self_ref_field_inner: None
+            }
+        }))
+    );
+}
+
+/// Test refs to primitive subschemas declaring their own defaults.
+#[test]
+fn test_default_ref_primitive_subschema() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultSubschema",
+            "$defs": {
+                "currency": {
+                    "type": "string",
+                    "default": "USD"
+                }
+            },
+            "type": "object",
+            "properties": {
+                "currency": { "$ref": "#/$defs/currency" }
+            },
+            "required": ["currency"]
+        }
+    );
+
+    let value = SchemaWithDefaultSubschema::from_str("{}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), "{\"currency\":\"USD\"}");
+    assert_eq!(value.currency, "USD");
+}
+
+/// Test optional refs to subschemas declaring defaults.
+#[test]
+fn test_default_optional_ref() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithOptionalDefaultRef",
+            "$defs": {
+                "limits": {
+                    "type": "object",
+                    "properties": {
+                        "max": { "type": "integer" }
+                    },
+                    "required": ["max"],
+                    "default": { "max": 10 }
+                }
+            },
+            "type": "object",
+            "properties": {
+                "limits": { "$ref": "#/$defs/limits" }
+            }
+        }
+    );
+
+    let value = SchemaWithOptionalDefaultRef::from_str("{}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), "{\"limits\":{\"max\":10}}");
+    assert_eq!(
+        value.limits,
+        Some(SchemaWithOptionalDefaultRefDefLimits { max: 10 })
+    );
+
+    let value = SchemaWithOptionalDefaultRef::from_str("{\"limits\":null}").unwrap();
+    assert_eq!(value.limits, None);
+}
+
+/// Test optional refs to enum subschemas declaring defaults.
+#[test]
+fn test_default_ref_enum() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "SchemaWithDefaultRefEnum",
+            "$defs": {
+                "size": {
+                    "enum": ["small", "medium", "large"],
+                    "default": "medium"
+                }
+            },
+            "type": "object",
+            "properties": {
+                "size": { "$ref": "#/$defs/size" },
+                "min_size": {
+                    "$ref": "#/$defs/size",
+                    "default": "small"
+                }
+            }
+        }
+    );
+
+    let value = SchemaWithDefaultRefEnum::from_str("{}").unwrap();
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"size\":\"medium\",\"min_size\":\"small\"}"
+    );
+    assert_eq!(value.size, Some(SchemaWithDefaultRefEnumDefSize::Medium));
+    assert_eq!(value.min_size, Some(SchemaWithDefaultRefEnumDefSize::Small));
+
+    let value =
+        SchemaWithDefaultRefEnum::from_str("{\"size\":\"large\",\"min_size\":null}").unwrap();
+    assert_eq!(value.size, Some(SchemaWithDefaultRefEnumDefSize::Large));
+    assert_eq!(value.min_size, None);
+}
+
+/// Test that refs from subschemas back to themselves do not use the
+/// subschema's default.
+#[test]
+fn test_default_ref_cyclic() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "SchemaWithDefaultRefCyclic",
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "label": { "type": "string" },
+                        "next": { "$ref": "#/$defs/node" }
+                    },
+                    "default": { "label": "tail" }
+                }
+            },
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "head": { "$ref": "#/$defs/node" }
+            },
+            "required": ["name"]
+        }
+    );
+
+    let value = SchemaWithDefaultRefCyclic::from_str("{\"name\":\"a\"}").unwrap();
+    assert_eq!(
+        value.head,
+        Some(SchemaWithDefaultRefCyclicDefNode {
+            label: Some("tail".to_owned()),
+            next: None,
+        })
+    );
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"name\":\"a\",\"head\":{\"label\":\"tail\",\"next\":null}}"
+    );
+
+    let value = SchemaWithDefaultRefCyclic::from_str("{\"name\":\"a\",\"head\":{\"label\":\"x\"}}")
+        .unwrap();
+    assert_eq!(
+        value.head,
+        Some(SchemaWithDefaultRefCyclicDefNode {
+            label: Some("x".to_owned()),
+            next: None,
+        })
+    );
+}
+
+/// Test implementing `Default` for structs from their schema defaults.
+#[test]
+fn test_default_impl() {
+    schema_struct!(
+        default_impl = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultImpl",
+            "description": "A schema whose fields can all be defaulted",
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string"
+                },
+                "count": {
+                    "type": "integer",
+                    "default": 3
+                },
+                "settings": {
+                    "type": "object",
+                    "properties": {
+                        "enabled": {
+                            "type": "boolean",
+                            "default": true
+                        },
+                        "label": {
+                            "type": "string"
+                        }
+                    },
+                    "required": ["enabled", "label"],
+                    "default": {
+                        "label": "main"
+                    }
+                }
+            },
+            "required": ["count", "settings"]
+        }
+    );
+
+    assert_eq!(
+        SchemaWithDefaultImpl::default(),
+        SchemaWithDefaultImpl {
+            name: None,
+            count: 3,
+            settings: SchemaWithDefaultImplSettings {
+                enabled: true,
+                label: "main".to_owned()
+            }
+        }
+    );
+    assert_eq!(
+        SchemaWithDefaultImpl::default(),
+        SchemaWithDefaultImpl::from_str("{}").unwrap()
+    );
+
+    schema_struct!(
+        default_impl = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithoutDefaultImpl",
+            "description": "A schema with a field that cannot be defaulted",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer"
+                }
+            },
+            "required": ["id"]
+        }
+    );
+
+    let value = SchemaWithoutDefaultImpl::from_str("{\"id\":1}").unwrap();
+    assert_eq!(value.id, 1);
+}
+
+/// Test generating constructors taking the required fields.
+#[test]
+fn test_constructor() {
+    schema_struct!(
+        constructor = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithConstructor",
+            "description": "A schema with a constructor",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer"
+                },
+                "nickname": {
+                    "type": "string"
+                },
+                "count": {
+                    "type": "integer",
+                    "default": 3
+                },
+                "settings": {
+                    "type": "object",
+                    "properties": {
+                        "enabled": {
+                            "type": "boolean",
+                            "default": true
+                        },
+                        "label": {
+                            "type": "string"
+                        }
+                    },
+                    "required": ["enabled", "label"]
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "default": ["new"]
+                }
+            },
+            "required": ["settings", "id", "count"]
+        }
+    );
+
+    let value =
+        SchemaWithConstructor::new(5, SchemaWithConstructorSettings::new("main".to_owned()));
+    assert_eq!(
+        value,
+        SchemaWithConstructor {
+            id: 5,
+            nickname: None,
+            count: 3,
+            settings: SchemaWithConstructorSettings {
+                enabled: true,
+                label: "main".to_owned()
+            },
+            tags: Some(vec!["new".to_owned()])
+        }
+    );
+    assert_eq!(
+        value,
+        SchemaWithConstructor::from_str("{\"id\":5,\"settings\":{\"label\":\"main\"}}").unwrap()
+    );
+}
+
+/// Test default values expressed as refs to values defined in the schema.
+#[test]
+fn test_default_ref_value() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultRefValue",
+            "description": "A schema with defaults defined elsewhere in the schema",
+            "$defs": {
+                "defaultMoney": {
+                    "const": {
+                        "amount": 5,
+                        "currency": "USD"
+                    }
+                },
+                "defaultCurrency": {
+                    "const": "USD"
+                }
+            },
+            "type": "object",
+            "properties": {
+                "price": {
+                    "type": "object",
+                    "properties": {
+                        "amount": { "type": "integer" },
+                        "currency": { "type": "string" }
+                    },
+                    "required": ["amount", "currency"],
+                    "default": { "$ref": "#/$defs/defaultMoney" }
+                },
+                "currency": {
+                    "type": "string",
+                    "default": { "$ref": "#/$defs/defaultCurrency" }
+                }
+            },
+            "required": ["price", "currency"]
+        }
+    );
+
+    let json = "{\"price\":{\"amount\":5,\"currency\":\"USD\"},\"currency\":\"USD\"}";
+    let value = SchemaWithDefaultRefValue::from_str("{}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.price,
+        SchemaWithDefaultRefValuePrice {
+            amount: 5,
+            currency: "USD".to_owned()
+        }
+    );
+    assert_eq!(value.currency, "USD");
+}
+
+/// Test structs with default arrays of objects.
+#[test]
+fn test_default_array_of_objects() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultArrayOfObjects",
+            "description": "A schema with arrays of objects",
+            "type": "object",
+            "properties": {
+                "array_of_objects": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "object_id": {
+                                "type": "integer",
+                                "default": 123
+                            }
+                        },
+                        "required": ["object_id"],
+                        "default": {
+                            "object_id": 456
+                        }
+                    },
+                    "default": [
+                        {
+                            "object_id": 789
+                        }
+                    ]
+                }
+            },
+            "required": ["array_of_objects"]
+        }
+    );
+
+    let json1 = "{\"array_of_objects\":[{\"object_id\":789}]}";
+    let value1 = SchemaWithDefaultArrayOfObjects::from_str("{}").unwrap();
+    assert_values_eq!(&value1.to_str().unwrap(), json1);
+    assert_eq!(
+        value1.array_of_objects,
+        vec![SchemaWithDefaultArrayOfObjectsItemsArrayOfObjects { object_id: 789 }]
+    );
+
+    let json2 = "{\"array_of_objects\":[{\"object_id\":123}]}";
+    let value2 = SchemaWithDefaultArrayOfObjects::from_str("{\"array_of_objects\":[{}]}").unwrap();
+    assert_values_eq!(&value2.to_str().unwrap(), json2);
+    assert_eq!(
+        value2.array_of_objects,
+        vec![SchemaWithDefaultArrayOfObjectsItemsArrayOfObjects { object_id: 123 }]
+    );
+}
+
+/// Test structs with default nested arrays.
+#[test]
+fn test_default_nested_arrays() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultNestedArrays",
+            "description": "A schema with nested arrays",
+            "type": "object",
+            "properties": {
+                "nested_arrays": {
+                    "description": "This should be defined as `Vec<Vec<Vec<...>>>`",
+                    "type": "array",
+                    "items": {
+                        "type": "array",
+                        "items": {
+                            "type": "array",
+                            "items": {
+                                "type": "integer",
+                                "default": 123
+                            },
+                            "default": [234]
+                        },
+                        "default": [[345]]
+                    },
+                    "default": [[[456]]]
+                }
+            },
+            "required": ["nested_arrays"]
+        }
+    );
+
+    let json = "{\"nested_arrays\":[[[456]]]}";
+    let value = SchemaWithDefaultNestedArrays::from_str("{}").unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.nested_arrays, vec![vec![vec![456]]]);
+}
+
+/// Test structs with default nested objects.
+#[test]
+fn test_default_nested_objects() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultNestedObjects",
+            "description": "A schema with nested objects",
+            "type": "object",
+            "properties": {
+                "foo": {
+                    "type": "object",
+                    "properties": {
+                        "bar": {
+                            "type": "object",
+                            "properties": {
+                                "baz": {
+                                    "type": "object",
+                                    "properties": {
+                                        "message": {
+                                            "type": "string",
+                                            "default": "one"
+                                        }
+                                    },
+                                    "required": ["message"],
+                                    "default": {
+                                        "message": "two"
+                                    }
+                                }
+                            },
+                            "required": ["baz"],
+                            "default": {
+                                "baz": {
+                                    "message": "three"
+                                }
+                            }
+                        }
+                    },
+                    "required": ["bar"],
+                    "default": {
+                        "bar": {
+                            "baz": {
+                                "message": "four"
+                            }
+                        }
+                    }
+                }
+            },
+            "required": ["foo"]
+        }
+    );
+
+    let json1 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"one\"}}}}";
+    let value1 =
+        SchemaWithDefaultNestedObjects::from_str("{\"foo\":{\"bar\":{\"baz\":{}}}}").unwrap();
+    assert_values_eq!(&value1.to_str().unwrap(), json1);
+    assert_eq!(value1.foo.bar.baz.message, "one");
+
+    let json2 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"two\"}}}}";
+    let value2 = SchemaWithDefaultNestedObjects::from_str("{\"foo\":{\"bar\":{}}}").unwrap();
+    assert_values_eq!(&value2.to_str().unwrap(), json2);
+    assert_eq!(value2.foo.bar.baz.message, "two");
+
+    let json3 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"three\"}}}}";
+    let value3 = SchemaWithDefaultNestedObjects::from_str("{\"foo\":{}}").unwrap();
+    assert_values_eq!(&value3.to_str().unwrap(), json3);
+    assert_eq!(value3.foo.bar.baz.message, "three");
+
+    let json4 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"four\"}}}}";
+    let value4 = SchemaWithDefaultNestedObjects::from_str("{}").unwrap();
+    assert_values_eq!(&value4.to_str().unwrap(), json4);
+    assert_eq!(value4.foo.bar.baz.message, "four");
+}
+
+/// Test structs with default values propagated through objects.
+#[test]
+fn test_default_propagation() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithPropagatedDefaults",
+            "type": "object",
+            "properties": {
+                "foo": {
+                    "type": "object",
+                    "properties": {
+                        "bar": {
+                            "type": "object",
+                            "properties": {
+                                "baz": {
+                                    "type": "object",
+                                    "properties": {
+                                        "message": {
+                                            "type": "string",
+                                            "default": "Hello, default propagation!"
+                                        }
+                                    },
+                                    "default": {}
+                                }
+                            },
+                            "default": {}
+                        }
+                    },
+                    "default": {}
+                },
+                "tuple_field": {
+                    "type": "array",
+                    "prefixItems": [
+                        {
+                            "type": "integer",
+                            "description": "The address number",
+                            "default": 1600
+                        },
+                        {
+                            "type": "string",
+                            "description": "The street name",
+                            "default": "Pennsylvania"
+                        },
+                        {
+                            "enum": ["Street", "Avenue", "Boulevard"],
+                            "description": "The street type",
+                            "default": "Avenue"
+                        },
+                        {
+                            "enum": ["NW", "NE", "SW", "SE"],
+                            "description": "The city quadrant of the address",
+                            "default": "NW"
+                        }
+                    ],
+                    "default": [2300]
+                },
+                "foo_with_null_default": {
+                    "type": "object",
+                    "properties": {
+                        "bar": {
+                            "type": "object",
+                            "properties": {
+                                "baz": {
+                                    "type": "object",
+                                    "properties": {
+                                        "message": {
+                                            "type": "string",
+                                            "default": "This should not propagate"
+                                        }
+                                    },
+                                    "default": {}
+                                }
+                            },
+                            "default": {
+                                "baz": null
+                            }
+                        }
+                    },
+                    "default": {}
+                }
+            }
+        }
+    );
+
+    let json1 = "{\"foo\":{\"bar\":{\"baz\":{\"message\":\"Hello, default propagation!\"}}},\"tuple_field\":[2300,\"Pennsylvania\",\"Avenue\",\"NW\"],\"foo_with_null_default\":{\"bar\":{\"baz\":null}}}";
+    let value1 = SchemaWithPropagatedDefaults::from_str("{}").unwrap();
+    assert_values_eq!(&value1.to_str().unwrap(), json1);
+    assert_eq!(
+        value1,
+        SchemaWithPropagatedDefaults {
+            foo: Some(SchemaWithPropagatedDefaultsFoo {
+                bar: Some(SchemaWithPropagatedDefaultsFooBar {
+                    baz: Some(SchemaWithPropagatedDefaultsFooBarBaz {
+                        message: Some("Hello, default propagation!".to_owned())
+                    })
+                })
+            }),
+            tuple_field: Some((
+                2300,
+                "Pennsylvania".to_owned(),
+                SchemaWithPropagatedDefaultsTupleField2::Avenue,
+                SchemaWithPropagatedDefaultsTupleField3::Nw
+            )),
+            foo_with_null_default: Some(SchemaWithPropagatedDefaultsFooWithNullDefault {
+                bar: Some(SchemaWithPropagatedDefaultsFooWithNullDefaultBar { baz: None })
+            })
+        }
+    );
+}
+
+/// Test structs with optional default fields.
+#[test]
+fn test_default_optional() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithOptionalDefaults",
+            "description": "A product from Acme's catalog",
+            "type": "object",
+            "$defs": {
+                "myDef": {
+                    "type": "integer",
+                    "default": 9
+                }
+            },
+            "properties": {
+                "null_prop": {
+                    "type": "null",
+                    "default": null
+                },
+                "boolean_prop": {
+                    "type": "boolean",
+                    "default": true
+                },
+                "integer_prop": {
+                    "type": "integer",
+                    "default": 7
+                },
+                "number_prop": {
+                    "type": "number",
+                    "default": 3.45
+                },
+                "string_prop": {
+                    "type": "string",
+                    "default": "Hello, world!"
+                },
+                "array_prop": {
+                    "type": "array",
+                    "items": {
+                        "type": "integer",
+                        "default": 0
+                    },
+                    "default": [1, 2, 3]
+                },
+                "object_prop": {
+                    "type": "object",
+                    "properties": {
+                        "message": {
+                            "type": "string",
+                            "default": "Hello, string!"
+                        }
+                    },
+                    "default": {
+                        "message": "Hello, object!"
+                    }
+                },
+                "enum_prop": {
+                    "enum": ["a", "b", "c"],
+                    "default": "c"
+                },
+                "tuple_prop": {
+                    "type": "array",
+                    "prefixItems": [
+                        {
+                            "type": "integer",
+                            "default": 1970
+                        },
+                        {
+                            "type": "string",
+                            "default": "January"
+                        },
+                        {
+                            "type": "integer",
+                            "default": 1
+                        }
+                    ],
+                    "default": [2020, "June", 27]
+                },
+                "ref_prop": {
+                    "$ref": "#/definitions/myDef"
+                },
+                "optional_prop_without_default": {
+                    "type": "integer"
+                }
+            }
+        }
+    );
+
+    let product_json = "{\"null_prop\":null,\"boolean_prop\":true,\"integer_prop\":7,\"number_prop\":3.45,\"string_prop\":\"Hello, world!\",\"array_prop\":[1,2,3],\"object_prop\":{\"message\":\"Hello, object!\"},\"enum_prop\":\"c\",\"tuple_prop\":[2020,\"June\",27],\"ref_prop\":9,\"optional_prop_without_default\":null}";
+    let product = SchemaWithOptionalDefaults::from_str("{}").unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.boolean_prop, Some(true));
+    assert_eq!(product.integer_prop, Some(7));
+    assert_eq!(product.number_prop, Some(3.45));
+    assert_eq!(product.string_prop, Some("Hello, world!".to_owned()));
+    assert_eq!(product.array_prop, Some(vec![1, 2, 3]));
+    assert_eq!(
+        product.object_prop,
+        Some(SchemaWithOptionalDefaultsObjectProp {
+            message: Some("Hello, object!".to_owned())
+        })
+    );
+    assert_eq!(
+        product.enum_prop,
+        Some(SchemaWithOptionalDefaultsEnumProp::C)
+    );
+    assert_eq!(product.tuple_prop, Some((2020, "June".to_owned(), 27)));
+    assert_eq!(product.ref_prop, Some(9));
+    assert_eq!(product.optional_prop_without_default, None);
+}
+
+/// Test treating fields as required unless they allow `null`.
+#[test]
+fn test_required_by_default() {
+    schema_struct!(
+        ident = SchemaWithOptionalByDefault,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "description": "A schema with no required fields",
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "label": {
+                                "type": "string"
+                            }
+                        }
+                    }
+                },
+                "nothing": {
+                    "type": "null"
+                }
+            }
+        }
+    );
+
+    schema_struct!(
+        ident = SchemaWithRequiredByDefault,
+        required_by_default = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "description": "A schema with no required fields",
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "label": {
+                                "type": "string"
+                            }
+                        }
+                    }
+                },
+                "nothing": {
+                    "type": "null"
+                }
+            }
+        }
+    );
+
+    let json = "{\"name\":\"product\",\"tags\":[{\"label\":\"new\"}],\"nothing\":null}";
+
+    let value_optional = SchemaWithOptionalByDefault::from_str(json).unwrap();
+    assert_values_eq!(&value_optional.to_str().unwrap(), json);
+    assert_eq!(value_optional.name, Some("product".to_owned()));
+    assert_eq!(
+        value_optional.tags,
+        Some(vec![SchemaWithOptionalByDefaultItemsTags {
+            label: Some("new".to_owned())
+        }])
+    );
+    assert_eq!(value_optional.nothing, None);
+    assert!(SchemaWithOptionalByDefault::from_str("{}").is_ok());
+
+    let value_required = SchemaWithRequiredByDefault::from_str(json).unwrap();
+    assert_values_eq!(&value_required.to_str().unwrap(), json);
+    assert_eq!(value_required.name, "product".to_owned());
+    assert_eq!(
+        value_required.tags,
+        vec![SchemaWithRequiredByDefaultItemsTags {
+            label: "new".to_owned()
+        }]
+    );
+    assert_eq!(value_required.nothing, None);
+    assert!(SchemaWithRequiredByDefault::from_str("{}").is_err());
+    assert!(SchemaWithRequiredByDefault::from_str("{\"name\":\"product\",\"tags\":[]}").is_ok());
+}
+
+/// Test ordering enum variants by their declaration order.
+#[test]
+fn test_ordered_enum() {
+    schema_struct!(
+        ordered_enum = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithOrderedEnum",
+            "description": "A schema with an ordered enum field",
+            "type": "object",
+            "properties": {
+                "level": {
+                    "enum": ["low", "medium", "high"]
+                }
+            },
+            "required": ["level"]
+        }
+    );
+
+    assert!(SchemaWithOrderedEnumLevel::Low < SchemaWithOrderedEnumLevel::Medium);
+    assert!(SchemaWithOrderedEnumLevel::Medium < SchemaWithOrderedEnumLevel::High);
+
+    let mut levels = ["high", "low", "medium"]
+        .iter()
+        .map(|level| {
+            SchemaWithOrderedEnum::from_str(&format!("{{\"level\":\"{}\"}}", level))
+                .unwrap()
+                .level
+        })
+        .collect::<Vec<_>>();
+    levels.sort();
+    assert_eq!(
+        levels,
+        vec![
+            SchemaWithOrderedEnumLevel::Low,
+            SchemaWithOrderedEnumLevel::Medium,
+            SchemaWithOrderedEnumLevel::High
+        ]
+    );
+}
+
+/// Test deriving additional traits on generated types.
+#[test]
+fn test_derives() {
+    schema_struct!(
+        derives = [Hash, Eq, Clone],
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDerives",
+            "description": "A schema deriving additional traits",
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string"
+                },
+                "level": {
+                    "enum": ["low", "high"]
+                },
+                "kind": {
+                    "const": "widget"
+                },
+                "dimensions": {
+                    "type": "object",
+                    "properties": {
+                        "width": {
+                            "type": "integer"
+                        }
+                    },
+                    "required": ["width"]
+                },
+                "id": {
+                    "oneOf": [
+                        { "type": "integer" },
+                        { "type": "string" }
+                    ]
+                }
+            },
+            "required": ["name", "level", "kind", "dimensions", "id"]
+        }
+    );
+
+    let json = "{\"name\":\"a\",\"level\":\"low\",\"kind\":\"widget\",\"dimensions\":{\"width\":1},\"id\":1}";
+    let value = SchemaWithDerives::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+
+    let mut values = HashSet::new();
+    values.insert(value.clone());
+    values.insert(value);
+    assert_eq!(values.len(), 1);
+}
+
+/// Test deriving `Default` on generated types alongside types that already
+/// implement it.
+#[test]
+fn test_derives_default() {
+    schema_struct!(
+        derives = [Default],
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultDerive",
+            "type": "object",
+            "properties": {
+                "mode": {
+                    "enum": ["fast", "slow"],
+                    "default": "slow"
+                },
+                "owner": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" }
+                    }
+                }
+            },
+            "required": ["mode"]
+        }
+    );
+
+    let value = SchemaWithDefaultDerive::default();
+    assert_eq!(value.mode, SchemaWithDefaultDeriveMode::Slow);
+    assert_eq!(value.owner, None);
+
+    schema_struct!(
+        derives = [Default],
+        default_impl = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDefaultDeriveAndImpl",
+            "type": "object",
+            "properties": {
+                "count": {
+                    "type": "integer",
+                    "default": 3
+                }
+            }
+        }
+    );
+
+    let value = SchemaWithDefaultDeriveAndImpl::default();
+    assert_eq!(value.count, Some(3));
+}
+
+/// Test debug formatting number fields with a fixed precision.
+#[test]
+fn test_float_precision() {
+    schema_struct!(
+        float_precision = 3,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithFloatPrecision",
+            "$defs": {
+                "rate": {
+                    "type": "number"
+                }
+            },
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string"
+                },
+                "price": {
+                    "type": "number"
+                },
+                "discount": {
+                    "type": "number"
+                },
+                "readings": {
+                    "type": "array",
+                    "items": {
+                        "type": "number"
+                    }
+                },
+                "weights": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "number"
+                    }
+                },
+                "rate": {
+                    "$ref": "#/$defs/rate"
+                },
+                "dimensions": {
+                    "type": "object",
+                    "properties": {
+                        "width": {
+                            "type": "integer"
+                        }
+                    },
+                    "required": ["width"]
+                }
+            },
+            "required": ["name", "price", "dimensions"]
+        }
+    );
+
+    let value = SchemaWithFloatPrecision {
+        name: "widget".to_owned(),
+        price: 0.1 + 0.2,
+        discount: Some(2.5),
+        readings: Some(vec![1.0 / 3.0, 2.0]),
+        weights: Some(HashMap::from([("a".to_owned(), 0.125)])),
+        rate: Some(1.0 / 3.0),
+        dimensions: SchemaWithFloatPrecisionDimensions { width: 3 },
+    };
+    assert_eq!(
+        format!("{:?}", value),
+        "SchemaWithFloatPrecision { name: \"widget\", price: 0.300, discount: Some(2.500), readings: Some([0.333, 2.000]), weights: Some({\"a\": 0.125}), rate: Some(0.333), dimensions: SchemaWithFloatPrecisionDimensions { width: 3 } }"
+    );
+
+    let value = SchemaWithFloatPrecision {
+        discount: None,
+        ..value
+    };
+    assert!(format!("{:?}", value).contains("discount: None"));
+}
+
+/// Simulates a crate that re-exports `schema_struct`.
+mod reexport {
+    pub use ::schema_struct;
+}
+
+/// Test overriding the path to the `schema_struct` crate.
+#[test]
+fn test_internal_path() {
+    schema_struct!(
+        internal_path = crate::reexport::schema_struct,
+        ident = ProductWithInternalPath,
+        file = "tests/schemas/product-file.json"
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = ProductWithInternalPath::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+}
+
+/// Test converting between generated types with compatible structures.
+#[test]
+fn test_compat() {
+    schema_struct!(
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "ProductV2",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer"
+                },
+                "name": {
+                    "type": "string"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    }
+                }
+            },
+            "required": ["id", "name", "tags"]
+        }
+    );
+
+    schema_struct!(
+        compat = [ProductV2],
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "ProductV1",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer"
+                },
+                "name": {
+                    "type": "string"
+                },
+                "price": {
+                    "type": "number"
+                }
+            },
+            "required": ["id", "name"]
+        }
+    );
+
+    schema_struct!(
+        compat = [ProductV2],
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "ProductWithSku",
+            "type": "object",
+            "properties": {
+                "sku": {
+                    "type": "string"
+                }
+            },
+            "required": ["sku"]
+        }
+    );
+
+    let v2 = ProductV2 {
+        id: 5,
+        name: "product name".to_owned(),
+        tags: vec!["new".to_owned()],
+    };
+
+    let v1 = ProductV1::try_from(v2.clone()).unwrap();
+    assert_eq!(
+        v1,
+        ProductV1 {
+            id: 5,
+            name: "product name".to_owned(),
+            price: None,
+        }
+    );
+
+    assert!(ProductWithSku::try_from(v2).is_err());
+}
+
+/// Test struct visibility configuration.
+#[test]
+fn test_vis() {
+    mod vis_test {
+        use super::schema_struct;
+
+        schema_struct!(
+            vis = pub,
+            ident = PublicProduct,
+            file = "tests/schemas/product-file.json"
+        );
+
+        schema_struct!(
+            vis = ,
+            ident = PrivateProduct,
+            file = "tests/schemas/product-file.json"
+        );
+    }
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = vis_test::PublicProduct::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.id, 5);
+    assert_eq!(product.name, "product name".to_owned());
+    assert_eq!(product.price, 12.34);
+}
+
+/// Test writing the generated code to a debug file.
+#[test]
+fn test_debug_file() {
+    schema_struct!(
+        ident = ProductWithDebugFile,
+        debug_file = "tests/out/debug_file_product.rs",
+        file = "tests/schemas/product-file.json"
     );
-    assert_eq!(product.tuple_prop, Some((2020, "June".to_owned(), 27)));
-    assert_eq!(product.ref_prop, Some(Box::new(9)));
-    assert_eq!(product.optional_prop_without_default, None);
+
+    let debug_file = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/out/debug_file_product.rs"
+    );
+    let output = std::fs::read_to_string(debug_file).unwrap();
+    assert_eq!(output, include_str!("snapshots/debug_file_product.rs"));
 }
 
-/// Test struct visibility configuration.
+/// Test resolving refs to locations in other schema files.
 #[test]
-fn test_vis() {
-    mod vis_test {
-        use super::schema_struct;
+fn test_file_refs() {
+    schema_struct!(validate = true, file = "tests/schemas/refs/customer.json");
+
+    let json = "{\"name\":\"Jane\",\"home\":{\"street\":\"Main St\",\"country\":\"US\"}}";
+    let customer = CustomerWithFileRefs::from_str(json).unwrap();
+    assert_eq!(
+        customer.home,
+        CustomerWithFileRefsDefAddress {
+            street: "Main St".to_owned(),
+            country: CustomerWithFileRefsDefCountry::Us,
+        }
+    );
+    assert_eq!(customer.work, None);
+
+    let invalid_json = "{\"name\":\"Jane\",\"home\":{\"street\":\"Main St\",\"country\":\"XX\"}}";
+    assert!(CustomerWithFileRefs::from_str(invalid_json).is_err());
+}
+
+/// Test generating a type per schema file in a directory.
+#[test]
+fn test_dir() {
+    schema_struct!(validate = true, dir = "tests/schemas/dir");
+
+    let json = "{\"quantity\":2,\"customer\":{\"id\":1,\"name\":\"Jane\"},\"referrer\":null}";
+    let order = OrderLine::from_str(json).unwrap();
+    assert_values_eq!(&order.to_str().unwrap(), json);
+    assert_eq!(order.quantity, 2);
+    assert_eq!(
+        order.customer,
+        DirCustomer {
+            id: 1,
+            name: "Jane".to_owned()
+        }
+    );
+    assert_eq!(order.referrer, None);
+
+    let customer = DirCustomer::from_str("{\"id\":3,\"name\":\"Joe\"}").unwrap();
+    assert_eq!(customer.id, 3);
+    assert_eq!(DirCustomer::TITLE, "DirCustomer");
+    assert_eq!(
+        OrderLine::DESCRIPTION,
+        Some("An order placed by a customer")
+    );
+}
+
+/// Test writing the generated code to an output file relative to the
+/// manifest directory.
+#[test]
+fn test_output() {
+    schema_struct!(
+        ident = ProductWithOutput,
+        output = "tests/out/output_product.rs",
+        file = "tests/schemas/product-file.json"
+    );
+
+    let output_file = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/out/output_product.rs");
+    let output = std::fs::read_to_string(output_file).unwrap();
+    assert!(output.contains("struct ProductWithOutput {"));
+    assert!(output.contains("impl ProductWithOutput {"));
+}
+
+/// Test appending a table of the top-level fields to the doc comment.
+#[test]
+fn test_doc_table() {
+    schema_struct!(
+        doc_table = true,
+        debug_file = "tests/out/debug_file_doc_table.rs",
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithDocTable",
+            "description": "A schema documented with a table of fields",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "description": "The unique identifier",
+                    "type": "integer"
+                },
+                "tags": {
+                    "description": "Tags, separated by `|`",
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    }
+                },
+                "owner": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string"
+                        }
+                    }
+                }
+            },
+            "required": ["id"]
+        }
+    );
+
+    let debug_file = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/out/debug_file_doc_table.rs"
+    );
+    let output = std::fs::read_to_string(debug_file).unwrap();
+    assert!(output.contains("\n# Fields\n"));
+    assert!(output.contains("| Field | Type | Required | Description |"));
+    assert!(output.contains("| `id` | `i64` | yes | The unique identifier |"));
+    assert!(output.contains("| `tags` | `Option<Vec<String>>` | no | Tags, separated by `\\|` |"));
+    assert!(output.contains("| `owner` | `Option<SchemaWithDocTableOwner>` | no |  |"));
+}
+
+/// Test constructing a struct with a custom identifier.
+#[test]
+fn test_custom_ident() {
+    schema_struct!(
+        ident = CustomIdentifier,
+        file = "tests/schemas/product-file.json"
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = CustomIdentifier::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.id, 5);
+    assert_eq!(product.name, "product name".to_owned());
+    assert_eq!(product.price, 12.34);
+}
+
+/// Test schema validation on deserialization.
+#[test]
+fn test_validation() {
+    schema_struct!(
+        validate = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "Product",
+            "description": "A product from Acme's catalog",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "description": "The unique identifier for a product",
+                    "type": "integer"
+                },
+                "name": {
+                    "description": "Name of the product",
+                    "type": "string"
+                },
+                "price": {
+                    "type": "number",
+                    "minimum": 0,
+                    "exclusiveMinimum": true
+                }
+            },
+            "required": ["id", "name", "price"]
+        }
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    let product = Product::from_str(product_json).unwrap();
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+
+    assert_eq!(product.id, 5);
+    assert_eq!(product.name, "product name".to_owned());
+    assert_eq!(product.price, 12.34);
+
+    let product_json_invalid = "{\"id\":5,\"name\":\"product name\",\"price\":-12.34}";
+    let product_invalid = Product::from_str(product_json_invalid);
+    assert!(product_invalid.is_err());
+
+    assert!(Product::is_valid(product_json));
+    assert!(!Product::is_valid(product_json_invalid));
+    assert!(!Product::is_valid("{\"id\":5"));
+}
+
+/// Test validating a payload and filling in its defaults in one call.
+#[test]
+fn test_validation_with_defaults() {
+    schema_struct!(
+        validate = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "ValidatedOrder",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "minimum": 1
+                },
+                "quantity": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "default": 1
+                },
+                "currency": {
+                    "type": "string",
+                    "default": "USD"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "default": ["new"]
+                }
+            },
+            "required": ["id"]
+        }
+    );
+
+    let order = ValidatedOrder::from_str("{\"id\":5}").unwrap();
+    assert_eq!(order.id, 5);
+    assert_eq!(order.quantity, Some(1));
+    assert_eq!(order.currency.as_deref(), Some("USD"));
+    assert_eq!(order.tags, Some(vec!["new".to_owned()]));
+    assert_values_eq!(
+        &order.to_str().unwrap(),
+        "{\"id\":5,\"quantity\":1,\"currency\":\"USD\",\"tags\":[\"new\"]}"
+    );
+
+    assert!(ValidatedOrder::from_str("{\"id\":0}").is_err());
+    assert!(ValidatedOrder::from_str("{\"id\":5,\"quantity\":0}").is_err());
+}
+
+/// Test validating internationalized string formats.
+#[test]
+fn test_validation_idn_formats() {
+    schema_struct!(
+        validate = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "InternationalContact",
+            "type": "object",
+            "properties": {
+                "email": {
+                    "type": "string",
+                    "format": "idn-email"
+                },
+                "homepage": {
+                    "type": "string",
+                    "format": "iri"
+                },
+                "host": {
+                    "type": "string",
+                    "format": "idn-hostname"
+                }
+            },
+            "required": ["email"]
+        }
+    );
+
+    let contact = InternationalContact::from_str(
+        "{\"email\":\"用户@例子.广告\",\"homepage\":\"https://例子.广告/路径?q=ü\",\"host\":\"bücher.example\"}",
+    )
+    .unwrap();
+    let email: String = contact.email;
+    assert_eq!(email, "用户@例子.广告");
+    assert_eq!(
+        contact.homepage.as_deref(),
+        Some("https://例子.广告/路径?q=ü")
+    );
+    assert_eq!(contact.host.as_deref(), Some("bücher.example"));
+
+    assert!(InternationalContact::is_valid(
+        "{\"email\":\"josé.garcía@correo.es\"}"
+    ));
+    assert!(InternationalContact::is_valid(
+        "{\"email\":\"उपयोगकर्ता@उदाहरण.परीक्षा\",\"host\":\"हिन्दी.परीक्षा\"}"
+    ));
+    assert!(!InternationalContact::is_valid(
+        "{\"email\":\"not an email\"}"
+    ));
+    assert!(!InternationalContact::is_valid(
+        "{\"email\":\"user@@example.com\"}"
+    ));
+    assert!(!InternationalContact::is_valid(
+        "{\"email\":\"user@-example.com\"}"
+    ));
+    assert!(!InternationalContact::is_valid(
+        "{\"email\":\"user@example.com\",\"homepage\":\"no scheme here\"}"
+    ));
+    assert!(!InternationalContact::is_valid(
+        "{\"email\":\"user@example.com\",\"host\":\"bücher..example\"}"
+    ));
+}
+
+/// Test sharing compiled schemas between types validating against the same
+/// schema.
+#[test]
+fn test_validation_shared_schema() {
+    schema_struct!(
+        ident = ProductValidatedFirst,
+        validate = true,
+        file = "tests/schemas/product-file.json"
+    );
+
+    schema_struct!(
+        ident = ProductValidatedSecond,
+        validate = true,
+        file = "tests/schemas/product-file.json"
+    );
+
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    assert!(ProductValidatedFirst::from_str(product_json).is_ok());
+    assert!(ProductValidatedSecond::from_str(product_json).is_ok());
+
+    let product_json_invalid = "{\"id\":5,\"name\":\"product name\",\"price\":-12.34}";
+    assert!(ProductValidatedFirst::from_str(product_json_invalid).is_err());
+    assert!(ProductValidatedSecond::from_str(product_json_invalid).is_err());
+
+    let schema = "{\"type\":\"object\",\"required\":[\"id\"]}";
+    let first = schema_struct::__internal::compiled_schema(schema).unwrap();
+    let second = schema_struct::__internal::compiled_schema(schema).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+}
+
+/// Test validating values against the schema when serializing.
+#[test]
+fn test_validate_on_serialize() {
+    schema_struct!(
+        ident = ProductValidatedOnSerialize,
+        validate_on_serialize = true,
+        file = "tests/schemas/product-file.json"
+    );
+
+    let product = ProductValidatedOnSerialize {
+        id: 5,
+        name: "product name".to_owned(),
+        price: 12.34,
+    };
+    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
+    assert_values_eq!(&product.to_str().unwrap(), product_json);
+    assert!(product.to_value().is_ok());
+
+    let product_invalid = ProductValidatedOnSerialize {
+        price: -12.34,
+        ..product
+    };
+    assert_eq!(
+        product_invalid.to_string(),
+        format!("{:?}", product_invalid)
+    );
+    assert!(matches!(
+        product_invalid.to_str(),
+        Err(schema_struct::__internal::JsonSchemaError::SchemaValidationError(_))
+    ));
+    assert!(matches!(
+        product_invalid.to_value(),
+        Err(schema_struct::__internal::JsonSchemaError::SchemaValidationError(_))
+    ));
+}
+
+/// Test generating types that implement only one of the serde traits.
+#[test]
+fn test_serde_traits() {
+    schema_struct!(
+        serde = "serialize",
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SerializeOnly",
+            "description": "A read model that is only serialized",
+            "type": "object",
+            "properties": {
+                "status": {
+                    "enum": ["active", "inactive"]
+                },
+                "count": {
+                    "type": "integer"
+                }
+            },
+            "required": ["status", "count"]
+        }
+    );
+
+    schema_struct!(
+        serde = "deserialize",
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "DeserializeOnly",
+            "description": "A write model that is only deserialized",
+            "type": "object",
+            "properties": {
+                "count": {
+                    "type": "integer"
+                }
+            },
+            "required": ["count"]
+        }
+    );
+
+    let value = SerializeOnly {
+        status: SerializeOnlyStatus::Active,
+        count: 3,
+    };
+    assert_values_eq!(
+        &value.to_str().unwrap(),
+        "{\"status\":\"active\",\"count\":3}"
+    );
+    assert_eq!(value.to_value().unwrap()["count"], 3);
+
+    let value = DeserializeOnly::from_str("{\"count\":4}").unwrap();
+    assert_eq!(value.count, 4);
+}
+
+/// Test generated `Deserialize` implementations that name the offending
+/// field on failure.
+#[test]
+fn test_custom_serde() {
+    schema_struct!(
+        custom_serde = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithCustomSerde",
+            "description": "A schema with hand-written deserialization",
+            "type": "object",
+            "properties": {
+                "productId": {
+                    "type": "integer"
+                },
+                "price": {
+                    "type": "number",
+                    "x-coerce": true
+                },
+                "quantity": {
+                    "type": "integer",
+                    "default": 1
+                },
+                "notes": {
+                    "type": "string"
+                },
+                "dimensions": {
+                    "type": "object",
+                    "properties": {
+                        "width": {
+                            "type": "number"
+                        }
+                    },
+                    "required": ["width"]
+                }
+            },
+            "required": ["productId", "price"]
+        }
+    );
 
-        schema_struct!(
-            vis = pub,
-            ident = PublicProduct,
-            file = "schema-struct/tests/schemas/product-file.json"
-        );
+    let json = "{\"productId\":5,\"price\":\"12.5\",\"dimensions\":{\"width\":2.0}}";
+    let value = SchemaWithCustomSerde::from_str(json).unwrap();
+    assert_eq!(value.product_id, 5);
+    assert_eq!(value.price, 12.5);
+    assert_eq!(value.quantity, Some(1));
+    assert_eq!(value.notes, None);
+    assert_eq!(value.dimensions.unwrap().width, 2.0);
 
-        schema_struct!(
-            vis = ,
-            ident = PrivateProduct,
-            file = "schema-struct/tests/schemas/product-file.json"
-        );
-    }
+    let json =
+        "{\"productId\":5,\"price\":12.5,\"quantity\":2,\"notes\":\"n\",\"dimensions\":null}";
+    let value = SchemaWithCustomSerde::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
 
-    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
-    let product = vis_test::PublicProduct::from_str(product_json).unwrap();
-    assert_values_eq!(&product.to_str().unwrap(), product_json);
+    let err = format!(
+        "{:?}",
+        SchemaWithCustomSerde::from_str("{\"productId\":\"five\",\"price\":1}").unwrap_err()
+    );
+    assert!(err.contains("field `productId`"), "{}", err);
+    assert!(err.contains("`integer`"), "{}", err);
 
-    assert_eq!(product.id, 5);
-    assert_eq!(product.name, "product name".to_owned());
-    assert_eq!(product.price, 12.34);
+    let err = format!(
+        "{:?}",
+        SchemaWithCustomSerde::from_str(
+            "{\"productId\":5,\"price\":1,\"dimensions\":{\"width\":true}}",
+        )
+        .unwrap_err()
+    );
+    assert!(err.contains("field `dimensions`"), "{}", err);
+    assert!(err.contains("field `width`"), "{}", err);
+
+    let err = format!(
+        "{:?}",
+        SchemaWithCustomSerde::from_str("{\"price\":1}").unwrap_err()
+    );
+    assert!(err.contains("missing field `productId`"), "{}", err);
 }
 
-/// Test constructing a struct with a custom identifier.
+/// Test renaming the generated JSON string methods.
 #[test]
-fn test_custom_ident() {
+fn test_method_names() {
     schema_struct!(
-        ident = CustomIdentifier,
-        file = "schema-struct/tests/schemas/product-file.json"
+        parse_method = from_json,
+        emit_method = to_json,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithMethodNames",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "dimensions": {
+                    "type": "object",
+                    "properties": {
+                        "width": { "type": "number" }
+                    },
+                    "required": ["width"]
+                }
+            },
+            "required": ["name", "dimensions"]
+        }
     );
 
-    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
-    let product = CustomIdentifier::from_str(product_json).unwrap();
-    assert_values_eq!(&product.to_str().unwrap(), product_json);
+    let json = "{\"name\":\"box\",\"dimensions\":{\"width\":2.5}}";
+    let value = SchemaWithMethodNames::from_json(json).unwrap();
+    assert_values_eq!(&value.to_json().unwrap(), json);
+    assert_eq!(value.name, "box");
 
-    assert_eq!(product.id, 5);
-    assert_eq!(product.name, "product name".to_owned());
-    assert_eq!(product.price, 12.34);
+    let value = SchemaWithMethodNames::from_string(json.to_owned()).unwrap();
+    assert_eq!(value.dimensions.width, 2.5);
+
+    let dimensions = SchemaWithMethodNamesDimensions::from_json("{\"width\":1.0}").unwrap();
+    assert_values_eq!(&dimensions.to_json().unwrap(), "{\"width\":1.0}");
 }
 
-/// Test schema validation on deserialization.
+/// Test using raw identifiers for fields named after Rust keywords.
 #[test]
-fn test_validation() {
+fn test_raw_idents() {
     schema_struct!(
-        validate = true,
+        raw_idents = true,
         schema = {
             "$schema": "http://json-schema.org/draft-04/schema#",
-            "title": "Product",
-            "description": "A product from Acme's catalog",
+            "title": "SchemaWithRawIdents",
+            "description": "A schema with fields named after Rust keywords",
             "type": "object",
             "properties": {
-                "id": {
-                    "description": "The unique identifier for a product",
-                    "type": "integer"
-                },
-                "name": {
-                    "description": "Name of the product",
+                "type": {
                     "type": "string"
                 },
-                "price": {
-                    "type": "number",
-                    "minimum": 0,
-                    "exclusiveMinimum": true
+                "match": {
+                    "type": "integer",
+                    "default": 3
+                },
+                "self": {
+                    "type": "boolean"
                 }
             },
-            "required": ["id", "name", "price"]
+            "required": ["type", "self"]
         }
     );
 
-    let product_json = "{\"id\":5,\"name\":\"product name\",\"price\":12.34}";
-    let product = Product::from_str(product_json).unwrap();
-    assert_values_eq!(&product.to_str().unwrap(), product_json);
-
-    assert_eq!(product.id, 5);
-    assert_eq!(product.name, "product name".to_owned());
-    assert_eq!(product.price, 12.34);
+    let value = SchemaWithRawIdents {
+        r#type: "widget".to_owned(),
+        r#match: Some(1),
+        self_: true,
+    };
+    let json = "{\"type\":\"widget\",\"match\":1,\"self\":true}";
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(SchemaWithRawIdents::from_str(json).unwrap(), value);
 
-    let product_json_invalid = "{\"id\":5,\"name\":\"product name\",\"price\":-12.34}";
-    let product_invalid = Product::from_str(product_json_invalid);
-    assert!(product_invalid.is_err());
+    let value = SchemaWithRawIdents::from_str("{\"type\":\"gadget\",\"self\":false}").unwrap();
+    assert_eq!(value.r#type, "gadget");
+    assert_eq!(value.r#match, Some(3));
 }
 
 /// Test renaming structs and fields.
@@ -1502,6 +5255,15 @@ fn test_renaming() {
         value_with_renamed_fields.invalid_characters_here0123456789,
         None
     );
+    assert_eq!(
+        SchemaWithRenamedFields::wire_name("strip_starting_number_456"),
+        Some("123strip_starting_number456")
+    );
+    assert_eq!(
+        SchemaWithRenamedFields::wire_name("schema"),
+        Some("$schema")
+    );
+    assert_eq!(SchemaWithRenamedFields::wire_name("missing"), None);
 
     schema_struct!(
         schema = {
@@ -1517,6 +5279,185 @@ fn test_renaming() {
     assert_values_eq!(&value_with_bad_title.to_str().unwrap(), json_with_bad_title);
 }
 
+/// Test applying the predominant property naming style with `rename_all`.
+#[test]
+fn test_auto_rename() {
+    schema_struct!(
+        auto_rename = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithCamelCaseKeys",
+            "description": "A schema with mostly camelCase property names",
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer"
+                },
+                "firstName": {
+                    "type": "string"
+                },
+                "lastName": {
+                    "type": "string"
+                },
+                "homeAddress": {
+                    "type": "object",
+                    "properties": {
+                        "streetName": {
+                            "type": "string"
+                        }
+                    },
+                    "required": ["streetName"]
+                },
+                "legacy_code": {
+                    "type": "string"
+                }
+            },
+            "required": ["id", "firstName", "lastName", "homeAddress", "legacy_code"]
+        }
+    );
+
+    let json = "{\"id\":1,\"firstName\":\"Jane\",\"lastName\":\"Doe\",\"homeAddress\":{\"streetName\":\"Main\"},\"legacy_code\":\"x\"}";
+    let value = SchemaWithCamelCaseKeys::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.id, 1);
+    assert_eq!(value.first_name, "Jane");
+    assert_eq!(value.last_name, "Doe");
+    assert_eq!(value.home_address.street_name, "Main");
+    assert_eq!(value.legacy_code, "x");
+}
+
+/// Test automatic renaming of the fields of `oneOf` variant payloads.
+#[test]
+fn test_auto_rename_one_of() {
+    schema_struct!(
+        auto_rename = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithCamelCaseVariants",
+            "description": "A schema with camelCase property names in `oneOf` variants",
+            "type": "object",
+            "properties": {
+                "contactMethod": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "kind": { "const": "email" },
+                                "emailAddress": { "type": "string" }
+                            },
+                            "required": ["kind", "emailAddress"]
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "kind": { "const": "phone" },
+                                "phoneNumber": { "type": "string" },
+                                "countryCode": { "type": "string" }
+                            },
+                            "required": ["kind", "phoneNumber", "countryCode"]
+                        }
+                    ]
+                }
+            },
+            "required": ["contactMethod"]
+        }
+    );
+
+    let json =
+        "{\"contactMethod\":{\"kind\":\"phone\",\"phoneNumber\":\"555\",\"countryCode\":\"US\"}}";
+    let value = SchemaWithCamelCaseVariants::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.contact_method,
+        SchemaWithCamelCaseVariantsContactMethod::Phone(
+            SchemaWithCamelCaseVariantsContactMethodPhone {
+                phone_number: "555".to_owned(),
+                country_code: "US".to_owned()
+            }
+        )
+    );
+
+    let json = "{\"contactMethod\":{\"kind\":\"email\",\"emailAddress\":\"a@b.c\"}}";
+    let value = SchemaWithCamelCaseVariants::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.contact_method,
+        SchemaWithCamelCaseVariantsContactMethod::Email(
+            SchemaWithCamelCaseVariantsContactMethodEmail {
+                email_address: "a@b.c".to_owned()
+            }
+        )
+    );
+}
+
+/// Test applying an explicit `rename_all` rule to generated structs.
+#[test]
+fn test_rename_all() {
+    schema_struct!(
+        rename_all = "PascalCase",
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithPascalCaseKeys",
+            "description": "A schema with PascalCase property names",
+            "type": "object",
+            "properties": {
+                "Id": {
+                    "type": "integer"
+                },
+                "FirstName": {
+                    "type": "string"
+                },
+                "HomeAddress": {
+                    "type": "object",
+                    "properties": {
+                        "StreetName": {
+                            "type": "string"
+                        }
+                    },
+                    "required": ["StreetName"]
+                },
+                "legacy_code": {
+                    "type": "string"
+                }
+            },
+            "required": ["Id", "FirstName", "HomeAddress", "legacy_code"]
+        }
+    );
+
+    let json = "{\"Id\":1,\"FirstName\":\"Jane\",\"HomeAddress\":{\"StreetName\":\"Main\"},\"legacy_code\":\"x\"}";
+    let value = SchemaWithPascalCaseKeys::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.id, 1);
+    assert_eq!(value.first_name, "Jane");
+    assert_eq!(value.home_address.street_name, "Main");
+    assert_eq!(value.legacy_code, "x");
+
+    schema_struct!(
+        rename_all = "SCREAMING_SNAKE_CASE",
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithScreamingKeys",
+            "description": "A schema with SCREAMING_SNAKE_CASE property names",
+            "type": "object",
+            "properties": {
+                "MAX_RETRIES": {
+                    "type": "integer"
+                },
+                "API-KEY": {
+                    "type": "string"
+                }
+            },
+            "required": ["MAX_RETRIES", "API-KEY"]
+        }
+    );
+
+    let json = "{\"MAX_RETRIES\":3,\"API-KEY\":\"secret\"}";
+    let value = SchemaWithScreamingKeys::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(value.max_retries, 3);
+    assert_eq!(value.api_key, "secret");
+}
+
 /// Test serializing and deserializing generated structs.
 #[test]
 fn test_serializing() {
@@ -1589,3 +5530,107 @@ fn test_serializing() {
     assert_eq!(value4.to_value().unwrap(), json4);
     assert_eq!(value4.message, "Hello, nested object 4!");
 }
+
+/// Types generated along with round-trip property tests. The generated test
+/// module is compiled and run as part of this test suite.
+#[cfg(feature = "round-trip-tests")]
+mod round_trip {
+    use schema_struct::schema_struct;
+
+    schema_struct!(
+        vis = pub,
+        generate_tests = true,
+        schema = {
+            "$schema": "http://json-schema.org/draft-04/schema#",
+            "title": "SchemaWithRoundTripTests",
+            "description": "A schema with generated round-trip tests",
+            "$defs": {
+                "myInteger": {
+                    "type": "integer"
+                }
+            },
+            "type": "object",
+            "properties": {
+                "null_field": {
+                    "type": "null"
+                },
+                "boolean_field": {
+                    "type": "boolean"
+                },
+                "integer_field": {
+                    "type": "integer"
+                },
+                "number_field": {
+                    "type": "number"
+                },
+                "string_field": {
+                    "type": "string"
+                },
+                "code_field": {
+                    "type": "string",
+                    "minLength": 3,
+                    "maxLength": 5
+                },
+                "label_field": {
+                    "type": "string",
+                    "minLength": 2
+                },
+                "array_field": {
+                    "type": "array",
+                    "items": {
+                        "type": "number"
+                    }
+                },
+                "object_field": {
+                    "type": "object",
+                    "properties": {
+                        "inner_field": {
+                            "type": "string"
+                        }
+                    },
+                    "required": ["inner_field"]
+                },
+                "enum_field": {
+                    "enum": ["first", "second", "third"]
+                },
+                "tuple_field": {
+                    "type": "array",
+                    "prefixItems": [
+                        {
+                            "type": "integer"
+                        },
+                        {
+                            "type": "string"
+                        }
+                    ]
+                },
+                "ref_field": {
+                    "$ref": "#/$defs/myInteger"
+                }
+            },
+            "required": ["integer_field", "number_field", "code_field"]
+        }
+    );
+}
+
+/// Test generating round-trip property tests.
+#[cfg(feature = "round-trip-tests")]
+#[test]
+fn test_generate_tests() {
+    use round_trip::*;
+
+    let json = "{\"null_field\":null,\"boolean_field\":true,\"integer_field\":5,\"number_field\":1.5,\"string_field\":null,\"code_field\":\"abc\",\"label_field\":\"label\",\"array_field\":[0.1,0.2],\"object_field\":{\"inner_field\":\"inner\"},\"enum_field\":\"second\",\"tuple_field\":[1,\"one\"],\"ref_field\":7}";
+    let value = SchemaWithRoundTripTests::from_str(json).unwrap();
+    assert_values_eq!(&value.to_str().unwrap(), json);
+    assert_eq!(
+        value.object_field,
+        Some(SchemaWithRoundTripTestsObjectField {
+            inner_field: "inner".to_owned()
+        })
+    );
+    assert_eq!(
+        value.enum_field,
+        Some(SchemaWithRoundTripTestsEnumField::Second)
+    );
+    assert!(SchemaWithRoundTripTests::from_str(&json.replace("abc", "ab")).is_err());
+}
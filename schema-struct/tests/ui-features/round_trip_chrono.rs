@@ -0,0 +1,17 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    generate_tests = true,
+    schema = {
+        "title": "RoundTripChrono",
+        "type": "object",
+        "properties": {
+            "created": {
+                "type": "string",
+                "format": "date-time"
+            }
+        }
+    }
+);
+
+fn main() {}
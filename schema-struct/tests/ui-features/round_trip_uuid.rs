@@ -0,0 +1,20 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    generate_tests = true,
+    schema = {
+        "title": "RoundTripUuid",
+        "type": "object",
+        "properties": {
+            "ids": {
+                "type": "array",
+                "items": {
+                    "type": "string",
+                    "format": "uuid"
+                }
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,17 @@
+#![forbid(unsafe_code)]
+
+/// Test macro invocations and usages that are expected to fail to compile.
+#[test]
+fn test_compile_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
+
+/// Test macro invocations that are expected to fail to compile when
+/// round-trip tests are combined with the `chrono` and `uuid` types.
+#[cfg(all(feature = "round-trip-tests", feature = "chrono", feature = "uuid"))]
+#[test]
+fn test_round_trip_compile_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui-features/round_trip_*.rs");
+}
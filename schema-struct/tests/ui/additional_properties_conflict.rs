@@ -0,0 +1,18 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "AdditionalPropertiesConflict",
+        "type": "object",
+        "properties": {
+            "additionalProperties": {
+                "type": "boolean"
+            }
+        },
+        "additionalProperties": {
+            "type": "string"
+        }
+    }
+);
+
+fn main() {}
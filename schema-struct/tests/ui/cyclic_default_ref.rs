@@ -0,0 +1,20 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "CyclicDefaultRef",
+        "type": "object",
+        "$defs": {
+            "first": { "$ref": "#/$defs/second" },
+            "second": { "$ref": "#/$defs/first" }
+        },
+        "properties": {
+            "count": {
+                "type": "integer",
+                "default": { "$ref": "#/$defs/first" }
+            }
+        }
+    }
+);
+
+fn main() {}
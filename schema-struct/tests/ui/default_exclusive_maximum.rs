@@ -0,0 +1,18 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "ExclusiveMaximum",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer",
+                "exclusiveMaximum": 10,
+                "default": 10
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,19 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "$schema": "http://json-schema.org/draft-04/schema#",
+        "title": "ExclusiveMinimum",
+        "type": "object",
+        "properties": {
+            "price": {
+                "type": "number",
+                "minimum": 0,
+                "exclusiveMinimum": true,
+                "default": 0
+            }
+        }
+    }
+);
+
+fn main() {}
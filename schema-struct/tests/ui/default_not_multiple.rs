@@ -0,0 +1,17 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "NotMultiple",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer",
+                "multipleOf": 5,
+                "default": 7
+            }
+        }
+    }
+);
+
+fn main() {}
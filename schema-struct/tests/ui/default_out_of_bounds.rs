@@ -0,0 +1,17 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "OutOfBounds",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer",
+                "maximum": 5,
+                "default": 10
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,17 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "OutOfBoundsLarge",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer",
+                "maximum": 9007199254740992,
+                "default": 9007199254740993
+            }
+        }
+    }
+);
+
+fn main() {}
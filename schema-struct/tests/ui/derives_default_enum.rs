@@ -0,0 +1,16 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    derives = [Default],
+    schema = {
+        "title": "DerivesDefaultEnum",
+        "type": "object",
+        "properties": {
+            "level": {
+                "enum": ["low", "high"]
+            }
+        }
+    }
+);
+
+fn main() {}
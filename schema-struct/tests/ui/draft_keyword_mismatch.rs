@@ -0,0 +1,21 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    draft = "draft-07",
+    schema = {
+        "title": "DraftKeywordMismatch",
+        "type": "object",
+        "properties": {
+            "point": {
+                "type": "array",
+                "prefixItems": [
+                    { "type": "number" },
+                    { "type": "number" }
+                ],
+                "items": { "type": "number" }
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,25 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "DuplicateSubschema",
+        "$defs": {
+            "quantity": {
+                "type": "integer"
+            }
+        },
+        "definitions": {
+            "quantity": {
+                "type": "number"
+            }
+        },
+        "type": "object",
+        "properties": {
+            "quantity": {
+                "$ref": "#/$defs/quantity"
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,16 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    repr = u8,
+    schema = {
+        "title": "EnumReprOutOfRange",
+        "type": "object",
+        "properties": {
+            "level": {
+                "enum": [1, 256]
+            }
+        }
+    }
+);
+
+fn main() {}
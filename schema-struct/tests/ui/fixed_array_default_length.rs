@@ -0,0 +1,19 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "FixedArrayDefaultLength",
+        "type": "object",
+        "properties": {
+            "position": {
+                "type": "array",
+                "items": { "type": "number" },
+                "minItems": 3,
+                "maxItems": 3,
+                "default": [0.0, 0.0]
+            }
+        }
+    }
+);
+
+fn main() {}
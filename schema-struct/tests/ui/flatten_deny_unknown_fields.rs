@@ -0,0 +1,25 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "FlattenDenyUnknownFields",
+        "type": "object",
+        "properties": {
+            "name": {
+                "type": "string"
+            },
+            "address": {
+                "type": "object",
+                "x-flatten": true,
+                "properties": {
+                    "city": {
+                        "type": "string"
+                    }
+                }
+            }
+        },
+        "additionalProperties": false
+    }
+);
+
+fn main() {}
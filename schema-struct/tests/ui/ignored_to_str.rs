@@ -0,0 +1,23 @@
+#![deny(unused_must_use)]
+
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "$schema": "http://json-schema.org/draft-04/schema#",
+        "title": "Product",
+        "type": "object",
+        "properties": {
+            "id": {
+                "type": "integer"
+            }
+        },
+        "required": ["id"]
+    }
+);
+
+fn main() {
+    let product = Product { id: 5 };
+    product.to_str();
+    product.to_value();
+}
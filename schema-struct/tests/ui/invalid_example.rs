@@ -0,0 +1,21 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    validate = true,
+    schema = {
+        "title": "InvalidExample",
+        "type": "object",
+        "properties": {
+            "id": {
+                "type": "integer"
+            }
+        },
+        "required": ["id"],
+        "examples": [
+            { "id": 1 },
+            { "id": "two" }
+        ]
+    }
+);
+
+fn main() {}
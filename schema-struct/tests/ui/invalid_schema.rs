@@ -0,0 +1,17 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "InvalidSchema",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer",
+                "minimum": "zero"
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,5 @@
+use schema_struct::schema_struct;
+
+schema_struct!(ident = Invalid, bytes = b"{\"type\":\"object\",\"title\":\"\xff\"}");
+
+fn main() {}
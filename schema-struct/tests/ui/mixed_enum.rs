@@ -0,0 +1,15 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "MixedEnum",
+        "type": "object",
+        "properties": {
+            "level": {
+                "enum": [1, "two", 3]
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,17 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "NegativeDefaultUnsigned",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer",
+                "minimum": 0,
+                "default": -1
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,16 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    output = "Cargo.toml/generated.rs",
+    schema = {
+        "title": "OutputWriteFailure",
+        "type": "object",
+        "properties": {
+            "id": {
+                "type": "integer"
+            }
+        }
+    }
+);
+
+fn main() {}
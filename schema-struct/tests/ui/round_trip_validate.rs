@@ -0,0 +1,18 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    generate_tests = true,
+    validate = true,
+    schema = {
+        "title": "RoundTripValidate",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer",
+                "maximum": 10
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,18 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    serde = "serialize",
+    schema = {
+        "title": "SerializeOnly",
+        "type": "object",
+        "properties": {
+            "count": {
+                "type": "integer"
+            }
+        }
+    }
+);
+
+fn main() {
+    let _ = SerializeOnly::from_str("{}");
+}
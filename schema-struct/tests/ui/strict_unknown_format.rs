@@ -0,0 +1,17 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    strict = true,
+    schema = {
+        "title": "StrictUnknownFormat",
+        "type": "object",
+        "properties": {
+            "contact": {
+                "type": "string",
+                "format": "emial"
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,15 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "TypeArrayMultipleTypes",
+        "type": "object",
+        "properties": {
+            "value": {
+                "type": ["string", "integer", "null"]
+            }
+        }
+    }
+);
+
+fn main() {}
@@ -0,0 +1,19 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "UnresolvedPointerRef",
+        "type": "object",
+        "properties": {
+            "address": {
+                "type": "object",
+                "properties": {
+                    "street": { "type": "string" }
+                }
+            },
+            "city": { "$ref": "#/properties/address/properties/city" }
+        }
+    }
+);
+
+fn main() {}
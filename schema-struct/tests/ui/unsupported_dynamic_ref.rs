@@ -0,0 +1,15 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    schema = {
+        "title": "UnsupportedDynamicRef",
+        "type": "object",
+        "properties": {
+            "node": {
+                "$dynamicRef": "#node"
+            }
+        }
+    }
+);
+
+fn main() {}
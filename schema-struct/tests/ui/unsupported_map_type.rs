@@ -0,0 +1,14 @@
+use schema_struct::schema_struct;
+
+schema_struct!(
+    map_type = "IndexMap",
+    schema = {
+        "title": "UnsupportedMapType",
+        "type": "object",
+        "additionalProperties": {
+            "type": "integer"
+        }
+    }
+);
+
+fn main() {}